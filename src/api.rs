@@ -0,0 +1,265 @@
+//! Stable library API for tools embedding hegel-pm
+//!
+//! `ProjectRegistry` covers discovery, the project cache, refresh, and metrics behind one
+//! type, so callers never touch cache files or storage backends directly. Everything
+//! re-exported here follows semver: breaking changes only in major releases. The rest of
+//! `hegel_pm::discovery` and `hegel_pm::cli` serve the CLI and may change in minor releases.
+//!
+//! ```no_run
+//! use hegel_pm::api::ProjectRegistry;
+//!
+//! let registry = ProjectRegistry::from_default_config()?;
+//! for project in registry.projects()? {
+//!     println!("{} ({:?})", project.name, project.status());
+//! }
+//! if let Some(summary) = registry.metrics_summary("hegel-pm")? {
+//!     println!("{} tokens", summary.total_all_tokens);
+//! }
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use anyhow::Result;
+use std::sync::Arc;
+
+use crate::discovery::{
+    budget_statuses, current_month, refresh_all_projects_with_progress, refresh_project,
+    remove_from_cache, set_archived, update_cached_project, DiscoveryEngine,
+};
+
+pub use crate::discovery::{
+    Budget, BudgetStatus, DiscoveredProject, DiscoveryConfig, GitInfo, ModeMetrics, MonthTokens,
+    ProgressEvent, ProgressSink, ProjectListItem, ProjectMetricsSummary, ProjectStatistics,
+    ProjectStatus, WorkflowState,
+};
+
+/// Discovered Hegel projects, cached between calls
+///
+/// Projects are looked up by name or stable ID (`DiscoveredProject::id`). Methods that change
+/// the cache return `Ok(false)` / `Ok(None)` when the project isn't known.
+#[derive(Clone)]
+pub struct ProjectRegistry {
+    engine: DiscoveryEngine,
+}
+
+impl ProjectRegistry {
+    /// Create a registry for `config` (validated up front)
+    pub fn new(config: DiscoveryConfig) -> Result<Self> {
+        Ok(Self {
+            engine: DiscoveryEngine::new(config)?,
+        })
+    }
+
+    /// Create a registry from `~/.config/hegel-pm/config.json` (defaults if missing)
+    pub fn from_default_config() -> Result<Self> {
+        Self::new(DiscoveryConfig::load_or_default()?)
+    }
+
+    /// Report scan and refresh progress to `progress`
+    pub fn with_progress(self, progress: Arc<dyn ProgressSink>) -> Self {
+        Self {
+            engine: self.engine.with_progress(progress),
+        }
+    }
+
+    /// Configuration in use
+    pub fn config(&self) -> &DiscoveryConfig {
+        self.engine.config()
+    }
+
+    /// All projects, from the cache if populated (scanning otherwise); statistics not loaded
+    pub fn projects(&self) -> Result<Vec<DiscoveredProject>> {
+        self.engine.get_projects(false)
+    }
+
+    /// Rescan the configured roots and replace the cache
+    pub fn rescan(&self) -> Result<Vec<DiscoveredProject>> {
+        self.engine.get_projects(true)
+    }
+
+    /// One project by name or ID (statistics not loaded)
+    pub fn project(&self, key: &str) -> Result<Option<DiscoveredProject>> {
+        Ok(self.projects()?.into_iter().find(|p| p.matches_key(key)))
+    }
+
+    /// One project by name or ID, with statistics loaded from its `.hegel` logs
+    pub fn project_with_statistics(&self, key: &str) -> Result<Option<DiscoveredProject>> {
+        let Some(mut project) = self.project(key)? else {
+            return Ok(None);
+        };
+        project.load_statistics()?;
+        Ok(Some(project))
+    }
+
+    /// Metrics summary (tokens, events, phases, per-mode split) for one project
+    pub fn metrics_summary(&self, key: &str) -> Result<Option<ProjectMetricsSummary>> {
+        Ok(self
+            .project_with_statistics(key)?
+            .and_then(|project| project.statistics.as_ref().map(ProjectMetricsSummary::from)))
+    }
+
+    /// Metrics summed over all non-archived projects, with `per_mode` merged by mode
+    ///
+    /// Uses each project's cached summary (from its last refresh) and loads statistics only for
+    /// projects without one, so it is complete from the first call; servers can compute it in a
+    /// background job at startup. Projects whose logs fail to parse or exceed
+    /// `statistics_budget_mb` count as empty.
+    pub fn aggregate_summary(&self) -> Result<ProjectMetricsSummary> {
+        let mut total = ProjectMetricsSummary::default();
+        for mut project in self.projects()? {
+            if project.archived {
+                continue;
+            }
+            if project.metrics_summary.is_none() {
+                project.update_summary(self.config().statistics_budget_bytes());
+            }
+            if let Some(summary) = &project.metrics_summary {
+                total.merge(summary);
+            }
+        }
+        Ok(total)
+    }
+
+    /// This month's usage against the configured budgets (global first, then per project)
+    ///
+    /// Like `aggregate_summary`, reads cached summaries and loads statistics only where a
+    /// project has none. Empty when no budgets are configured.
+    pub fn budget_statuses(&self) -> Result<Vec<BudgetStatus>> {
+        let config = self.config();
+        if config.budget.is_none() && config.project_budgets.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut projects = self.projects()?;
+        for project in projects.iter_mut().filter(|p| !p.archived) {
+            if project.metrics_summary.is_none() {
+                project.update_summary(config.statistics_budget_bytes());
+            }
+        }
+        Ok(budget_statuses(
+            &projects,
+            config,
+            &current_month(chrono::Utc::now()),
+        ))
+    }
+
+    /// Re-read one project from disk into the cache
+    ///
+    /// `Ok(false)` if unknown, or skipped by the refresh debounce/lock (see `DiscoveryConfig`).
+    pub fn refresh(&self, key: &str) -> Result<bool> {
+        match self.name_of(key)? {
+            Some(name) => refresh_project(&name, self.config()),
+            None => Ok(false),
+        }
+    }
+
+    /// Re-read every cached project, returning how many were refreshed
+    pub fn refresh_all(&self) -> Result<usize> {
+        // Populate the cache first so a fresh registry can refresh
+        self.projects()?;
+        refresh_all_projects_with_progress(self.config(), self.engine.progress())
+    }
+
+    /// Archive (hide from listings) or unarchive a project
+    pub fn set_archived(&self, key: &str, archived: bool) -> Result<bool> {
+        match self.name_of(key)? {
+            Some(name) => set_archived(&name, archived, self.config()),
+            None => Ok(false),
+        }
+    }
+
+    /// Set one metadata entry (owner, ticket link, ...) on a project
+    pub fn set_metadata(&self, key: &str, field: &str, value: &str) -> Result<bool> {
+        self.update(key, |project| {
+            project
+                .metadata
+                .insert(field.to_string(), value.to_string());
+        })
+    }
+
+    /// Remove one metadata entry from a project
+    pub fn remove_metadata(&self, key: &str, field: &str) -> Result<bool> {
+        self.update(key, |project| {
+            project.metadata.remove(field);
+        })
+    }
+
+    /// Stop tracking a project (removed from the cache until the next rescan finds it)
+    pub fn remove(&self, key: &str) -> Result<bool> {
+        match self.name_of(key)? {
+            Some(name) => remove_from_cache(&name, self.config()),
+            None => Ok(false),
+        }
+    }
+
+    fn update(&self, key: &str, update: impl FnOnce(&mut DiscoveredProject)) -> Result<bool> {
+        match self.name_of(key)? {
+            Some(name) => update_cached_project(&name, self.config(), update),
+            None => Ok(false),
+        }
+    }
+
+    /// Resolve a name or ID to the cached name (cache functions are keyed by name)
+    fn name_of(&self, key: &str) -> Result<Option<String>> {
+        Ok(self.project(key)?.map(|project| project.name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn registry(temp: &TempDir) -> ProjectRegistry {
+        let hegel_dir = temp.path().join("code").join("alpha").join(".hegel");
+        fs::create_dir_all(&hegel_dir).unwrap();
+        fs::write(
+            hegel_dir.join("state.json"),
+            r#"{"workflow": {"current_node": "code", "mode": "discovery", "history": ["spec", "code"]}}"#,
+        )
+        .unwrap();
+
+        ProjectRegistry::new(DiscoveryConfig::new(
+            vec![temp.path().join("code")],
+            10,
+            vec![],
+            temp.path().join("config").join("cache.json"),
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_registry_lookup_by_name_and_id() {
+        let temp = TempDir::new().unwrap();
+        let registry = registry(&temp);
+
+        let projects = registry.projects().unwrap();
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].status(), ProjectStatus::Active);
+
+        let id = projects[0].id();
+        assert_eq!(registry.project(&id).unwrap().unwrap().name, "alpha");
+        assert!(registry.project("missing").unwrap().is_none());
+        assert!(registry.metrics_summary("missing").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_registry_updates_cache() {
+        let temp = TempDir::new().unwrap();
+        let registry = registry(&temp);
+
+        assert!(registry.set_metadata("alpha", "owner", "alice").unwrap());
+        assert!(registry.set_archived("alpha", true).unwrap());
+        let alpha = registry.project("alpha").unwrap().unwrap();
+        assert_eq!(alpha.metadata["owner"], "alice");
+        assert!(alpha.archived);
+
+        assert!(registry.remove_metadata("alpha", "owner").unwrap());
+        assert!(registry.refresh("alpha").unwrap());
+        let alpha = registry.project("alpha").unwrap().unwrap();
+        assert!(alpha.metadata.is_empty());
+        assert!(alpha.archived);
+
+        assert!(!registry.set_archived("missing", true).unwrap());
+        assert!(registry.remove("alpha").unwrap());
+    }
+}
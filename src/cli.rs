@@ -1,7 +1,27 @@
+pub mod alerts;
+pub mod diff;
+pub mod digest;
 pub mod discover;
+pub mod error;
 pub mod hegel;
+pub mod note;
+pub mod open;
+pub mod pick;
+pub mod refresh;
+pub mod report;
+pub mod resolve;
+pub mod snapshot;
+pub mod tail;
 
-use clap::{Parser, Subcommand};
+pub use error::HegelPmError;
+
+use crate::debug::{LogFormat, LogLevel};
+use crate::discovery::ProjectStatus;
+use clap::{Parser, Subcommand, ValueEnum};
+use digest::DigestPeriod;
+use discover::GroupBy;
+use report::ReportFormat;
+use std::path::PathBuf;
 
 /// Hegel Project Manager - CLI for discovering and managing Hegel projects
 #[derive(Parser, Debug)]
@@ -10,6 +30,35 @@ use clap::{Parser, Subcommand};
 pub struct Args {
     #[command(subcommand)]
     pub command: Option<Command>,
+
+    /// Emit progress events on stderr during scans, statistics loading, and refresh
+    #[arg(long, value_enum, global = true, default_value = "none")]
+    pub progress: ProgressMode,
+
+    /// Require exact project names or IDs (no prefix/fuzzy matching or "did you mean" prompts)
+    #[arg(long, global = true)]
+    pub exact: bool,
+
+    /// Diagnostics to show: error, warn, info, or debug (default warn; debug if $DEBUG is set)
+    #[arg(long, global = true)]
+    pub log_level: Option<LogLevel>,
+
+    /// Diagnostic line format: pretty or json
+    #[arg(long, global = true, default_value = "pretty")]
+    pub log_format: LogFormat,
+
+    /// Append diagnostics to this file instead of stderr
+    #[arg(long, global = true)]
+    pub log_file: Option<PathBuf>,
+}
+
+/// How progress is reported for long operations
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ProgressMode {
+    /// No progress output
+    None,
+    /// Newline-delimited JSON events on stderr (for editor/IDE integrations)
+    Json,
 }
 
 #[derive(Subcommand, Debug)]
@@ -26,28 +75,203 @@ pub enum Command {
         /// Force fresh filesystem scan, bypass cache
         #[arg(long, global = true)]
         no_cache: bool,
+
+        /// Include archived projects in list and all
+        #[arg(long, global = true)]
+        include_archived: bool,
+
+        /// Only list projects with this workflow status (active, completed, error, inactive)
+        #[arg(long, global = true)]
+        status: Option<ProjectStatus>,
+
+        /// Scan this directory instead of the configured roots (repeatable; one-off scans are
+        /// cached separately from the regular project list)
+        #[arg(long = "root", global = true)]
+        roots: Vec<PathBuf>,
+
+        /// Maximum directory depth to scan (overrides the config file)
+        #[arg(long, global = true)]
+        max_depth: Option<usize>,
     },
 
     /// Remove a project from tracking (clears from cache)
     Remove {
-        /// Name of the project to remove
+        /// Name of the project to remove (omit to pick one interactively)
+        project_name: Option<String>,
+    },
+
+    /// Hide a project from discover list/all and x, keeping its cached data
+    Archive {
+        /// Name of the project to archive
+        project_name: String,
+    },
+
+    /// Restore an archived project
+    Unarchive {
+        /// Name of the project to unarchive
         project_name: String,
     },
 
+    /// Attach freeform metadata (owner, ticket link, status, notes...) to a project
+    Note {
+        #[command(subcommand)]
+        subcommand: NoteCommand,
+    },
+
     /// Refresh cached data for project(s)
     Refresh {
         /// Names of projects to refresh (omit to refresh all cached projects)
         project_names: Vec<String>,
+
+        /// Pick the project to refresh interactively
+        #[arg(long, conflicts_with = "project_names")]
+        pick: bool,
+
+        /// Refresh even if refreshed within `refresh_debounce_secs`
+        #[arg(long)]
+        force: bool,
+
+        /// Show what would change (last activity, workflow node, phases) without writing the cache
+        #[arg(long, conflicts_with = "diff")]
+        dry_run: bool,
+
+        /// Refresh and print a before/after summary per project
+        #[arg(long)]
+        diff: bool,
+    },
+
+    /// Export a project report (workflow state, phases, metrics) as Markdown or HTML
+    Report {
+        /// Name of the project to report on
+        project_name: String,
+
+        /// Document format
+        #[arg(long, value_enum, default_value = "md")]
+        format: ReportFormat,
+
+        /// Write to this file instead of stdout
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+
+    /// Show token/event/phase changes for a project since a recorded snapshot
+    Diff {
+        /// Name of the project
+        project_name: String,
+
+        /// Compare against the latest snapshot at or before this time (RFC 3339, snapshot ID
+        /// like 20250101T120000Z, or period like 24h); defaults to the latest snapshot
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Output as JSON instead of human-readable format
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Record a metrics snapshot across all projects (for historical trends)
+    Snapshot {
+        /// List recorded snapshots instead of recording a new one
+        #[arg(long)]
+        list: bool,
+
+        /// Output as JSON instead of human-readable format
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Summarize the latest snapshot against the previous period (top projects, new projects,
+    /// completed workflows, estimated cost) as Markdown or HTML
+    Digest {
+        /// How far back to compare
+        #[arg(long, value_enum, default_value = "weekly")]
+        period: DigestPeriod,
+
+        /// Document format
+        #[arg(long, value_enum, default_value = "md")]
+        format: ReportFormat,
+
+        /// Write to this file instead of stdout
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+
+    /// Flag workflows stuck in a phase (`alert_stuck_hours`, default 24), with corrupted state, or
+    /// at 80%/100% of a monthly budget
+    Alerts {
+        /// Output as JSON instead of human-readable format
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Follow a project's hook events live (bash commands, file edits, transitions)
+    Tail {
+        /// Name of the project
+        project_name: String,
+
+        /// Only show matching events: field=value or field~text, fields kind (bash, edit,
+        /// transition, other), tool, command, path, node, mode (repeatable, all must match)
+        #[arg(long = "filter")]
+        filters: Vec<String>,
+
+        /// Number of recent events to print before following
+        #[arg(short = 'n', long, default_value = "10")]
+        lines: usize,
+    },
+
+    /// Open a project in $EDITOR (default), a shell in its directory, or the dashboard
+    Open {
+        /// Name of the project to open (omit to pick one interactively)
+        project_name: Option<String>,
+
+        /// Launch $VISUAL or $EDITOR on the project directory (default)
+        #[arg(long, group = "target")]
+        editor: bool,
+
+        /// Start $SHELL in the project directory
+        #[arg(long, group = "target")]
+        terminal: bool,
+
+        /// Open the project's dashboard page (`dashboard_url` in config)
+        #[arg(long, group = "target")]
+        browser: bool,
     },
 
     /// Run a hegel command across all discovered projects
     X {
+        /// Also run in archived projects (must come before the hegel command)
+        #[arg(long)]
+        include_archived: bool,
+
         /// Arguments to pass to hegel command
         #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
         args: Vec<String>,
     },
 }
 
+#[derive(Subcommand, Debug)]
+pub enum NoteCommand {
+    /// Set one or more metadata keys
+    Set {
+        /// Name of the project
+        project_name: String,
+
+        /// Metadata as key=value (e.g. owner=alice status="in review")
+        #[arg(required = true)]
+        entries: Vec<String>,
+    },
+
+    /// Remove metadata keys
+    Unset {
+        /// Name of the project
+        project_name: String,
+
+        /// Keys to remove
+        #[arg(required = true)]
+        keys: Vec<String>,
+    },
+}
+
 #[derive(Subcommand, Debug)]
 pub enum DiscoverCommand {
     /// List all discovered projects (lightweight, no metrics)
@@ -55,20 +279,44 @@ pub enum DiscoverCommand {
 
     /// Show detailed information for a specific project
     Show {
-        /// Name of the project to show
-        project_name: String,
+        /// Name of the project to show (omit to pick one interactively)
+        project_name: Option<String>,
     },
 
     /// Show aggregate metrics for all projects in a table
     All {
-        /// Column to sort by (name, path, size, last-activity, tokens, events, phases, load-time)
-        #[arg(long, default_value = "last-activity")]
-        sort_by: String,
+        /// Column to sort by (name, path, size, last-activity, tokens, events, phases, load-time);
+        /// remembered for next time, default last-activity
+        #[arg(long)]
+        sort_by: Option<String>,
+
+        /// Comma-separated columns to show (name, path, branch, size, last-activity, tokens,
+        /// events, phases, budget); remembered for next time, default all but budget
+        #[arg(long, value_delimiter = ',')]
+        columns: Option<Vec<String>>,
 
         /// Include load time column for performance profiling
         #[arg(long)]
         benchmark: bool,
+
+        /// Split into per-group tables with token/event/phase subtotals (tag = the `tag`
+        /// metadata key, root = configured root directory, mode = workflow mode)
+        #[arg(long, value_enum)]
+        group_by: Option<GroupBy>,
     },
+
+    /// Show per-phase statistics (tokens, duration) aggregated across all projects
+    Phases {
+        /// Compare against this project's per-phase averages
+        #[arg(long)]
+        project: Option<String>,
+    },
+
+    /// Diagnose config, cache integrity, and unreadable projects (with suggested fixes)
+    Doctor,
+
+    /// Show what the cache holds: indexed projects, file sizes, footprint, last save
+    Stats,
 }
 
 #[cfg(test)]
@@ -87,6 +335,33 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_discover_root_overrides() {
+        let args = Args::parse_from([
+            "hegel-pm",
+            "discover",
+            "--root",
+            "/mnt/usb",
+            "--root",
+            "/srv/checkout",
+            "list",
+            "--max-depth",
+            "4",
+        ]);
+        match args.command {
+            Some(Command::Discover {
+                roots, max_depth, ..
+            }) => {
+                assert_eq!(
+                    roots,
+                    vec![PathBuf::from("/mnt/usb"), PathBuf::from("/srv/checkout")]
+                );
+                assert_eq!(max_depth, Some(4));
+            }
+            _ => panic!("Expected Discover command"),
+        }
+    }
+
     #[test]
     fn test_show_subcommand() {
         let args = Args::parse_from(["hegel-pm", "discover", "show", "my-project"]);
@@ -95,10 +370,78 @@ mod tests {
                 subcommand: DiscoverCommand::Show { project_name },
                 ..
             }) => {
-                assert_eq!(project_name, "my-project");
+                assert_eq!(project_name.as_deref(), Some("my-project"));
             }
             _ => panic!("Expected Show subcommand"),
         }
+
+        let args = Args::parse_from(["hegel-pm", "discover", "show"]);
+        assert!(matches!(
+            args.command,
+            Some(Command::Discover {
+                subcommand: DiscoverCommand::Show { project_name: None },
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_log_flags() {
+        let args = Args::parse_from([
+            "hegel-pm",
+            "discover",
+            "list",
+            "--log-level",
+            "debug",
+            "--log-format",
+            "json",
+            "--log-file",
+            "/tmp/hegel-pm.log",
+        ]);
+        assert_eq!(args.log_level, Some(LogLevel::Debug));
+        assert_eq!(args.log_format, LogFormat::Json);
+        assert_eq!(args.log_file, Some(PathBuf::from("/tmp/hegel-pm.log")));
+
+        assert!(Args::try_parse_from(["hegel-pm", "--log-level", "loud", "alerts"]).is_err());
+    }
+
+    #[test]
+    fn test_exact_is_global() {
+        let args = Args::parse_from(["hegel-pm", "remove", "alpha", "--exact"]);
+        assert!(args.exact);
+        assert!(!Args::parse_from(["hegel-pm", "remove", "alpha"]).exact);
+    }
+
+    #[test]
+    fn test_open_targets_conflict() {
+        let args = Args::parse_from(["hegel-pm", "open", "alpha", "--terminal"]);
+        match args.command {
+            Some(Command::Open {
+                project_name,
+                editor,
+                terminal,
+                browser,
+            }) => {
+                assert_eq!(project_name.as_deref(), Some("alpha"));
+                assert!(terminal && !editor && !browser);
+            }
+            _ => panic!("Expected Open command"),
+        }
+
+        assert!(
+            Args::try_parse_from(["hegel-pm", "open", "alpha", "--editor", "--browser"]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_refresh_pick_conflicts_with_names() {
+        let args = Args::parse_from(["hegel-pm", "refresh", "--pick"]);
+        assert!(matches!(
+            args.command,
+            Some(Command::Refresh { pick: true, .. })
+        ));
+
+        assert!(Args::try_parse_from(["hegel-pm", "refresh", "--pick", "alpha"]).is_err());
     }
 
     #[test]
@@ -106,11 +449,19 @@ mod tests {
         let args = Args::parse_from(["hegel-pm", "discover", "all"]);
         match args.command {
             Some(Command::Discover {
-                subcommand: DiscoverCommand::All { sort_by, benchmark },
+                subcommand:
+                    DiscoverCommand::All {
+                        sort_by,
+                        columns,
+                        benchmark,
+                        group_by,
+                    },
                 ..
             }) => {
-                assert_eq!(sort_by, "last-activity");
+                assert!(sort_by.is_none());
+                assert!(columns.is_none());
                 assert!(!benchmark);
+                assert!(group_by.is_none());
             }
             _ => panic!("Expected All subcommand"),
         }
@@ -124,20 +475,206 @@ mod tests {
             "all",
             "--sort-by",
             "tokens",
+            "--columns",
+            "name,tokens",
             "--benchmark",
+            "--group-by",
+            "tag",
         ]);
         match args.command {
             Some(Command::Discover {
-                subcommand: DiscoverCommand::All { sort_by, benchmark },
+                subcommand:
+                    DiscoverCommand::All {
+                        sort_by,
+                        columns,
+                        benchmark,
+                        group_by,
+                    },
                 ..
             }) => {
-                assert_eq!(sort_by, "tokens");
+                assert_eq!(sort_by.as_deref(), Some("tokens"));
+                assert_eq!(
+                    columns,
+                    Some(vec!["name".to_string(), "tokens".to_string()])
+                );
                 assert!(benchmark);
+                assert_eq!(group_by, Some(GroupBy::Tag));
             }
             _ => panic!("Expected All subcommand"),
         }
     }
 
+    #[test]
+    fn test_phases_subcommand() {
+        let args = Args::parse_from(["hegel-pm", "discover", "phases", "--project", "my-project"]);
+        match args.command {
+            Some(Command::Discover {
+                subcommand: DiscoverCommand::Phases { project },
+                ..
+            }) => {
+                assert_eq!(project.as_deref(), Some("my-project"));
+            }
+            _ => panic!("Expected Phases subcommand"),
+        }
+    }
+
+    #[test]
+    fn test_doctor_subcommand() {
+        let args = Args::parse_from(["hegel-pm", "discover", "doctor", "--json"]);
+        assert!(matches!(
+            args.command,
+            Some(Command::Discover {
+                subcommand: DiscoverCommand::Doctor,
+                json: true,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_stats_subcommand() {
+        let args = Args::parse_from(["hegel-pm", "discover", "stats"]);
+        assert!(matches!(
+            args.command,
+            Some(Command::Discover {
+                subcommand: DiscoverCommand::Stats,
+                json: false,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_progress_flag() {
+        let args = Args::parse_from(["hegel-pm", "discover", "all"]);
+        assert_eq!(args.progress, ProgressMode::None);
+
+        let args = Args::parse_from(["hegel-pm", "refresh", "--progress", "json"]);
+        assert_eq!(args.progress, ProgressMode::Json);
+    }
+
+    #[test]
+    fn test_report_command() {
+        let args = Args::parse_from(["hegel-pm", "report", "my-project"]);
+        match args.command {
+            Some(Command::Report {
+                project_name,
+                format,
+                output,
+            }) => {
+                assert_eq!(project_name, "my-project");
+                assert_eq!(format, ReportFormat::Md);
+                assert!(output.is_none());
+            }
+            _ => panic!("Expected Report command"),
+        }
+
+        let args = Args::parse_from([
+            "hegel-pm",
+            "report",
+            "my-project",
+            "--format",
+            "html",
+            "-o",
+            "out.html",
+        ]);
+        assert!(matches!(
+            args.command,
+            Some(Command::Report {
+                format: ReportFormat::Html,
+                output: Some(_),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_digest_command() {
+        let args = Args::parse_from(["hegel-pm", "digest"]);
+        assert!(matches!(
+            args.command,
+            Some(Command::Digest {
+                period: DigestPeriod::Weekly,
+                format: ReportFormat::Md,
+                output: None,
+            })
+        ));
+
+        let args = Args::parse_from([
+            "hegel-pm", "digest", "--period", "monthly", "--format", "html",
+        ]);
+        assert!(matches!(
+            args.command,
+            Some(Command::Digest {
+                period: DigestPeriod::Monthly,
+                format: ReportFormat::Html,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_snapshot_command() {
+        let args = Args::parse_from(["hegel-pm", "snapshot", "--list"]);
+        assert!(matches!(
+            args.command,
+            Some(Command::Snapshot {
+                list: true,
+                json: false
+            })
+        ));
+    }
+
+    #[test]
+    fn test_diff_command() {
+        let args = Args::parse_from(["hegel-pm", "diff", "my-project", "--since", "24h"]);
+        match args.command {
+            Some(Command::Diff {
+                project_name,
+                since,
+                json,
+            }) => {
+                assert_eq!(project_name, "my-project");
+                assert_eq!(since.as_deref(), Some("24h"));
+                assert!(!json);
+            }
+            _ => panic!("Expected Diff command"),
+        }
+    }
+
+    #[test]
+    fn test_alerts_command() {
+        let args = Args::parse_from(["hegel-pm", "alerts", "--json"]);
+        assert!(matches!(args.command, Some(Command::Alerts { json: true })));
+    }
+
+    #[test]
+    fn test_tail_command() {
+        let args = Args::parse_from([
+            "hegel-pm",
+            "tail",
+            "my-project",
+            "--filter",
+            "kind=bash",
+            "--filter",
+            "command~cargo",
+            "-n",
+            "0",
+        ]);
+        match args.command {
+            Some(Command::Tail {
+                project_name,
+                filters,
+                lines,
+            }) => {
+                assert_eq!(project_name, "my-project");
+                assert_eq!(filters, vec!["kind=bash", "command~cargo"]);
+                assert_eq!(lines, 0);
+            }
+            _ => panic!("Expected Tail command"),
+        }
+    }
+
     #[test]
     fn test_global_json_flag() {
         let args = Args::parse_from(["hegel-pm", "discover", "--json", "list"]);
@@ -164,18 +701,90 @@ mod tests {
     fn test_hegel_command() {
         let args = Args::parse_from(["hegel-pm", "x", "status"]);
         match args.command {
-            Some(Command::X { args }) => {
+            Some(Command::X { args, .. }) => {
                 assert_eq!(args, vec!["status"]);
             }
             _ => panic!("Expected X command"),
         }
     }
 
+    #[test]
+    fn test_hegel_command_include_archived() {
+        let args = Args::parse_from(["hegel-pm", "x", "--include-archived", "status", "--json"]);
+        match args.command {
+            Some(Command::X {
+                include_archived,
+                args,
+            }) => {
+                assert!(include_archived);
+                assert_eq!(args, vec!["status", "--json"]);
+            }
+            _ => panic!("Expected X command"),
+        }
+    }
+
+    #[test]
+    fn test_archive_commands() {
+        let args = Args::parse_from(["hegel-pm", "archive", "old-project"]);
+        assert!(matches!(
+            args.command,
+            Some(Command::Archive { project_name }) if project_name == "old-project"
+        ));
+
+        let args = Args::parse_from(["hegel-pm", "unarchive", "old-project"]);
+        assert!(matches!(
+            args.command,
+            Some(Command::Unarchive { project_name }) if project_name == "old-project"
+        ));
+    }
+
+    #[test]
+    fn test_discover_status_filter() {
+        let args = Args::parse_from(["hegel-pm", "discover", "list", "--status", "active"]);
+        match args.command {
+            Some(Command::Discover { status, .. }) => {
+                assert_eq!(status, Some(ProjectStatus::Active));
+            }
+            _ => panic!("Expected Discover command"),
+        }
+
+        assert!(
+            Args::try_parse_from(["hegel-pm", "discover", "all", "--status", "paused"]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_note_set_command() {
+        let args = Args::parse_from([
+            "hegel-pm",
+            "note",
+            "set",
+            "my-project",
+            "owner=alice",
+            "status=in review",
+        ]);
+        match args.command {
+            Some(Command::Note {
+                subcommand:
+                    NoteCommand::Set {
+                        project_name,
+                        entries,
+                    },
+            }) => {
+                assert_eq!(project_name, "my-project");
+                assert_eq!(entries, vec!["owner=alice", "status=in review"]);
+            }
+            _ => panic!("Expected Note Set command"),
+        }
+
+        assert!(Args::try_parse_from(["hegel-pm", "note", "set", "my-project"]).is_err());
+    }
+
     #[test]
     fn test_hegel_command_with_multiple_args() {
         let args = Args::parse_from(["hegel-pm", "x", "analyze", "--fix-archives", "--dry-run"]);
         match args.command {
-            Some(Command::X { args }) => {
+            Some(Command::X { args, .. }) => {
                 assert_eq!(args, vec!["analyze", "--fix-archives", "--dry-run"]);
             }
             _ => panic!("Expected X command"),
@@ -186,7 +795,7 @@ mod tests {
     fn test_hegel_command_with_flags() {
         let args = Args::parse_from(["hegel-pm", "x", "analyze", "--fix-archives", "--json"]);
         match args.command {
-            Some(Command::X { args }) => {
+            Some(Command::X { args, .. }) => {
                 assert_eq!(args, vec!["analyze", "--fix-archives", "--json"]);
             }
             _ => panic!("Expected X command"),
@@ -198,7 +807,7 @@ mod tests {
         let args = Args::parse_from(["hegel-pm", "remove", "my-project"]);
         match args.command {
             Some(Command::Remove { project_name }) => {
-                assert_eq!(project_name, "my-project");
+                assert_eq!(project_name.as_deref(), Some("my-project"));
             }
             _ => panic!("Expected Remove command"),
         }
@@ -208,7 +817,7 @@ mod tests {
     fn test_refresh_command_single() {
         let args = Args::parse_from(["hegel-pm", "refresh", "my-project"]);
         match args.command {
-            Some(Command::Refresh { project_names }) => {
+            Some(Command::Refresh { project_names, .. }) => {
                 assert_eq!(project_names, vec!["my-project"]);
             }
             _ => panic!("Expected Refresh command"),
@@ -219,7 +828,7 @@ mod tests {
     fn test_refresh_command_multiple() {
         let args = Args::parse_from(["hegel-pm", "refresh", "project1", "project2", "project3"]);
         match args.command {
-            Some(Command::Refresh { project_names }) => {
+            Some(Command::Refresh { project_names, .. }) => {
                 assert_eq!(project_names, vec!["project1", "project2", "project3"]);
             }
             _ => panic!("Expected Refresh command"),
@@ -230,10 +839,41 @@ mod tests {
     fn test_refresh_command_no_args() {
         let args = Args::parse_from(["hegel-pm", "refresh"]);
         match args.command {
-            Some(Command::Refresh { project_names }) => {
+            Some(Command::Refresh { project_names, .. }) => {
                 assert!(project_names.is_empty());
             }
             _ => panic!("Expected Refresh command"),
         }
     }
+
+    #[test]
+    fn test_refresh_command_force() {
+        let args = Args::parse_from(["hegel-pm", "refresh", "--force", "my-project"]);
+        match args.command {
+            Some(Command::Refresh {
+                project_names,
+                force,
+                ..
+            }) => {
+                assert_eq!(project_names, vec!["my-project"]);
+                assert!(force);
+            }
+            _ => panic!("Expected Refresh command"),
+        }
+    }
+
+    #[test]
+    fn test_refresh_command_dry_run() {
+        let args = Args::parse_from(["hegel-pm", "refresh", "--dry-run"]);
+        assert!(matches!(
+            args.command,
+            Some(Command::Refresh {
+                dry_run: true,
+                diff: false,
+                ..
+            })
+        ));
+
+        assert!(Args::try_parse_from(["hegel-pm", "refresh", "--dry-run", "--diff"]).is_err());
+    }
 }
@@ -0,0 +1,147 @@
+use crate::cli::discover::format::OutputContext;
+use crate::cli::HegelPmError;
+use crate::discovery::{
+    budget_alerts, budget_statuses, current_month, find_alerts, Alert, AlertKind, DiscoveryEngine,
+    ProjectMetricsSummary,
+};
+use chrono::Utc;
+use std::io::{self, Write};
+
+/// Run the alerts command: stuck phases, corrupted state, and budgets across projects
+pub fn run(engine: &DiscoveryEngine, json: bool) -> Result<(), HegelPmError> {
+    let mut projects = engine.get_projects(false)?;
+    let budget = engine.config().statistics_budget_bytes();
+    for project in projects.iter_mut().filter(|p| !p.archived) {
+        let _ = project.load_statistics_within(budget); // Ignore errors, only state is checked
+                                                        // Fresh statistics beat the summary cached at the last refresh for budget usage
+        if let Some(stats) = &project.statistics {
+            project.metrics_summary = Some(ProjectMetricsSummary::from(stats));
+        }
+    }
+
+    let now = Utc::now();
+    let mut alerts = find_alerts(&projects, engine.config().stuck_phase_threshold(), now);
+    alerts.extend(budget_alerts(&budget_statuses(
+        &projects,
+        engine.config(),
+        &current_month(now),
+    )));
+
+    let mut out = io::stdout().lock();
+    if json {
+        writeln!(out, "{}", serde_json::to_string_pretty(&alerts)?)?;
+    } else {
+        output_human(&alerts, &OutputContext::system(), &mut out)?;
+    }
+
+    Ok(())
+}
+
+fn output_human(
+    alerts: &[Alert],
+    ctx: &OutputContext,
+    out: &mut dyn Write,
+) -> Result<(), HegelPmError> {
+    if alerts.is_empty() {
+        writeln!(out, "✓ No alerts")?;
+        return Ok(());
+    }
+
+    for alert in alerts {
+        match &alert.kind {
+            AlertKind::StuckPhase {
+                phase,
+                started,
+                age_hours,
+            } => writeln!(
+                out,
+                "⚠ {}: phase '{}' in progress for {}h (since {})",
+                alert.project_name,
+                phase,
+                age_hours,
+                ctx.format_timestamp((*started).into())
+            )?,
+            AlertKind::CorruptedState { error } => {
+                writeln!(out, "✗ {}: corrupted state ({})", alert.project_name, error)?
+            }
+            AlertKind::Budget {
+                month,
+                threshold,
+                percent_used,
+            } => {
+                let (icon, what) = if *threshold >= 100 {
+                    ("✗", "exceeded")
+                } else {
+                    ("⚠", "nearly used")
+                };
+                writeln!(
+                    out,
+                    "{} {}: {} monthly budget {} ({:.0}% used)",
+                    icon, alert.project_name, month, what, percent_used
+                )?
+            }
+        }
+    }
+    writeln!(out, "\n{} alert(s)", alerts.len())?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::DateTime;
+
+    #[test]
+    fn test_snapshot_alerts_human() {
+        let alerts = vec![
+            Alert {
+                project_name: "alpha".to_string(),
+                kind: AlertKind::StuckPhase {
+                    phase: "code".to_string(),
+                    started: DateTime::parse_from_rfc3339("2023-11-13T16:13:20Z")
+                        .unwrap()
+                        .with_timezone(&Utc),
+                    age_hours: 30,
+                },
+            },
+            Alert {
+                project_name: "gamma".to_string(),
+                kind: AlertKind::CorruptedState {
+                    error: "Failed to load state".to_string(),
+                },
+            },
+            Alert {
+                project_name: "beta-service".to_string(),
+                kind: AlertKind::Budget {
+                    month: "2023-11".to_string(),
+                    threshold: 80,
+                    percent_used: 84.6,
+                },
+            },
+            Alert {
+                project_name: "all projects".to_string(),
+                kind: AlertKind::Budget {
+                    month: "2023-11".to_string(),
+                    threshold: 100,
+                    percent_used: 112.0,
+                },
+            },
+        ];
+
+        let mut out = Vec::new();
+        output_human(&alerts, &OutputContext::fixed(), &mut out).unwrap();
+        insta::assert_snapshot!(String::from_utf8(out).unwrap(), @r"
+        ⚠ alpha: phase 'code' in progress for 30h (since 2023-11-13 16:13:20)
+        ✗ gamma: corrupted state (Failed to load state)
+        ⚠ beta-service: 2023-11 monthly budget nearly used (85% used)
+        ✗ all projects: 2023-11 monthly budget exceeded (112% used)
+
+        4 alert(s)
+        ");
+
+        let mut out = Vec::new();
+        output_human(&[], &OutputContext::fixed(), &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "✓ No alerts\n");
+    }
+}
@@ -0,0 +1,136 @@
+use crate::cli::discover::format::OutputContext;
+use crate::cli::HegelPmError;
+use crate::discovery::{
+    parse_since, snapshot_baseline, DiscoveryEngine, MetricsDelta, ProjectMetricsSummary,
+    SnapshotStore,
+};
+use chrono::Utc;
+use std::io::{self, Write};
+
+/// Run the diff command: current metrics against the latest snapshot at or before `since`
+pub fn run(
+    engine: &DiscoveryEngine,
+    store: &SnapshotStore,
+    project_name: &str,
+    since: Option<&str>,
+    json: bool,
+) -> Result<(), HegelPmError> {
+    let at = since
+        .map(|value| parse_since(value, Utc::now()))
+        .transpose()
+        .map_err(|e| HegelPmError::InvalidArgument(e.to_string()))?;
+
+    let mut projects = engine.get_projects(false)?;
+    let available_names: Vec<String> = projects.iter().map(|p| p.name.clone()).collect();
+    let project = projects
+        .iter_mut()
+        .find(|p| p.matches_key(project_name))
+        .ok_or_else(|| HegelPmError::ProjectNotFound {
+            name: project_name.to_string(),
+            available: available_names,
+        })?;
+
+    // Snapshots record projects by name, so an ID argument resolves to the name first
+    let Some((taken_at, baseline)) = snapshot_baseline(store, &project.name, at)? else {
+        return Err(HegelPmError::InvalidArgument(format!(
+            "No snapshot of '{}'{} to compare against (record one with `hegel-pm snapshot`)",
+            project.name,
+            if since.is_some() {
+                " at or before --since"
+            } else {
+                ""
+            }
+        )));
+    };
+
+    project.load_statistics()?;
+    let current = project
+        .statistics
+        .as_ref()
+        .map(ProjectMetricsSummary::from)
+        .unwrap_or_default();
+    let delta = MetricsDelta::between(&project.name, taken_at, &baseline, &current);
+
+    let mut out = io::stdout().lock();
+    if json {
+        writeln!(out, "{}", serde_json::to_string_pretty(&delta)?)?;
+    } else {
+        output_human(&delta, &OutputContext::system(), &mut out)?;
+    }
+
+    Ok(())
+}
+
+fn output_human(
+    delta: &MetricsDelta,
+    ctx: &OutputContext,
+    out: &mut dyn Write,
+) -> Result<(), HegelPmError> {
+    writeln!(
+        out,
+        "{}: changes since snapshot of {}\n",
+        delta.project_name,
+        ctx.format_timestamp(delta.baseline_taken_at.into())
+    )?;
+
+    let rows = [
+        ("Tokens", delta.total_tokens),
+        ("  input", delta.input_tokens),
+        ("  output", delta.output_tokens),
+        ("  cache write", delta.cache_creation_tokens),
+        ("  cache read", delta.cache_read_tokens),
+        ("Events", delta.events),
+        ("  bash commands", delta.bash_commands),
+        ("  file edits", delta.file_modifications),
+        ("Git commits", delta.git_commits),
+        ("Phases", delta.phases),
+    ];
+    for (label, value) in rows {
+        writeln!(out, "  {:<16} {:>+10}", label, value)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::DateTime;
+
+    #[test]
+    fn test_snapshot_diff_human() {
+        let delta = MetricsDelta {
+            project_name: "alpha".to_string(),
+            baseline_taken_at: DateTime::parse_from_rfc3339("2023-11-14T22:13:20Z")
+                .unwrap()
+                .with_timezone(&Utc),
+            input_tokens: 1_200,
+            output_tokens: 300,
+            cache_creation_tokens: 0,
+            cache_read_tokens: 8_000,
+            total_tokens: 9_500,
+            events: 15,
+            bash_commands: 10,
+            file_modifications: 5,
+            git_commits: 2,
+            phases: -1,
+        };
+
+        let mut out = Vec::new();
+        output_human(&delta, &OutputContext::fixed(), &mut out).unwrap();
+        insta::assert_snapshot!(String::from_utf8(out).unwrap(), @r"
+        alpha: changes since snapshot of 2023-11-14 22:13:20
+
+          Tokens                +9500
+            input               +1200
+            output               +300
+            cache write            +0
+            cache read          +8000
+          Events                  +15
+            bash commands         +10
+            file edits             +5
+          Git commits              +2
+          Phases                   -1
+        ");
+    }
+}
@@ -0,0 +1,226 @@
+use crate::cli::report::{Report, ReportFormat, Table};
+use crate::cli::HegelPmError;
+use crate::discovery::{digest, Digest, DiscoveryConfig, SnapshotStore};
+use chrono::{DateTime, Duration, Local, Utc};
+use clap::ValueEnum;
+use std::fs;
+use std::path::Path;
+
+/// Most projects listed under "Top projects by tokens"
+const TOP_PROJECTS: usize = 10;
+
+/// How far back the digest's baseline snapshot is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DigestPeriod {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+impl DigestPeriod {
+    fn duration(self) -> Duration {
+        match self {
+            DigestPeriod::Daily => Duration::days(1),
+            DigestPeriod::Weekly => Duration::weeks(1),
+            DigestPeriod::Monthly => Duration::days(30),
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            DigestPeriod::Daily => "Daily",
+            DigestPeriod::Weekly => "Weekly",
+            DigestPeriod::Monthly => "Monthly",
+        }
+    }
+}
+
+/// Run the digest command: latest snapshot against the one a period earlier
+pub fn run(
+    config: &DiscoveryConfig,
+    store: &SnapshotStore,
+    period: DigestPeriod,
+    format: ReportFormat,
+    output: Option<&Path>,
+) -> Result<(), HegelPmError> {
+    let history = store.history(None, None)?;
+    let Some(digest) = digest(&history, period.duration(), &config.token_pricing()) else {
+        return Err(HegelPmError::InvalidArgument(format!(
+            "No snapshots recorded in {} (record them with `hegel-pm snapshot`, e.g. from cron)",
+            store.location().display()
+        )));
+    };
+
+    let report = build_report(&digest, period, |time| {
+        time.with_timezone(&Local)
+            .format("%Y-%m-%d %H:%M")
+            .to_string()
+    });
+    let rendered = match format {
+        ReportFormat::Md => report.to_markdown(),
+        ReportFormat::Html => report.to_html(),
+    };
+
+    match output {
+        Some(path) => {
+            fs::write(path, rendered)?;
+            println!("✓ Wrote digest to {}", path.display());
+        }
+        None => print!("{}", rendered),
+    }
+
+    Ok(())
+}
+
+/// Lay out a digest as a report, formatting snapshot times with `format_time`
+fn build_report(
+    digest: &Digest,
+    period: DigestPeriod,
+    format_time: impl Fn(DateTime<Utc>) -> String,
+) -> Report {
+    let window = match digest.since {
+        Some(since) => format!("{} → {}", format_time(since), format_time(digest.until)),
+        None => format!(
+            "up to {} (no earlier snapshot; totals since tracking began)",
+            format_time(digest.until)
+        ),
+    };
+
+    let names_table = |names: &[String]| {
+        (!names.is_empty()).then(|| Table {
+            headers: vec!["Project"],
+            rows: names.iter().map(|name| vec![name.clone()]).collect(),
+        })
+    };
+
+    let top = (!digest.projects.is_empty()).then(|| Table {
+        headers: vec!["Project", "Tokens", "Events", "Phases", "Est. cost"],
+        rows: digest
+            .projects
+            .iter()
+            .take(TOP_PROJECTS)
+            .map(|entry| {
+                vec![
+                    entry.project_name.clone(),
+                    entry.tokens.to_string(),
+                    entry.events.to_string(),
+                    entry.phases.to_string(),
+                    format_cost(entry.estimated_cost),
+                ]
+            })
+            .collect(),
+    });
+
+    Report {
+        title: format!("{} digest", period.title()),
+        facts: vec![
+            ("Period", window),
+            ("Active projects", digest.projects.len().to_string()),
+            ("Total tokens", digest.total_tokens.to_string()),
+            ("Total events", digest.total_events.to_string()),
+            ("Estimated cost", format_cost(digest.estimated_cost)),
+        ],
+        sections: vec![
+            ("Top projects by tokens", top),
+            ("New projects", names_table(&digest.new_projects)),
+            (
+                "Completed workflows",
+                names_table(&digest.completed_workflows),
+            ),
+        ],
+    }
+}
+
+fn format_cost(usd: f64) -> String {
+    format!("${:.2}", usd)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::DigestEntry;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn fixture_digest() -> Digest {
+        let entry =
+            |name: &str, tokens: u64, events: usize, phases: usize, cost: f64| DigestEntry {
+                project_name: name.to_string(),
+                tokens,
+                events,
+                phases,
+                estimated_cost: cost,
+            };
+        Digest {
+            since: Some(at("2025-01-06T09:00:00Z")),
+            until: at("2025-01-13T09:00:00Z"),
+            total_tokens: 1_251_000,
+            total_events: 140,
+            estimated_cost: 7.41,
+            projects: vec![
+                entry("gamma", 1_200_000, 120, 3, 7.2),
+                entry("alpha", 51_000, 20, 1, 0.21),
+            ],
+            new_projects: vec!["gamma".to_string()],
+            completed_workflows: vec![],
+        }
+    }
+
+    #[test]
+    fn test_snapshot_digest_markdown() {
+        let report = build_report(&fixture_digest(), DigestPeriod::Weekly, |time| {
+            time.format("%Y-%m-%d %H:%M").to_string()
+        });
+
+        insta::assert_snapshot!(report.to_markdown(), @r"
+        # Weekly digest
+
+        - **Period**: 2025-01-06 09:00 → 2025-01-13 09:00
+        - **Active projects**: 2
+        - **Total tokens**: 1251000
+        - **Total events**: 140
+        - **Estimated cost**: $7.41
+
+        ## Top projects by tokens
+
+        | Project | Tokens | Events | Phases | Est. cost |
+        |---|---|---|---|---|
+        | gamma | 1200000 | 120 | 3 | $7.20 |
+        | alpha | 51000 | 20 | 1 | $0.21 |
+
+        ## New projects
+
+        | Project |
+        |---|
+        | gamma |
+
+        ## Completed workflows
+
+        None recorded
+        ");
+    }
+
+    #[test]
+    fn test_run_without_snapshots() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let config = DiscoveryConfig::new(
+            vec![temp.path().to_path_buf()],
+            10,
+            vec![],
+            temp.path().join("cache.json"),
+        );
+        let store = SnapshotStore::new(temp.path().join("snapshots"));
+
+        let err = run(
+            &config,
+            &store,
+            DigestPeriod::Weekly,
+            ReportFormat::Md,
+            None,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("No snapshots recorded"));
+    }
+}
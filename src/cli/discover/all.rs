@@ -1,70 +1,202 @@
-use super::format::{abbreviate_path, format_duration_ms, format_size, format_timestamp};
-use super::validate_sort_column;
-use crate::discovery::DiscoveryEngine;
+use super::format::{format_duration_ms, format_size, format_timestamp_iso, OutputContext};
+use super::{validate_columns, validate_sort_column, GroupBy, ListFilter};
+use crate::cli::HegelPmError;
+use crate::discovery::{
+    budget_statuses, current_month, BudgetStatus, DiscoveredProject, DiscoveryEngine, GitInfo,
+    ProgressEvent, ProjectMetricsSummary,
+};
+use crate::warn;
+use chrono::Utc;
 use serde::Serialize;
-use std::error::Error;
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::path::PathBuf;
 use std::time::Instant;
 
+/// Table layout for `discover all`
+pub struct AllOptions<'a> {
+    /// Column to sort by (already resolved from flag or config)
+    pub sort_by: &'a str,
+    /// Columns to show (human output only)
+    pub columns: &'a [String],
+    /// Measure and show per-project load time
+    pub benchmark: bool,
+    /// Split the table into per-group sub-tables with subtotals
+    pub group_by: Option<GroupBy>,
+}
+
 #[derive(Clone)]
 struct ProjectRow {
     name: String,
     path: String,
+    git: Option<GitInfo>,
     size: u64,
     last_activity: std::time::SystemTime,
     total_tokens: u64,
     total_events: usize,
     phase_count: usize,
     load_time_ms: Option<u64>,
+    /// Statistics not loaded because the project exceeded `statistics_budget_mb`
+    statistics_skipped: bool,
+    /// Group key with `--group-by` (None = ungrouped)
+    group: Option<String>,
+    /// Percent of the project's monthly budget used this month (None = no project budget)
+    budget_percent: Option<f64>,
+}
+
+impl ProjectRow {
+    /// Build a table row from a project (statistics should already be loaded)
+    fn from_project(project: &DiscoveredProject, load_time_ms: Option<u64>) -> Self {
+        let (total_tokens, total_events, phase_count) = if let Some(stats) = &project.statistics {
+            (
+                stats.token_metrics.total_input_tokens + stats.token_metrics.total_output_tokens,
+                stats.hook_metrics.total_events as usize,
+                stats.phase_metrics.len(),
+            )
+        } else {
+            (0, 0, 0)
+        };
+
+        Self {
+            name: project.name.clone(),
+            path: project.project_path.display().to_string(),
+            git: project.git_info.clone(),
+            size: project.hegel_size_bytes,
+            last_activity: project.last_activity,
+            total_tokens,
+            total_events,
+            phase_count,
+            load_time_ms,
+            statistics_skipped: false,
+            group: None,
+            budget_percent: None,
+        }
+    }
+
+    /// Build a table row from the summary cached by the last refresh
+    fn from_summary(project: &DiscoveredProject, summary: &ProjectMetricsSummary) -> Self {
+        Self {
+            total_tokens: summary.total_input_tokens + summary.total_output_tokens,
+            total_events: summary.total_events,
+            phase_count: summary.phase_count,
+            ..Self::from_project(project, None)
+        }
+    }
 }
 
 /// Run the all command
+///
+/// `columns` only affects human output; `--benchmark` adds `load-time` if it isn't listed, and
+/// per-project budgets add `budget`.
 pub fn run(
     engine: &DiscoveryEngine,
-    sort_by: &str,
-    benchmark: bool,
+    options: &AllOptions,
     json: bool,
     no_cache: bool,
-) -> Result<(), Box<dyn Error>> {
-    // Validate sort column
+    filter: &ListFilter,
+) -> Result<(), HegelPmError> {
+    let AllOptions {
+        sort_by,
+        columns,
+        benchmark,
+        group_by,
+    } = *options;
+
+    // Validate sort and display columns
     validate_sort_column(sort_by, benchmark)?;
+    validate_columns(columns, benchmark)?;
+    let mut columns = columns.to_vec();
+    if benchmark && !columns.iter().any(|c| c == "load-time") {
+        columns.push("load-time".to_string());
+    }
+    if !engine.config().project_budgets.is_empty() && !columns.iter().any(|c| c == "budget") {
+        columns.push("budget".to_string());
+    }
 
     // Load projects
     let mut projects = engine.get_projects(no_cache)?;
+    // Budgets cover every project, whatever the filter lists
+    let mut unfiltered = projects.clone();
+    filter.apply(&mut projects);
 
-    // Load metrics for all projects with optional benchmarking
+    // Load metrics one project at a time, keeping only the row aggregates (streaming),
+    // so peak memory is one project's UnifiedMetrics rather than all of them
     let start_all = Instant::now();
     let mut rows: Vec<ProjectRow> = Vec::new();
+    let budget = engine.config().statistics_budget_bytes();
+    let roots = &engine.config().root_directories;
+    let group_of = |project: &DiscoveredProject| group_by.map(|g| group_key(project, g, roots));
+
+    let total = projects.len();
+    for (i, project) in projects.iter_mut().enumerate() {
+        // Refreshed projects carry a cached summary; benchmarks always load, since that's
+        // what they measure
+        if let Some(summary) = project
+            .metrics_summary
+            .as_ref()
+            .filter(|_| !no_cache && !benchmark)
+        {
+            engine.progress().emit(ProgressEvent::StatisticsLoaded {
+                name: project.name.clone(),
+                current: i + 1,
+                total,
+                ok: true,
+            });
+            rows.push(ProjectRow {
+                group: group_of(project),
+                ..ProjectRow::from_summary(project, summary)
+            });
+            continue;
+        }
 
-    for project in &mut projects {
         let start = Instant::now();
-        let _ = project.load_statistics(); // Ignore errors
+        let result = project.load_statistics_within(budget);
+        let (name, current) = (project.name.clone(), i + 1);
+        engine.progress().emit(match result {
+            // Over the budget on purpose; consumers shouldn't count it as a failure
+            Ok(false) => ProgressEvent::StatisticsSkipped {
+                name,
+                current,
+                total,
+            },
+            _ => ProgressEvent::StatisticsLoaded {
+                name,
+                current,
+                total,
+                ok: result.is_ok(),
+            },
+        });
+        if let Err(e) = &result {
+            // Shown as N/A in the table; the reason is a diagnostic, not data
+            warn!("failed to load statistics for '{}': {:#}", project.name, e);
+        }
         let load_time = if benchmark {
             Some(start.elapsed().as_millis() as u64)
         } else {
             None
         };
 
-        let (total_tokens, total_events, phase_count) = if let Some(stats) = &project.statistics {
-            (
-                stats.token_metrics.total_input_tokens + stats.token_metrics.total_output_tokens,
-                stats.hook_metrics.total_events as usize,
-                stats.phase_metrics.len(),
-            )
-        } else {
-            (0, 0, 0)
-        };
-
         rows.push(ProjectRow {
-            name: project.name.clone(),
-            path: project.project_path.display().to_string(),
-            size: calculate_dir_size(&project.hegel_dir).unwrap_or(0),
-            last_activity: project.last_activity,
-            total_tokens,
-            total_events,
-            phase_count,
-            load_time_ms: load_time,
+            statistics_skipped: matches!(result, Ok(false)),
+            group: group_of(project),
+            ..ProjectRow::from_project(project, load_time)
         });
+        // Keep the totals (for budgets below), not the statistics
+        if let Some(stats) = project.statistics.take() {
+            project.metrics_summary = Some(ProjectMetricsSummary::from(&stats));
+        }
+    }
+
+    // This month's budget usage, with the summaries just computed for listed projects
+    copy_summaries(&projects, &mut unfiltered);
+    let statuses = budget_statuses(&unfiltered, engine.config(), &current_month(Utc::now()));
+    for row in &mut rows {
+        row.budget_percent = statuses
+            .iter()
+            .find(|s| s.project_name.as_deref() == Some(row.name.as_str()))
+            .map(|s| s.percent_used);
     }
+    let global_budget = statuses.into_iter().find(|s| s.project_name.is_none());
 
     let total_load_time = if benchmark {
         Some(start_all.elapsed().as_millis() as u64)
@@ -75,25 +207,182 @@ pub fn run(
     // Sort rows
     sort_rows(&mut rows, sort_by);
 
+    let mut out = io::stdout().lock();
     if json {
-        output_json(&rows, sort_by, total_load_time, !no_cache)?;
+        output_json(
+            &rows,
+            sort_by,
+            group_by,
+            total_load_time,
+            global_budget.as_ref(),
+            !no_cache,
+            &mut out,
+        )?;
     } else {
-        output_human(&rows, sort_by, total_load_time, !no_cache)?;
+        let ctx = OutputContext::system();
+        output_human(
+            &rows,
+            &columns,
+            sort_by,
+            total_load_time,
+            global_budget.as_ref(),
+            &ctx,
+            &mut out,
+        )?;
     }
 
     Ok(())
 }
 
-fn calculate_dir_size(path: &std::path::Path) -> Result<u64, std::io::Error> {
-    let mut total = 0u64;
-    for entry in std::fs::read_dir(path)? {
-        let entry = entry?;
-        let metadata = entry.metadata()?;
-        if metadata.is_file() {
-            total += metadata.len();
+/// Give each project in `all` the metrics summary of the same project in `listed`, if it has one
+fn copy_summaries(listed: &[DiscoveredProject], all: &mut [DiscoveredProject]) {
+    for project in all {
+        if let Some(summary) = listed
+            .iter()
+            .find(|p| p.project_path == project.project_path)
+            .and_then(|p| p.metrics_summary.clone())
+        {
+            project.metrics_summary = Some(summary);
         }
     }
-    Ok(total)
+}
+
+/// Which group a project falls in for `--group-by`
+fn group_key(project: &DiscoveredProject, group_by: GroupBy, roots: &[PathBuf]) -> String {
+    match group_by {
+        GroupBy::Tag => project
+            .metadata
+            .get("tag")
+            .cloned()
+            .unwrap_or_else(|| "(untagged)".to_string()),
+        // Nested roots: the innermost one the project is under
+        GroupBy::Root => roots
+            .iter()
+            .filter(|root| project.project_path.starts_with(root))
+            .max_by_key(|root| root.components().count())
+            .map(|root| root.display().to_string())
+            .unwrap_or_else(|| "(other)".to_string()),
+        GroupBy::Mode => project
+            .workflow_state
+            .as_ref()
+            .map(|ws| ws.mode.clone())
+            .unwrap_or_else(|| "(no workflow)".to_string()),
+    }
+}
+
+/// Summed metrics of a group's rows
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+struct GroupTotals {
+    projects: usize,
+    size: u64,
+    total_tokens: u64,
+    total_events: usize,
+    phase_count: usize,
+}
+
+/// Rows sharing a group key, in table order
+struct RowGroup<'a> {
+    /// None when the table isn't grouped (a single group holding every row)
+    key: Option<&'a str>,
+    rows: Vec<&'a ProjectRow>,
+    totals: GroupTotals,
+}
+
+/// Split sorted rows into groups ordered by key, keeping the sort order within each group
+fn group_rows(rows: &[ProjectRow]) -> Vec<RowGroup<'_>> {
+    let mut groups: BTreeMap<Option<&str>, Vec<&ProjectRow>> = BTreeMap::new();
+    for row in rows {
+        groups.entry(row.group.as_deref()).or_default().push(row);
+    }
+
+    groups
+        .into_iter()
+        .map(|(key, rows)| {
+            let totals = GroupTotals {
+                projects: rows.len(),
+                size: rows.iter().map(|r| r.size).sum(),
+                total_tokens: rows.iter().map(|r| r.total_tokens).sum(),
+                total_events: rows.iter().map(|r| r.total_events).sum(),
+                phase_count: rows.iter().map(|r| r.phase_count).sum(),
+            };
+            RowGroup { key, rows, totals }
+        })
+        .collect()
+}
+
+/// Pad values to their column widths, two spaces apart (no trailing whitespace)
+fn format_line<'a>(
+    values: impl Iterator<Item = &'a str>,
+    layout: &[(&str, usize, bool)],
+) -> String {
+    let line = values
+        .zip(layout)
+        .map(|(value, &(_, width, right_aligned))| {
+            if right_aligned {
+                format!("{:>width$}", value, width = width)
+            } else {
+                format!("{:<width$}", value, width = width)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("  ");
+    line.trim_end().to_string()
+}
+
+/// Header, minimum width, and whether the column is right-aligned
+fn column_spec(column: &str) -> (&'static str, usize, bool) {
+    match column {
+        "name" => ("NAME", 4, false),
+        "path" => ("PATH", 4, false),
+        "branch" => ("BRANCH", 6, false),
+        "size" => ("SIZE", 8, true),
+        "last-activity" => ("LAST ACTIVITY", 19, true),
+        "tokens" => ("TOKENS", 8, true),
+        "events" => ("EVENTS", 8, true),
+        "phases" => ("PHASES", 7, true),
+        "load-time" => ("LOAD TIME", 9, true),
+        "budget" => ("BUDGET", 6, true),
+        _ => ("", 0, false), // Already validated
+    }
+}
+
+/// A row's value for one column
+fn cell(row: &ProjectRow, column: &str, ctx: &OutputContext) -> String {
+    match column {
+        "name" => row.name.clone(),
+        "path" => ctx.abbreviate_path(std::path::Path::new(&row.path)),
+        "branch" => row
+            .git
+            .as_ref()
+            .map(|g| g.summary())
+            .unwrap_or_else(|| "-".to_string()),
+        "size" => format_size(row.size),
+        "last-activity" => ctx.format_timestamp(row.last_activity),
+        "tokens" => row.total_tokens.to_string(),
+        "events" => row.total_events.to_string(),
+        "phases" => row.phase_count.to_string(),
+        "load-time" => row
+            .load_time_ms
+            .map(|ms| format_duration_ms(std::time::Duration::from_millis(ms)))
+            .unwrap_or_else(|| "-".to_string()),
+        "budget" => row
+            .budget_percent
+            .map(|percent| format!("{:.0}%", percent))
+            .unwrap_or_else(|| "-".to_string()),
+        _ => String::new(), // Already validated
+    }
+}
+
+/// A group subtotal's value for one column (blank where summing makes no sense)
+fn subtotal_cell(totals: &GroupTotals, column: &str) -> String {
+    match column {
+        "name" => "subtotal".to_string(),
+        "size" => format_size(totals.size),
+        "tokens" => totals.total_tokens.to_string(),
+        "events" => totals.total_events.to_string(),
+        "phases" => totals.phase_count.to_string(),
+        _ => String::new(),
+    }
 }
 
 fn sort_rows(rows: &mut [ProjectRow], sort_by: &str) {
@@ -118,6 +407,7 @@ fn sort_rows(rows: &mut [ProjectRow], sort_by: &str) {
 struct AllProjectJson {
     name: String,
     path: String,
+    git: Option<GitInfo>,
     size_bytes: u64,
     last_activity: String,
     total_tokens: u64,
@@ -125,6 +415,22 @@ struct AllProjectJson {
     phase_count: usize,
     #[serde(skip_serializing_if = "Option::is_none")]
     load_time_ms: Option<u64>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    statistics_skipped: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    group: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    budget_percent: Option<f64>,
+}
+
+#[derive(Serialize)]
+struct GroupJson {
+    key: String,
+    project_count: usize,
+    size_bytes: u64,
+    total_tokens: u64,
+    total_events: usize,
+    phase_count: usize,
 }
 
 #[derive(Serialize)]
@@ -133,27 +439,55 @@ struct AllOutputJson {
     total_count: usize,
     sorted_by: String,
     #[serde(skip_serializing_if = "Option::is_none")]
+    group_by: Option<&'static str>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    groups: Vec<GroupJson>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     total_load_time_ms: Option<u64>,
+    /// This month's usage of the global budget
+    #[serde(skip_serializing_if = "Option::is_none")]
+    budget: Option<BudgetStatus>,
     cache_used: bool,
 }
 
 fn output_json(
     rows: &[ProjectRow],
     sort_by: &str,
+    group_by: Option<GroupBy>,
     total_load_time: Option<u64>,
+    budget: Option<&BudgetStatus>,
     cache_used: bool,
-) -> Result<(), Box<dyn Error>> {
+    out: &mut dyn Write,
+) -> Result<(), HegelPmError> {
     let projects: Vec<AllProjectJson> = rows
         .iter()
         .map(|r| AllProjectJson {
             name: r.name.clone(),
             path: r.path.clone(),
+            git: r.git.clone(),
             size_bytes: r.size,
-            last_activity: super::format::format_timestamp_iso(r.last_activity),
+            last_activity: format_timestamp_iso(r.last_activity),
             total_tokens: r.total_tokens,
             total_events: r.total_events,
             phase_count: r.phase_count,
             load_time_ms: r.load_time_ms,
+            statistics_skipped: r.statistics_skipped,
+            group: r.group.clone(),
+            budget_percent: r.budget_percent,
+        })
+        .collect();
+
+    let groups: Vec<GroupJson> = group_rows(rows)
+        .into_iter()
+        .filter_map(|group| {
+            Some(GroupJson {
+                key: group.key?.to_string(),
+                project_count: group.totals.projects,
+                size_bytes: group.totals.size,
+                total_tokens: group.totals.total_tokens,
+                total_events: group.totals.total_events,
+                phase_count: group.totals.phase_count,
+            })
         })
         .collect();
 
@@ -161,111 +495,124 @@ fn output_json(
         projects,
         total_count: rows.len(),
         sorted_by: sort_by.to_string(),
+        group_by: group_by.map(GroupBy::as_str),
+        groups,
         total_load_time_ms: total_load_time,
+        budget: budget.cloned(),
         cache_used,
     };
 
-    println!("{}", serde_json::to_string_pretty(&output)?);
+    writeln!(out, "{}", serde_json::to_string_pretty(&output)?)?;
     Ok(())
 }
 
 fn output_human(
     rows: &[ProjectRow],
+    columns: &[String],
     sort_by: &str,
     total_load_time: Option<u64>,
-    _cache_used: bool,
-) -> Result<(), Box<dyn Error>> {
+    budget: Option<&BudgetStatus>,
+    ctx: &OutputContext,
+    out: &mut dyn Write,
+) -> Result<(), HegelPmError> {
     if rows.is_empty() {
-        println!("No Hegel projects found");
+        writeln!(out, "No Hegel projects found")?;
         return Ok(());
     }
 
-    // Calculate column widths
-    let name_width = rows.iter().map(|r| r.name.len()).max().unwrap_or(4).max(4);
-    let path_width = rows
+    // One table per group (a single untitled one when not grouped), each group ending
+    // with a subtotal row
+    let tables: Vec<(RowGroup, Vec<Vec<String>>)> = group_rows(rows)
+        .into_iter()
+        .map(|group| {
+            let mut cells: Vec<Vec<String>> = group
+                .rows
+                .iter()
+                .map(|row| columns.iter().map(|c| cell(row, c, ctx)).collect())
+                .collect();
+            if group.key.is_some() {
+                cells.push(
+                    columns
+                        .iter()
+                        .map(|c| subtotal_cell(&group.totals, c))
+                        .collect(),
+                );
+            }
+            (group, cells)
+        })
+        .collect();
+    let grouped = tables.iter().any(|(group, _)| group.key.is_some());
+
+    // Calculate column widths (at least the column's minimum, grown to fit content), shared
+    // by every group so the sub-tables line up
+    let layout: Vec<(&str, usize, bool)> = columns
         .iter()
-        .map(|r| abbreviate_path(&std::path::PathBuf::from(&r.path)).len())
-        .max()
-        .unwrap_or(4)
-        .max(4);
-
-    // Print header
-    if total_load_time.is_some() {
-        println!(
-            "{:<name_width$}  {:<path_width$}  {:>8}  {:>19}  {:>8}  {:>8}  {:>7}  {:>9}",
-            "NAME",
-            "PATH",
-            "SIZE",
-            "LAST ACTIVITY",
-            "TOKENS",
-            "EVENTS",
-            "PHASES",
-            "LOAD TIME",
-            name_width = name_width,
-            path_width = path_width
-        );
-    } else {
-        println!(
-            "{:<name_width$}  {:<path_width$}  {:>8}  {:>19}  {:>8}  {:>8}  {:>7}",
-            "NAME",
-            "PATH",
-            "SIZE",
-            "LAST ACTIVITY",
-            "TOKENS",
-            "EVENTS",
-            "PHASES",
-            name_width = name_width,
-            path_width = path_width
-        );
-    }
+        .enumerate()
+        .map(|(i, column)| {
+            let (header, min_width, right_aligned) = column_spec(column);
+            let width = tables
+                .iter()
+                .flat_map(|(_, cells)| cells)
+                .map(|row| row[i].chars().count())
+                .chain([min_width, header.len()])
+                .max()
+                .unwrap_or(min_width);
+            (header, width, right_aligned)
+        })
+        .collect();
 
-    // Print rows
-    for row in rows {
-        let path_abbrev = abbreviate_path(&std::path::PathBuf::from(&row.path));
-        let timestamp = format_timestamp(row.last_activity);
-
-        if let Some(load_ms) = row.load_time_ms {
-            println!(
-                "{:<name_width$}  {:<path_width$}  {:>8}  {:>19}  {:>8}  {:>8}  {:>7}  {:>9}",
-                row.name,
-                path_abbrev,
-                format_size(row.size),
-                timestamp,
-                row.total_tokens,
-                row.total_events,
-                row.phase_count,
-                format_duration_ms(std::time::Duration::from_millis(load_ms)),
-                name_width = name_width,
-                path_width = path_width
-            );
-        } else {
-            println!(
-                "{:<name_width$}  {:<path_width$}  {:>8}  {:>19}  {:>8}  {:>8}  {:>7}",
-                row.name,
-                path_abbrev,
-                format_size(row.size),
-                timestamp,
-                row.total_tokens,
-                row.total_events,
-                row.phase_count,
-                name_width = name_width,
-                path_width = path_width
-            );
+    // Print header and rows
+    for (i, (group, cells)) in tables.iter().enumerate() {
+        if let Some(key) = group.key {
+            if i > 0 {
+                writeln!(out)?;
+            }
+            writeln!(out, "{} ({} project(s))", key, group.totals.projects)?;
+        }
+        let headers = layout.iter().map(|&(header, _, _)| header);
+        writeln!(out, "{}", format_line(headers, &layout))?;
+        for row in cells {
+            writeln!(
+                out,
+                "{}",
+                format_line(row.iter().map(String::as_str), &layout)
+            )?;
         }
     }
 
     // Footer
-    if sort_by == "last-activity" {
-        println!("\n{} projects found", rows.len());
-    } else {
-        println!("\n{} projects found (sorted by {})", rows.len(), sort_by);
+    let mut found = format!("{} projects found", rows.len());
+    if grouped {
+        found.push_str(&format!(" in {} groups", tables.len()));
+    }
+    if sort_by != "last-activity" {
+        found.push_str(&format!(" (sorted by {})", sort_by));
+    }
+    writeln!(out, "\n{}", found)?;
+
+    let skipped = rows.iter().filter(|r| r.statistics_skipped).count();
+    if skipped > 0 {
+        writeln!(
+            out,
+            "{} project(s) over statistics_budget_mb, metrics not loaded",
+            skipped
+        )?;
+    }
+
+    if let Some(budget) = budget {
+        writeln!(
+            out,
+            "Monthly budget ({}): {:.0}% used ({} tokens, ${:.2})",
+            budget.month, budget.percent_used, budget.used_tokens, budget.estimated_cost
+        )?;
     }
 
     if let Some(total_ms) = total_load_time {
-        println!(
+        writeln!(
+            out,
             "Total load time: {}",
             format_duration_ms(std::time::Duration::from_millis(total_ms))
-        );
+        )?;
     }
 
     Ok(())
@@ -273,9 +620,10 @@ fn output_human(
 
 #[cfg(test)]
 mod tests {
-    use super::super::VALID_SORT_COLUMNS;
+    use super::super::{default_columns, VALID_SORT_COLUMNS};
     use super::*;
     use crate::discovery::DiscoveryConfig;
+    use crate::test_helpers::fixture_projects;
     use std::fs;
     use tempfile::TempDir;
 
@@ -305,10 +653,113 @@ mod tests {
         );
         let engine = DiscoveryEngine::new(config).unwrap();
 
-        let result = run(&engine, "last-activity", false, false, false);
+        let result = run(
+            &engine,
+            &AllOptions {
+                sort_by: "last-activity",
+                columns: &default_columns(),
+                benchmark: false,
+                group_by: None,
+            },
+            false,
+            false,
+            &ListFilter::default(),
+        );
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_run_all_reports_budget_skips_apart_from_failures() {
+        use crate::discovery::RecordingProgress;
+        use std::sync::Arc;
+
+        let temp = TempDir::new().unwrap();
+        create_test_project(temp.path(), "project1", true);
+        create_test_project(temp.path(), "project2", true);
+        // Over the 1 MB budget below
+        fs::write(
+            temp.path().join("project2/.hegel/hooks.jsonl"),
+            vec![b'\n'; 2 * 1024 * 1024],
+        )
+        .unwrap();
+
+        let mut config = DiscoveryConfig::new(
+            vec![temp.path().to_path_buf()],
+            10,
+            vec![],
+            temp.path().join("cache.json"),
+        );
+        config.statistics_budget_mb = Some(1);
+        let progress = Arc::new(RecordingProgress::default());
+        let engine = DiscoveryEngine::new(config)
+            .unwrap()
+            .with_progress(progress.clone());
+
+        run(
+            &engine,
+            &AllOptions {
+                sort_by: "name",
+                columns: &default_columns(),
+                benchmark: false,
+                group_by: None,
+            },
+            false,
+            false,
+            &ListFilter::default(),
+        )
+        .unwrap();
+
+        let events = progress.events.lock().unwrap();
+        assert!(events.iter().any(|e| matches!(
+            e,
+            ProgressEvent::StatisticsLoaded { name, ok: true, .. } if name == "project1"
+        )));
+        assert!(events.iter().any(|e| matches!(
+            e,
+            ProgressEvent::StatisticsSkipped { name, total: 2, .. } if name == "project2"
+        )));
+        assert!(!events.iter().any(|e| matches!(
+            e,
+            ProgressEvent::StatisticsLoaded { name, .. } if name == "project2"
+        )));
+    }
+
+    #[test]
+    fn test_global_budget_ignores_listing_filter() {
+        use crate::discovery::{Budget, MonthTokens, ProjectMetricsSummary};
+        use std::collections::BTreeMap;
+
+        let month = current_month(Utc::now());
+        let summary = |input_tokens| ProjectMetricsSummary {
+            per_month: BTreeMap::from([(
+                month.clone(),
+                MonthTokens {
+                    input_tokens,
+                    ..Default::default()
+                },
+            )]),
+            ..Default::default()
+        };
+        let mut all = fixture_projects();
+        all[1].metrics_summary = Some(summary(300)); // cached from the last refresh
+
+        // Only alpha listed (e.g. --status), its summary computed while loading rows
+        let mut listed = vec![all[0].clone()];
+        listed[0].metrics_summary = Some(summary(700));
+        copy_summaries(&listed, &mut all);
+
+        let config = DiscoveryConfig {
+            budget: Some(Budget {
+                monthly_tokens: Some(10_000),
+                monthly_cost_usd: None,
+            }),
+            ..Default::default()
+        };
+        let statuses = budget_statuses(&all, &config, &month);
+        assert_eq!(statuses[0].label(), "all projects");
+        assert_eq!(statuses[0].used_tokens, 1_000);
+    }
+
     #[test]
     fn test_run_all_command_with_benchmark() {
         let temp = TempDir::new().unwrap();
@@ -322,7 +773,18 @@ mod tests {
         );
         let engine = DiscoveryEngine::new(config).unwrap();
 
-        let result = run(&engine, "load-time", true, false, false);
+        let result = run(
+            &engine,
+            &AllOptions {
+                sort_by: "load-time",
+                columns: &default_columns(),
+                benchmark: true,
+                group_by: None,
+            },
+            false,
+            false,
+            &ListFilter::default(),
+        );
         assert!(result.is_ok());
     }
 
@@ -340,7 +802,18 @@ mod tests {
         let engine = DiscoveryEngine::new(config).unwrap();
 
         for sort_col in VALID_SORT_COLUMNS {
-            let result = run(&engine, sort_col, false, false, false);
+            let result = run(
+                &engine,
+                &AllOptions {
+                    sort_by: sort_col,
+                    columns: &default_columns(),
+                    benchmark: false,
+                    group_by: None,
+                },
+                false,
+                false,
+                &ListFilter::default(),
+            );
             assert!(result.is_ok(), "Failed for sort column: {}", sort_col);
         }
     }
@@ -358,7 +831,18 @@ mod tests {
         );
         let engine = DiscoveryEngine::new(config).unwrap();
 
-        let result = run(&engine, "invalid", false, false, false);
+        let result = run(
+            &engine,
+            &AllOptions {
+                sort_by: "invalid",
+                columns: &default_columns(),
+                benchmark: false,
+                group_by: None,
+            },
+            false,
+            false,
+            &ListFilter::default(),
+        );
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("Invalid sort"));
     }
@@ -369,22 +853,30 @@ mod tests {
             ProjectRow {
                 name: "zzz".to_string(),
                 path: "/path/z".to_string(),
+                git: None,
                 size: 100,
                 last_activity: std::time::SystemTime::UNIX_EPOCH,
                 total_tokens: 50,
                 total_events: 10,
                 phase_count: 2,
                 load_time_ms: Some(100),
+                statistics_skipped: false,
+                group: None,
+                budget_percent: None,
             },
             ProjectRow {
                 name: "aaa".to_string(),
                 path: "/path/a".to_string(),
+                git: None,
                 size: 200,
                 last_activity: std::time::SystemTime::now(),
                 total_tokens: 100,
                 total_events: 20,
                 phase_count: 5,
                 load_time_ms: Some(50),
+                statistics_skipped: false,
+                group: None,
+                budget_percent: None,
             },
         ];
 
@@ -397,4 +889,357 @@ mod tests {
         sort_rows(&mut rows, "tokens");
         assert_eq!(rows[0].total_tokens, 100); // Descending
     }
+
+    /// Fixture rows with metrics filled in (fixture projects have no statistics on disk)
+    fn fixture_rows() -> Vec<ProjectRow> {
+        let metrics = [(2048, 152_340, 87, 4), (0, 9_800, 12, 1), (0, 0, 0, 0)];
+        fixture_projects()
+            .iter()
+            .zip(metrics)
+            .map(|(project, (size, tokens, events, phases))| ProjectRow {
+                size,
+                total_tokens: tokens,
+                total_events: events,
+                phase_count: phases,
+                ..ProjectRow::from_project(project, None)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_snapshot_all_human() {
+        let mut out = Vec::new();
+        output_human(
+            &fixture_rows(),
+            &default_columns(),
+            "last-activity",
+            None,
+            None,
+            &OutputContext::fixed(),
+            &mut out,
+        )
+        .unwrap();
+        insta::assert_snapshot!(String::from_utf8(out).unwrap(), @r"
+        NAME          PATH                      BRANCH        SIZE        LAST ACTIVITY    TOKENS    EVENTS   PHASES
+        alpha         ~/Code/alpha              main* ↑2      2 KB  2023-11-14 22:13:20    152340        87        4
+        beta-service  ~/Code/work/beta-service  -              0 B  2023-11-13 18:26:40      9800        12        1
+        gamma         /srv/gamma                -              0 B  2023-11-03 08:26:40         0         0        0
+
+        3 projects found
+        ");
+    }
+
+    #[test]
+    fn test_snapshot_all_human_benchmark() {
+        let mut rows = fixture_rows();
+        for (row, load_ms) in rows.iter_mut().zip([12, 3, 0]) {
+            row.load_time_ms = Some(load_ms);
+        }
+        sort_rows(&mut rows, "load-time");
+        let mut columns = default_columns();
+        columns.push("load-time".to_string());
+
+        let mut out = Vec::new();
+        output_human(
+            &rows,
+            &columns,
+            "load-time",
+            Some(20),
+            None,
+            &OutputContext::fixed(),
+            &mut out,
+        )
+        .unwrap();
+        insta::assert_snapshot!(String::from_utf8(out).unwrap(), @r"
+        NAME          PATH                      BRANCH        SIZE        LAST ACTIVITY    TOKENS    EVENTS   PHASES  LOAD TIME
+        alpha         ~/Code/alpha              main* ↑2      2 KB  2023-11-14 22:13:20    152340        87        4       12ms
+        beta-service  ~/Code/work/beta-service  -              0 B  2023-11-13 18:26:40      9800        12        1        3ms
+        gamma         /srv/gamma                -              0 B  2023-11-03 08:26:40         0         0        0        0ms
+
+        3 projects found (sorted by load-time)
+        Total load time: 20ms
+        ");
+    }
+
+    #[test]
+    fn test_snapshot_all_human_columns() {
+        let mut rows = fixture_rows();
+        sort_rows(&mut rows, "tokens");
+        let columns: Vec<String> = ["name", "tokens", "phases"].map(String::from).to_vec();
+
+        let mut out = Vec::new();
+        output_human(
+            &rows,
+            &columns,
+            "tokens",
+            None,
+            None,
+            &OutputContext::fixed(),
+            &mut out,
+        )
+        .unwrap();
+        insta::assert_snapshot!(String::from_utf8(out).unwrap(), @r"
+        NAME            TOKENS   PHASES
+        alpha           152340        4
+        beta-service      9800        1
+        gamma                0        0
+
+        3 projects found (sorted by tokens)
+        ");
+    }
+
+    #[test]
+    fn test_snapshot_all_human_budget() {
+        let mut rows = fixture_rows();
+        rows[0].budget_percent = Some(84.6);
+        let columns: Vec<String> = ["name", "tokens", "budget"].map(String::from).to_vec();
+        let budget = BudgetStatus {
+            project_name: None,
+            month: "2023-11".to_string(),
+            budget: Default::default(),
+            used_tokens: 162_140,
+            estimated_cost: 1.234,
+            percent_used: 32.4,
+        };
+
+        let mut out = Vec::new();
+        output_human(
+            &rows,
+            &columns,
+            "last-activity",
+            None,
+            Some(&budget),
+            &OutputContext::fixed(),
+            &mut out,
+        )
+        .unwrap();
+        insta::assert_snapshot!(String::from_utf8(out).unwrap(), @r"
+        NAME            TOKENS  BUDGET
+        alpha           152340     85%
+        beta-service      9800       -
+        gamma                0       -
+
+        3 projects found
+        Monthly budget (2023-11): 32% used (162140 tokens, $1.23)
+        ");
+
+        let mut out = Vec::new();
+        output_json(
+            &rows,
+            "last-activity",
+            None,
+            None,
+            Some(&budget),
+            true,
+            &mut out,
+        )
+        .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(json["projects"][0]["budget_percent"], 84.6);
+        assert!(json["projects"][1].get("budget_percent").is_none());
+        assert_eq!(json["budget"]["month"], "2023-11");
+        assert_eq!(json["budget"]["project_name"], serde_json::Value::Null);
+    }
+
+    #[test]
+    fn test_group_key() {
+        let mut projects = fixture_projects();
+        projects[0]
+            .metadata
+            .insert("tag".to_string(), "client-a".to_string());
+        let roots = vec![
+            PathBuf::from("/home/dev/Code"),
+            PathBuf::from("/home/dev/Code/work"),
+        ];
+
+        let keys = |group_by| -> Vec<String> {
+            projects
+                .iter()
+                .map(|p| group_key(p, group_by, &roots))
+                .collect()
+        };
+        assert_eq!(keys(GroupBy::Tag), ["client-a", "(untagged)", "(untagged)"]);
+        assert_eq!(
+            keys(GroupBy::Root),
+            ["/home/dev/Code", "/home/dev/Code/work", "(other)"]
+        );
+        assert_eq!(
+            keys(GroupBy::Mode),
+            ["discovery", "(no workflow)", "(no workflow)"]
+        );
+    }
+
+    /// Fixture rows tagged for grouping: alpha and beta-service belong to client-a
+    fn grouped_rows() -> Vec<ProjectRow> {
+        let mut rows = fixture_rows();
+        for (row, group) in rows.iter_mut().zip(["client-a", "client-a", "(untagged)"]) {
+            row.group = Some(group.to_string());
+        }
+        rows
+    }
+
+    #[test]
+    fn test_snapshot_all_human_grouped() {
+        let columns: Vec<String> = ["name", "tokens", "events", "phases"]
+            .map(String::from)
+            .to_vec();
+
+        let mut out = Vec::new();
+        output_human(
+            &grouped_rows(),
+            &columns,
+            "last-activity",
+            None,
+            None,
+            &OutputContext::fixed(),
+            &mut out,
+        )
+        .unwrap();
+        insta::assert_snapshot!(String::from_utf8(out).unwrap(), @r"
+        (untagged) (1 project(s))
+        NAME            TOKENS    EVENTS   PHASES
+        gamma                0         0        0
+        subtotal             0         0        0
+
+        client-a (2 project(s))
+        NAME            TOKENS    EVENTS   PHASES
+        alpha           152340        87        4
+        beta-service      9800        12        1
+        subtotal        162140        99        5
+
+        3 projects found in 2 groups
+        ");
+    }
+
+    #[test]
+    fn test_all_json_grouped() {
+        let mut out = Vec::new();
+        output_json(
+            &grouped_rows(),
+            "last-activity",
+            Some(GroupBy::Tag),
+            None,
+            None,
+            true,
+            &mut out,
+        )
+        .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+
+        assert_eq!(json["group_by"], "tag");
+        assert_eq!(json["projects"][0]["group"], "client-a");
+        assert_eq!(json["groups"][1]["key"], "client-a");
+        assert_eq!(json["groups"][1]["project_count"], 2);
+        assert_eq!(json["groups"][1]["size_bytes"], 2048);
+        assert_eq!(json["groups"][1]["total_tokens"], 162_140);
+        assert_eq!(json["groups"][1]["total_events"], 99);
+        assert_eq!(json["groups"][1]["phase_count"], 5);
+    }
+
+    #[test]
+    fn test_snapshot_all_json() {
+        let mut out = Vec::new();
+        output_json(
+            &fixture_rows(),
+            "last-activity",
+            None,
+            None,
+            None,
+            true,
+            &mut out,
+        )
+        .unwrap();
+        insta::assert_snapshot!(String::from_utf8(out).unwrap(), @r#"
+        {
+          "projects": [
+            {
+              "name": "alpha",
+              "path": "/home/dev/Code/alpha",
+              "git": {
+                "branch": "main",
+                "dirty": true,
+                "ahead": 2,
+                "behind": 0
+              },
+              "size_bytes": 2048,
+              "last_activity": "2023-11-14T22:13:20+00:00",
+              "total_tokens": 152340,
+              "total_events": 87,
+              "phase_count": 4
+            },
+            {
+              "name": "beta-service",
+              "path": "/home/dev/Code/work/beta-service",
+              "git": null,
+              "size_bytes": 0,
+              "last_activity": "2023-11-13T18:26:40+00:00",
+              "total_tokens": 9800,
+              "total_events": 12,
+              "phase_count": 1
+            },
+            {
+              "name": "gamma",
+              "path": "/srv/gamma",
+              "git": null,
+              "size_bytes": 0,
+              "last_activity": "2023-11-03T08:26:40+00:00",
+              "total_tokens": 0,
+              "total_events": 0,
+              "phase_count": 0
+            }
+          ],
+          "total_count": 3,
+          "sorted_by": "last-activity",
+          "cache_used": true
+        }
+        "#);
+    }
+
+    #[test]
+    fn test_skipped_statistics_reported() {
+        let mut rows = fixture_rows();
+        rows[2].statistics_skipped = true;
+
+        let mut out = Vec::new();
+        output_human(
+            &rows,
+            &default_columns(),
+            "name",
+            None,
+            None,
+            &OutputContext::fixed(),
+            &mut out,
+        )
+        .unwrap();
+        let human = String::from_utf8(out).unwrap();
+        assert!(human.ends_with(
+            "3 projects found (sorted by name)\n1 project(s) over statistics_budget_mb, metrics not loaded\n"
+        ));
+
+        let mut out = Vec::new();
+        output_json(&rows, "name", None, None, None, true, &mut out).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(json["projects"][2]["statistics_skipped"], true);
+        assert!(json["projects"][0].get("statistics_skipped").is_none());
+    }
+
+    #[test]
+    fn test_row_from_cached_summary() {
+        let project = &fixture_projects()[0];
+        let summary = ProjectMetricsSummary {
+            total_input_tokens: 1_000,
+            total_output_tokens: 250,
+            total_cache_read_tokens: 9_000,
+            total_events: 42,
+            phase_count: 3,
+            ..Default::default()
+        };
+
+        let row = ProjectRow::from_summary(project, &summary);
+        // Same token measure as rows built from statistics (input + output)
+        assert_eq!(row.total_tokens, 1_250);
+        assert_eq!(row.total_events, 42);
+        assert_eq!(row.phase_count, 3);
+        assert_eq!(row.name, "alpha");
+    }
 }
@@ -0,0 +1,159 @@
+use crate::cli::HegelPmError;
+use crate::discovery::{
+    diagnose, diagnose_config_file, DiscoveryConfig, DoctorReport, Finding, Severity,
+};
+use std::io::{self, Write};
+use std::path::Path;
+
+/// Number of slowest projects shown in human output
+const SLOWEST_SHOWN: usize = 5;
+
+/// Run the doctor command (fails with `Unhealthy` if any check reports an error)
+///
+/// Takes the config rather than an engine so it still runs when the config fails validation.
+pub fn run(config: &DiscoveryConfig, json: bool) -> Result<(), HegelPmError> {
+    print_report(&diagnose(config), json)
+}
+
+/// Run the doctor command on the config file at `path`, even one that fails to parse
+pub fn run_config_file(path: &Path, json: bool) -> Result<(), HegelPmError> {
+    print_report(&diagnose_config_file(path), json)
+}
+
+fn print_report(report: &DoctorReport, json: bool) -> Result<(), HegelPmError> {
+    let mut out = io::stdout().lock();
+    if json {
+        writeln!(out, "{}", serde_json::to_string_pretty(report)?)?;
+    } else {
+        output_human(report, &mut out)?;
+    }
+
+    if report.is_healthy() {
+        Ok(())
+    } else {
+        Err(HegelPmError::Unhealthy(report.count(Severity::Error)))
+    }
+}
+
+fn output_human(report: &DoctorReport, out: &mut dyn Write) -> Result<(), HegelPmError> {
+    for (title, findings) in [
+        ("Config", &report.config),
+        ("Cache", &report.cache),
+        ("Projects", &report.projects),
+    ] {
+        writeln!(out, "{}", title)?;
+        for finding in findings {
+            write_finding(finding, out)?;
+        }
+        writeln!(out)?;
+    }
+
+    if !report.load_times.is_empty() {
+        writeln!(out, "Slowest to load")?;
+        writeln!(
+            out,
+            "  {:<30} {:>10} {:>10} {:>10}",
+            "NAME", "STATE", "METRICS", "TOTAL"
+        )?;
+        for time in report.load_times.iter().take(SLOWEST_SHOWN) {
+            writeln!(
+                out,
+                "  {:<30} {:>8}ms {:>8}ms {:>8}ms",
+                time.name,
+                time.state_ms,
+                time.metrics_ms,
+                time.total_ms()
+            )?;
+        }
+        writeln!(out)?;
+    }
+
+    writeln!(
+        out,
+        "{} error(s), {} warning(s)",
+        report.count(Severity::Error),
+        report.count(Severity::Warning)
+    )?;
+    Ok(())
+}
+
+fn write_finding(finding: &Finding, out: &mut dyn Write) -> Result<(), HegelPmError> {
+    let symbol = match finding.severity {
+        Severity::Ok => "✓",
+        Severity::Warning => "⚠",
+        Severity::Error => "✗",
+    };
+    writeln!(out, "  {} {}: {}", symbol, finding.subject, finding.message)?;
+    if let Some(suggestion) = &finding.suggestion {
+        writeln!(out, "      → {}", suggestion)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::ProjectLoadTime;
+    use std::path::PathBuf;
+
+    fn finding(severity: Severity, subject: &str, message: &str, fix: Option<&str>) -> Finding {
+        Finding {
+            severity,
+            subject: subject.to_string(),
+            message: message.to_string(),
+            suggestion: fix.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_output_human() {
+        let report = DoctorReport {
+            config: vec![finding(
+                Severity::Ok,
+                "/home/dev/Code",
+                "Root directory readable",
+                None,
+            )],
+            cache: vec![finding(
+                Severity::Warning,
+                "old",
+                "Cached but /home/dev/Code/old/.hegel no longer exists",
+                Some("Run `hegel-pm remove old` to stop tracking it"),
+            )],
+            projects: vec![finding(
+                Severity::Error,
+                "alpha",
+                "Corrupted state.json: Failed to load state: EOF at line 1 column 9",
+                Some("Fix or delete /home/dev/Code/alpha/.hegel/state.json"),
+            )],
+            load_times: vec![ProjectLoadTime {
+                name: "alpha".to_string(),
+                project_path: PathBuf::from("/home/dev/Code/alpha"),
+                state_ms: 2,
+                metrics_ms: 40,
+            }],
+        };
+
+        let mut out = Vec::new();
+        output_human(&report, &mut out).unwrap();
+
+        insta::assert_snapshot!(String::from_utf8(out).unwrap(), @r"
+        Config
+          ✓ /home/dev/Code: Root directory readable
+
+        Cache
+          ⚠ old: Cached but /home/dev/Code/old/.hegel no longer exists
+              → Run `hegel-pm remove old` to stop tracking it
+
+        Projects
+          ✗ alpha: Corrupted state.json: Failed to load state: EOF at line 1 column 9
+              → Fix or delete /home/dev/Code/alpha/.hegel/state.json
+
+        Slowest to load
+          NAME                                STATE    METRICS      TOTAL
+          alpha                                 2ms       40ms       42ms
+
+        1 error(s), 1 warning(s)
+        ");
+    }
+}
@@ -1,6 +1,75 @@
-use std::path::Path;
+use chrono::{DateTime, Local, Utc};
+use std::path::{Path, PathBuf};
 use std::time::{Duration, SystemTime};
 
+/// Timezone used for human-readable timestamps
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Timezone {
+    Local,
+    /// Only used by `OutputContext::fixed()`
+    #[cfg_attr(not(test), allow(dead_code))]
+    Utc,
+}
+
+/// Environment-dependent formatting inputs (home directory, timezone)
+///
+/// Commands use `OutputContext::system()`; tests pin both so output is deterministic.
+#[derive(Debug, Clone)]
+pub struct OutputContext {
+    /// Directory abbreviated as `~` (None disables abbreviation)
+    pub home: Option<PathBuf>,
+    pub timezone: Timezone,
+}
+
+impl OutputContext {
+    /// Context for the current user: real home directory, local timezone
+    pub fn system() -> Self {
+        Self {
+            home: dirs::home_dir(),
+            timezone: Timezone::Local,
+        }
+    }
+
+    /// Deterministic context for snapshot tests (home `/home/dev`, UTC)
+    #[cfg(test)]
+    pub fn fixed() -> Self {
+        Self {
+            home: Some(PathBuf::from("/home/dev")),
+            timezone: Timezone::Utc,
+        }
+    }
+
+    /// Abbreviate path by replacing home directory with ~ (platform separator after it)
+    pub fn abbreviate_path(&self, path: &Path) -> String {
+        if let Some(home) = &self.home {
+            if let Ok(stripped) = path.strip_prefix(home) {
+                if stripped.as_os_str().is_empty() {
+                    return "~".to_string();
+                }
+                return format!("~{}{}", std::path::MAIN_SEPARATOR, stripped.display());
+            }
+        }
+        path.display().to_string()
+    }
+
+    /// Format SystemTime as human-readable timestamp
+    pub fn format_timestamp(&self, time: SystemTime) -> String {
+        self.format_time(time, "%Y-%m-%d %H:%M:%S")
+    }
+
+    /// Format SystemTime as time of day only (`HH:MM:SS`), for live streams
+    pub fn format_time_of_day(&self, time: SystemTime) -> String {
+        self.format_time(time, "%H:%M:%S")
+    }
+
+    fn format_time(&self, time: SystemTime, format: &str) -> String {
+        match self.timezone {
+            Timezone::Local => DateTime::<Local>::from(time).format(format).to_string(),
+            Timezone::Utc => DateTime::<Utc>::from(time).format(format).to_string(),
+        }
+    }
+}
+
 /// Format bytes as human-readable size (KB, MB, GB, TB)
 pub fn format_size(bytes: u64) -> String {
     const KB: u64 = 1024;
@@ -21,26 +90,8 @@ pub fn format_size(bytes: u64) -> String {
     }
 }
 
-/// Abbreviate path by replacing home directory with ~
-pub fn abbreviate_path(path: &Path) -> String {
-    if let Some(home) = dirs::home_dir() {
-        if let Ok(stripped) = path.strip_prefix(&home) {
-            return format!("~/{}", stripped.display());
-        }
-    }
-    path.display().to_string()
-}
-
-/// Format SystemTime as human-readable timestamp
-pub fn format_timestamp(time: SystemTime) -> String {
-    use chrono::{DateTime, Local};
-    let datetime: DateTime<Local> = time.into();
-    datetime.format("%Y-%m-%d %H:%M:%S").to_string()
-}
-
 /// Format SystemTime as ISO 8601 for JSON
 pub fn format_timestamp_iso(time: SystemTime) -> String {
-    use chrono::{DateTime, Utc};
     let datetime: DateTime<Utc> = time.into();
     datetime.to_rfc3339()
 }
@@ -50,6 +101,21 @@ pub fn format_duration_ms(duration: Duration) -> String {
     format!("{}ms", duration.as_millis())
 }
 
+/// Format seconds as a compact duration (e.g. "45s", "12m 30s", "2h 05m")
+pub fn format_duration_secs(seconds: u64) -> String {
+    let hours = seconds / 3600;
+    let minutes = (seconds % 3600) / 60;
+    let secs = seconds % 60;
+
+    if hours > 0 {
+        format!("{}h {:02}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m {:02}s", minutes, secs)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -88,24 +154,42 @@ mod tests {
 
     #[test]
     fn test_abbreviate_path_with_home() {
-        if let Some(home) = dirs::home_dir() {
-            let path = home.join("Code/project");
-            assert_eq!(abbreviate_path(&path), "~/Code/project");
-        }
+        let ctx = OutputContext::fixed();
+        let path = Path::new("/home/dev/Code/project");
+        assert_eq!(
+            ctx.abbreviate_path(path),
+            format!("~{}Code/project", std::path::MAIN_SEPARATOR)
+        );
+        assert_eq!(ctx.abbreviate_path(Path::new("/home/dev")), "~");
     }
 
     #[test]
     fn test_abbreviate_path_without_home() {
+        let ctx = OutputContext::fixed();
         let path = Path::new("/tmp/project");
-        assert_eq!(abbreviate_path(path), "/tmp/project");
+        assert_eq!(ctx.abbreviate_path(path), "/tmp/project");
+
+        let ctx = OutputContext {
+            home: None,
+            ..OutputContext::fixed()
+        };
+        assert_eq!(
+            ctx.abbreviate_path(Path::new("/home/dev/Code")),
+            "/home/dev/Code"
+        );
     }
 
     #[test]
     fn test_format_timestamp() {
         let time = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_700_000_000);
-        let formatted = format_timestamp(time);
-        // Just verify it doesn't panic and produces non-empty string
-        assert!(!formatted.is_empty());
+        assert_eq!(
+            OutputContext::fixed().format_timestamp(time),
+            "2023-11-14 22:13:20"
+        );
+        assert_eq!(OutputContext::fixed().format_time_of_day(time), "22:13:20");
+
+        let formatted = OutputContext::system().format_timestamp(time);
+        // Local timezone varies by machine; just verify shape
         assert!(formatted.contains("-")); // Contains date separators
         assert!(formatted.contains(":")); // Contains time separators
     }
@@ -125,4 +209,12 @@ mod tests {
         assert_eq!(format_duration_ms(Duration::from_millis(5)), "5ms");
         assert_eq!(format_duration_ms(Duration::from_secs(1)), "1000ms");
     }
+
+    #[test]
+    fn test_format_duration_secs() {
+        assert_eq!(format_duration_secs(45), "45s");
+        assert_eq!(format_duration_secs(750), "12m 30s");
+        assert_eq!(format_duration_secs(7500), "2h 05m");
+        assert_eq!(format_duration_secs(0), "0s");
+    }
 }
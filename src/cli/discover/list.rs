@@ -1,44 +1,46 @@
-use super::format::{abbreviate_path, format_size, format_timestamp, format_timestamp_iso};
-use crate::discovery::{DiscoveredProject, DiscoveryEngine};
+use super::format::{format_size, format_timestamp_iso, OutputContext};
+use super::ListFilter;
+use crate::cli::HegelPmError;
+use crate::discovery::{group_by_repo, DiscoveredProject, DiscoveryEngine, GitInfo, ProjectGroup};
 use serde::Serialize;
-use std::error::Error;
+use std::io::{self, Write};
 
 /// Run the list command
-pub fn run(engine: &DiscoveryEngine, json: bool, no_cache: bool) -> Result<(), Box<dyn Error>> {
+pub fn run(
+    engine: &DiscoveryEngine,
+    json: bool,
+    no_cache: bool,
+    filter: &ListFilter,
+) -> Result<(), HegelPmError> {
     // Load projects (with cache unless no_cache is set)
-    let projects = engine.get_projects(no_cache)?;
+    let mut projects = engine.get_projects(no_cache)?;
+    filter.apply(&mut projects);
 
+    let mut out = io::stdout().lock();
     if json {
-        output_json(&projects, !no_cache)?;
+        output_json(&projects, !no_cache, &mut out)?;
     } else {
-        output_human(&projects, !no_cache)?;
+        output_human(&projects, &OutputContext::system(), &mut out)?;
     }
 
     Ok(())
 }
 
-/// Calculate directory size (non-recursive)
-fn calculate_dir_size(path: &std::path::Path) -> Result<u64, std::io::Error> {
-    let mut total = 0u64;
-    for entry in std::fs::read_dir(path)? {
-        let entry = entry?;
-        let metadata = entry.metadata()?;
-        if metadata.is_file() {
-            total += metadata.len();
-        }
-    }
-    Ok(total)
-}
-
 #[derive(Serialize)]
 struct ListProjectJson {
     name: String,
+    id: String,
     project_path: String,
     hegel_dir: String,
     hegel_size_bytes: u64,
     last_activity: String,
     has_state: bool,
     has_error: bool,
+    git: Option<GitInfo>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    archived: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    renamed_from: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -48,19 +50,27 @@ struct ListOutputJson {
     cache_used: bool,
 }
 
-fn output_json(projects: &[DiscoveredProject], cache_used: bool) -> Result<(), Box<dyn Error>> {
+fn output_json(
+    projects: &[DiscoveredProject],
+    cache_used: bool,
+    out: &mut dyn Write,
+) -> Result<(), HegelPmError> {
     let json_projects: Vec<ListProjectJson> = projects
         .iter()
         .map(|p| {
-            let size = calculate_dir_size(&p.hegel_dir).unwrap_or(0);
+            let size = p.hegel_size_bytes;
             ListProjectJson {
                 name: p.name.clone(),
+                id: p.id(),
                 project_path: p.project_path.display().to_string(),
                 hegel_dir: p.hegel_dir.display().to_string(),
                 hegel_size_bytes: size,
                 last_activity: format_timestamp_iso(p.last_activity),
                 has_state: p.has_state(),
                 has_error: p.has_error(),
+                git: p.git_info.clone(),
+                archived: p.archived,
+                renamed_from: p.renamed_from.as_ref().map(|r| r.display().to_string()),
             }
         })
         .collect();
@@ -71,55 +81,102 @@ fn output_json(projects: &[DiscoveredProject], cache_used: bool) -> Result<(), B
         cache_used,
     };
 
-    println!("{}", serde_json::to_string_pretty(&output)?);
+    writeln!(out, "{}", serde_json::to_string_pretty(&output)?)?;
     Ok(())
 }
 
-fn output_human(projects: &[DiscoveredProject], _cache_used: bool) -> Result<(), Box<dyn Error>> {
+fn output_human(
+    projects: &[DiscoveredProject],
+    ctx: &OutputContext,
+    out: &mut dyn Write,
+) -> Result<(), HegelPmError> {
     if projects.is_empty() {
-        println!("No Hegel projects found");
+        writeln!(out, "No Hegel projects found")?;
         return Ok(());
     }
 
+    // Projects sharing a git repository are listed under it, indented
+    let groups = group_by_repo(projects);
+    let label = |group: &ProjectGroup, project: &DiscoveredProject| match group.repo_root {
+        Some(_) => format!("  {}", project.name),
+        None => project.name.clone(),
+    };
+
     // Calculate column widths
-    let name_width = projects
+    let name_width = groups
         .iter()
-        .map(|p| p.name.len())
+        .flat_map(|g| g.projects.iter().map(move |p| label(g, p).len()))
         .max()
         .unwrap_or(4)
         .max(4);
     let path_width = projects
         .iter()
-        .map(|p| abbreviate_path(&p.project_path).len())
+        .map(|p| ctx.abbreviate_path(&p.project_path).len())
         .max()
         .unwrap_or(4)
         .max(4);
+    let git_width = projects
+        .iter()
+        .map(|p| git_summary(p).chars().count())
+        .max()
+        .unwrap_or(1)
+        .max(1);
 
     // Print table
-    for project in projects {
-        let size = calculate_dir_size(&project.hegel_dir).unwrap_or(0);
-        let path = abbreviate_path(&project.project_path);
-        let timestamp = format_timestamp(project.last_activity);
-
-        println!(
-            "{:<name_width$}  {:<path_width$}  {:>8}  {}",
-            project.name,
-            path,
-            format_size(size),
-            timestamp,
-            name_width = name_width,
-            path_width = path_width
-        );
+    for group in &groups {
+        if let Some(root) = &group.repo_root {
+            writeln!(
+                out,
+                "{} (monorepo, {} projects)",
+                ctx.abbreviate_path(root),
+                group.projects.len()
+            )?;
+        }
+
+        for project in &group.projects {
+            let size = project.hegel_size_bytes;
+            let path = ctx.abbreviate_path(&project.project_path);
+            let timestamp = ctx.format_timestamp(project.last_activity);
+            let renamed = project
+                .renamed_from
+                .as_ref()
+                .map(|from| format!("  (renamed from {})", ctx.abbreviate_path(from)))
+                .unwrap_or_default();
+
+            writeln!(
+                out,
+                "{:<name_width$}  {:<path_width$}  {:<git_width$}  {:>8}  {}{}",
+                label(group, project),
+                path,
+                git_summary(project),
+                format_size(size),
+                timestamp,
+                renamed,
+                name_width = name_width,
+                path_width = path_width,
+                git_width = git_width
+            )?;
+        }
     }
 
-    println!("\n{} projects found", projects.len());
+    writeln!(out, "\n{} projects found", projects.len())?;
     Ok(())
 }
 
+/// Git branch summary for table display ("-" when not a git repository)
+fn git_summary(project: &DiscoveredProject) -> String {
+    project
+        .git_info
+        .as_ref()
+        .map(|g| g.summary())
+        .unwrap_or_else(|| "-".to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::discovery::DiscoveryConfig;
+    use crate::test_helpers::fixture_projects;
     use std::fs;
     use tempfile::TempDir;
 
@@ -149,7 +206,7 @@ mod tests {
         let engine = DiscoveryEngine::new(config).unwrap();
 
         // Run list command (human output)
-        let result = run(&engine, false, false);
+        let result = run(&engine, false, false, &ListFilter::default());
         assert!(result.is_ok());
     }
 
@@ -167,7 +224,7 @@ mod tests {
         let engine = DiscoveryEngine::new(config).unwrap();
 
         // Run list command (JSON output)
-        let result = run(&engine, true, false);
+        let result = run(&engine, true, false, &ListFilter::default());
         assert!(result.is_ok());
     }
 
@@ -184,26 +241,108 @@ mod tests {
         let engine = DiscoveryEngine::new(config).unwrap();
 
         // Run list command with no projects
-        let result = run(&engine, false, false);
+        let result = run(&engine, false, false, &ListFilter::default());
         assert!(result.is_ok());
     }
 
     #[test]
-    fn test_calculate_dir_size() {
-        let temp = TempDir::new().unwrap();
-        let test_dir = temp.path().join("test");
-        fs::create_dir(&test_dir).unwrap();
-        fs::write(test_dir.join("file1.txt"), b"hello").unwrap();
-        fs::write(test_dir.join("file2.txt"), b"world!").unwrap();
+    fn test_snapshot_list_human() {
+        let mut out = Vec::new();
+        output_human(&fixture_projects(), &OutputContext::fixed(), &mut out).unwrap();
+        insta::assert_snapshot!(String::from_utf8(out).unwrap(), @r"
+        alpha         ~/Code/alpha              main* ↑2       0 B  2023-11-14 22:13:20
+        beta-service  ~/Code/work/beta-service  -              0 B  2023-11-13 18:26:40
+        gamma         /srv/gamma                -              0 B  2023-11-03 08:26:40
 
-        let size = calculate_dir_size(&test_dir).unwrap();
-        assert_eq!(size, 11); // 5 + 6 bytes
+        3 projects found
+        ");
     }
 
     #[test]
-    fn test_calculate_dir_size_empty() {
-        let temp = TempDir::new().unwrap();
-        let size = calculate_dir_size(temp.path()).unwrap();
-        assert_eq!(size, 0);
+    fn test_snapshot_list_human_monorepo() {
+        let mut projects = fixture_projects();
+        let mut git = projects[0].git_info.clone().unwrap();
+        git.root = Some(std::path::PathBuf::from("/home/dev/Code"));
+        projects[0].git_info = Some(git.clone());
+        projects[1].git_info = Some(git);
+
+        let mut out = Vec::new();
+        output_human(&projects, &OutputContext::fixed(), &mut out).unwrap();
+        insta::assert_snapshot!(String::from_utf8(out).unwrap(), @r"
+        ~/Code (monorepo, 2 projects)
+          alpha         ~/Code/alpha              main* ↑2       0 B  2023-11-14 22:13:20
+          beta-service  ~/Code/work/beta-service  main* ↑2       0 B  2023-11-13 18:26:40
+        gamma           /srv/gamma                -              0 B  2023-11-03 08:26:40
+
+        3 projects found
+        ");
+    }
+
+    #[test]
+    fn test_snapshot_list_human_renamed() {
+        let mut projects = fixture_projects();
+        projects[1].renamed_from = Some(std::path::PathBuf::from("/home/dev/Code/beta"));
+
+        let mut out = Vec::new();
+        output_human(&projects, &OutputContext::fixed(), &mut out).unwrap();
+        insta::assert_snapshot!(String::from_utf8(out).unwrap(), @r"
+        alpha         ~/Code/alpha              main* ↑2       0 B  2023-11-14 22:13:20
+        beta-service  ~/Code/work/beta-service  -              0 B  2023-11-13 18:26:40  (renamed from ~/Code/beta)
+        gamma         /srv/gamma                -              0 B  2023-11-03 08:26:40
+
+        3 projects found
+        ");
+    }
+
+    #[test]
+    fn test_snapshot_list_json() {
+        let mut out = Vec::new();
+        output_json(&fixture_projects(), true, &mut out).unwrap();
+        insta::assert_snapshot!(String::from_utf8(out).unwrap(), @r#"
+        {
+          "projects": [
+            {
+              "name": "alpha",
+              "id": "dd169f734c72c7fc",
+              "project_path": "/home/dev/Code/alpha",
+              "hegel_dir": "/home/dev/Code/alpha/.hegel",
+              "hegel_size_bytes": 0,
+              "last_activity": "2023-11-14T22:13:20+00:00",
+              "has_state": true,
+              "has_error": false,
+              "git": {
+                "branch": "main",
+                "dirty": true,
+                "ahead": 2,
+                "behind": 0
+              }
+            },
+            {
+              "name": "beta-service",
+              "id": "c947eb44b888f026",
+              "project_path": "/home/dev/Code/work/beta-service",
+              "hegel_dir": "/home/dev/Code/work/beta-service/.hegel",
+              "hegel_size_bytes": 0,
+              "last_activity": "2023-11-13T18:26:40+00:00",
+              "has_state": false,
+              "has_error": false,
+              "git": null
+            },
+            {
+              "name": "gamma",
+              "id": "625306cce4b07dad",
+              "project_path": "/srv/gamma",
+              "hegel_dir": "/srv/gamma/.hegel",
+              "hegel_size_bytes": 0,
+              "last_activity": "2023-11-03T08:26:40+00:00",
+              "has_state": false,
+              "has_error": true,
+              "git": null
+            }
+          ],
+          "total_count": 3,
+          "cache_used": true
+        }
+        "#);
     }
 }
@@ -1,11 +1,59 @@
 mod all;
-mod format;
+pub mod doctor;
+pub(crate) mod format;
 mod list;
+mod phases;
 mod show;
+mod stats;
 
-use crate::cli::DiscoverCommand;
-use crate::discovery::DiscoveryEngine;
-use std::error::Error;
+use crate::cli::{DiscoverCommand, HegelPmError};
+use crate::discovery::{DiscoveredProject, DiscoveryConfig, DiscoveryEngine, ProjectStatus};
+use clap::ValueEnum;
+
+/// Which projects `list` and `all` show
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ListFilter {
+    /// Keep archived projects (hidden by default)
+    pub include_archived: bool,
+    /// Keep only projects with this workflow status
+    pub status: Option<ProjectStatus>,
+}
+
+impl ListFilter {
+    /// Drop filtered-out projects, re-reading workflow state when filtering by status
+    pub fn apply(&self, projects: &mut Vec<DiscoveredProject>) {
+        projects.retain(|p| self.include_archived || !p.archived);
+
+        if let Some(status) = self.status {
+            // Cached state may predate the last workflow transition; state.json is cheap to read
+            for project in projects.iter_mut() {
+                project.reload_state();
+            }
+            projects.retain(|p| p.status() == status);
+        }
+    }
+}
+
+/// How `discover all --group-by` splits the table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum GroupBy {
+    /// The `tag` metadata key (`hegel-pm note set <name> tag=client-a`)
+    Tag,
+    /// The configured root directory the project was found under
+    Root,
+    /// Workflow mode (discovery, execution, ...)
+    Mode,
+}
+
+impl GroupBy {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            GroupBy::Tag => "tag",
+            GroupBy::Root => "root",
+            GroupBy::Mode => "mode",
+        }
+    }
+}
 
 /// Run a discover subcommand
 pub fn run(
@@ -13,13 +61,46 @@ pub fn run(
     subcommand: &DiscoverCommand,
     json: bool,
     no_cache: bool,
-) -> Result<(), Box<dyn Error>> {
+    filter: &ListFilter,
+) -> Result<(), HegelPmError> {
     match subcommand {
-        DiscoverCommand::List => list::run(engine, json, no_cache),
-        DiscoverCommand::Show { project_name } => show::run(engine, project_name, json, no_cache),
-        DiscoverCommand::All { sort_by, benchmark } => {
-            all::run(engine, sort_by, *benchmark, json, no_cache)
+        DiscoverCommand::List => list::run(engine, json, no_cache, filter),
+        DiscoverCommand::Show { project_name } => {
+            let project_name = match project_name {
+                Some(name) => name.clone(),
+                None => crate::cli::pick::pick_project(engine)?,
+            };
+            show::run(engine, &project_name, json, no_cache)
+        }
+        DiscoverCommand::All {
+            sort_by,
+            columns,
+            benchmark,
+            group_by,
+        } => {
+            // Flags win over the layout remembered in the config file
+            let config = engine.config();
+            let sort_by = sort_by
+                .as_deref()
+                .or(config.all_sort_by.as_deref())
+                .unwrap_or("last-activity");
+            let columns = columns
+                .clone()
+                .or_else(|| config.all_columns.clone())
+                .unwrap_or_else(default_columns);
+            let options = all::AllOptions {
+                sort_by,
+                columns: &columns,
+                benchmark: *benchmark,
+                group_by: *group_by,
+            };
+            all::run(engine, &options, json, no_cache, filter)
         }
+        DiscoverCommand::Phases { project } => {
+            phases::run(engine, project.as_deref(), json, no_cache)
+        }
+        DiscoverCommand::Doctor => doctor::run(engine.config(), json),
+        DiscoverCommand::Stats => stats::run(engine.config(), json),
     }
 }
 
@@ -46,8 +127,80 @@ pub const VALID_SORT_COLUMNS_WITH_BENCHMARK: &[&str] = &[
     "load-time",
 ];
 
+/// Columns `discover all` can display, in default order (`load-time` needs `--benchmark`)
+pub const VALID_COLUMNS: &[&str] = &[
+    "name",
+    "path",
+    "branch",
+    "size",
+    "last-activity",
+    "tokens",
+    "events",
+    "phases",
+];
+
+/// Columns `discover all` shows only when asked for (`budget` is added when per-project budgets
+/// are configured)
+pub const OPTIONAL_COLUMNS: &[&str] = &["budget"];
+
+/// Default `discover all` columns (all of `VALID_COLUMNS`)
+pub fn default_columns() -> Vec<String> {
+    VALID_COLUMNS.iter().map(|c| c.to_string()).collect()
+}
+
+/// Validate `--columns` names (non-empty, each a valid column)
+pub fn validate_columns(columns: &[String], benchmark: bool) -> Result<(), HegelPmError> {
+    let invalid = columns.iter().find(|c| {
+        !(VALID_COLUMNS.contains(&c.as_str())
+            || OPTIONAL_COLUMNS.contains(&c.as_str())
+            || (benchmark && *c == "load-time"))
+    });
+
+    match invalid {
+        None if !columns.is_empty() => Ok(()),
+        _ => {
+            let mut valid = VALID_COLUMNS.to_vec();
+            valid.extend(OPTIONAL_COLUMNS);
+            if benchmark {
+                valid.push("load-time");
+            }
+            Err(HegelPmError::InvalidArgument(format!(
+                "Invalid column '{}'\n\nValid columns: {}",
+                invalid.map(String::as_str).unwrap_or(""),
+                valid.join(", ")
+            )))
+        }
+    }
+}
+
+/// Remember `discover all` flags as the new defaults in `config` (returns whether it changed)
+///
+/// Only layouts valid without `--benchmark` are remembered, so the saved defaults always work.
+pub fn remember_all_layout(
+    config: &mut DiscoveryConfig,
+    sort_by: Option<&str>,
+    columns: Option<&[String]>,
+) -> bool {
+    let mut changed = false;
+
+    if let Some(sort_by) = sort_by.filter(|s| validate_sort_column(s, false).is_ok()) {
+        if config.all_sort_by.as_deref() != Some(sort_by) {
+            config.all_sort_by = Some(sort_by.to_string());
+            changed = true;
+        }
+    }
+    if let Some(columns) = columns.filter(|c| validate_columns(c, false).is_ok()) {
+        if config.all_columns.as_deref() != Some(columns) {
+            config.all_columns = Some(columns.to_vec());
+            changed = true;
+        }
+    }
+
+    changed
+}
+
 /// Validate sort column name
-pub fn validate_sort_column(column: &str, benchmark: bool) -> Result<(), Box<dyn Error>> {
+pub fn validate_sort_column(column: &str, benchmark: bool) -> Result<(), HegelPmError> {
     let valid_columns = if benchmark {
         VALID_SORT_COLUMNS_WITH_BENCHMARK
     } else {
@@ -57,18 +210,77 @@ pub fn validate_sort_column(column: &str, benchmark: bool) -> Result<(), Box<dyn
     if valid_columns.contains(&column) {
         Ok(())
     } else {
-        Err(format!(
+        Err(HegelPmError::InvalidArgument(format!(
             "Invalid sort column '{}'\n\nValid columns: {}",
             column,
             valid_columns.join(", ")
-        )
-        .into())
+        )))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_helpers::fixture_projects;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_list_filter_hides_archived() {
+        let mut projects = fixture_projects();
+        projects[1].archived = true;
+
+        let mut shown = projects.clone();
+        ListFilter::default().apply(&mut shown);
+        assert_eq!(shown.len(), 2);
+        assert!(shown.iter().all(|p| !p.archived));
+
+        let filter = ListFilter {
+            include_archived: true,
+            ..Default::default()
+        };
+        filter.apply(&mut projects);
+        assert_eq!(projects.len(), 3);
+    }
+
+    #[test]
+    fn test_list_filter_by_status_reloads_state() {
+        let temp = TempDir::new().unwrap();
+        let mut projects = Vec::new();
+        for (name, node) in [
+            ("building", Some("code")),
+            ("shipped", Some("done")),
+            ("idle", None),
+        ] {
+            let hegel_dir = temp.path().join(name).join(".hegel");
+            fs::create_dir_all(&hegel_dir).unwrap();
+            if let Some(node) = node {
+                let state = format!(
+                    r#"{{"workflow": {{"current_node": "{}", "mode": "discovery", "history": ["spec"]}}}}"#,
+                    node
+                );
+                fs::write(hegel_dir.join("state.json"), state).unwrap();
+            }
+            // Cached without state: the filter must read state.json itself
+            projects.push(DiscoveredProject::new(
+                name.to_string(),
+                temp.path().join(name),
+                hegel_dir,
+                None,
+                std::time::SystemTime::now(),
+                None,
+            ));
+        }
+
+        let filter = ListFilter {
+            status: Some(ProjectStatus::Completed),
+            ..Default::default()
+        };
+        filter.apply(&mut projects);
+
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].name, "shipped");
+    }
 
     #[test]
     fn test_validate_sort_column_valid() {
@@ -94,4 +306,42 @@ mod tests {
     fn test_validate_sort_column_load_time_with_benchmark() {
         assert!(validate_sort_column("load-time", true).is_ok());
     }
+
+    #[test]
+    fn test_validate_columns() {
+        let columns = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+
+        assert!(validate_columns(&default_columns(), false).is_ok());
+        assert!(validate_columns(&columns(&["tokens", "name"]), false).is_ok());
+        assert!(validate_columns(&columns(&["name", "budget"]), false).is_ok());
+        assert!(validate_columns(&columns(&["load-time"]), true).is_ok());
+        assert!(validate_columns(&columns(&["load-time"]), false).is_err());
+        assert!(validate_columns(&[], false).is_err());
+
+        let err = validate_columns(&columns(&["name", "cost"]), false).unwrap_err();
+        assert!(err.to_string().starts_with("Invalid column 'cost'"));
+    }
+
+    #[test]
+    fn test_remember_all_layout() {
+        let mut config = DiscoveryConfig::default();
+        let columns = vec!["name".to_string(), "tokens".to_string()];
+
+        assert!(remember_all_layout(
+            &mut config,
+            Some("tokens"),
+            Some(&columns)
+        ));
+        assert_eq!(config.all_sort_by.as_deref(), Some("tokens"));
+        assert_eq!(config.all_columns.as_deref(), Some(columns.as_slice()));
+
+        // Same layout again, or one that only works with --benchmark: nothing to save
+        assert!(!remember_all_layout(
+            &mut config,
+            Some("tokens"),
+            Some(&columns)
+        ));
+        assert!(!remember_all_layout(&mut config, Some("load-time"), None));
+        assert_eq!(config.all_sort_by.as_deref(), Some("tokens"));
+    }
 }
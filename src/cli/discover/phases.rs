@@ -0,0 +1,198 @@
+use super::format::format_duration_secs;
+use crate::cli::HegelPmError;
+use crate::discovery::{phase_name_stats, DiscoveryEngine, PhaseNameStats};
+use serde::Serialize;
+use std::io::{self, Write};
+
+/// Run the phases command
+pub fn run(
+    engine: &DiscoveryEngine,
+    project_name: Option<&str>,
+    json: bool,
+    no_cache: bool,
+) -> Result<(), HegelPmError> {
+    let mut projects = engine.get_projects(no_cache)?;
+
+    if let Some(name) = project_name {
+        if !projects.iter().any(|p| p.matches_key(name)) {
+            return Err(HegelPmError::not_found(name));
+        }
+    }
+
+    for project in &mut projects {
+        let _ = project.load_statistics(); // Ignore errors, project contributes nothing
+    }
+
+    let overall = phase_name_stats(&projects);
+    let comparison = project_name.and_then(|name| {
+        projects
+            .iter()
+            .find(|p| p.matches_key(name))
+            .map(|p| (name, phase_name_stats(std::slice::from_ref(p))))
+    });
+
+    let mut out = io::stdout().lock();
+    if json {
+        output_json(overall, comparison, &mut out)?;
+    } else {
+        output_human(&overall, comparison.as_ref(), &mut out)?;
+    }
+
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct ProjectPhasesJson {
+    name: String,
+    phases: Vec<PhaseNameStats>,
+}
+
+#[derive(Serialize)]
+struct PhasesOutputJson {
+    phases: Vec<PhaseNameStats>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    project: Option<ProjectPhasesJson>,
+}
+
+fn output_json(
+    overall: Vec<PhaseNameStats>,
+    comparison: Option<(&str, Vec<PhaseNameStats>)>,
+    out: &mut dyn Write,
+) -> Result<(), HegelPmError> {
+    let output = PhasesOutputJson {
+        phases: overall,
+        project: comparison.map(|(name, phases)| ProjectPhasesJson {
+            name: name.to_string(),
+            phases,
+        }),
+    };
+
+    writeln!(out, "{}", serde_json::to_string_pretty(&output)?)?;
+    Ok(())
+}
+
+fn output_human(
+    overall: &[PhaseNameStats],
+    comparison: Option<&(&str, Vec<PhaseNameStats>)>,
+    out: &mut dyn Write,
+) -> Result<(), HegelPmError> {
+    if overall.is_empty() {
+        writeln!(out, "No phase metrics found")?;
+        return Ok(());
+    }
+
+    let phase_width = overall
+        .iter()
+        .map(|p| p.phase_name.len())
+        .max()
+        .unwrap_or(5)
+        .max(5);
+
+    let mut header = format!(
+        "{:<phase_width$}  {:>6}  {:>8}  {:>10}  {:>10}  {:>12}",
+        "PHASE",
+        "COUNT",
+        "PROJECTS",
+        "AVG TOKENS",
+        "MAX TOKENS",
+        "AVG DURATION",
+        phase_width = phase_width
+    );
+    if let Some((name, _)) = comparison {
+        header.push_str(&format!("  {:>11}  {:>13}", "PROJ TOKENS", "PROJ DURATION"));
+        writeln!(out, "{}", header)?;
+        writeln!(out, "(PROJ columns: {})", name)?;
+    } else {
+        writeln!(out, "{}", header)?;
+    }
+
+    for stats in overall {
+        let mut line = format!(
+            "{:<phase_width$}  {:>6}  {:>8}  {:>10.0}  {:>10}  {:>12}",
+            stats.phase_name,
+            stats.count,
+            stats.project_count,
+            stats.avg_tokens,
+            stats.max_tokens,
+            format_avg_duration(stats.avg_duration_seconds),
+            phase_width = phase_width
+        );
+
+        if let Some((_, project_phases)) = comparison {
+            match project_phases
+                .iter()
+                .find(|p| p.phase_name == stats.phase_name)
+            {
+                Some(own) => line.push_str(&format!(
+                    "  {:>11.0}  {:>13}",
+                    own.avg_tokens,
+                    format_avg_duration(own.avg_duration_seconds)
+                )),
+                None => line.push_str(&format!("  {:>11}  {:>13}", "-", "-")),
+            }
+        }
+
+        writeln!(out, "{}", line)?;
+    }
+
+    Ok(())
+}
+
+fn format_avg_duration(seconds: Option<f64>) -> String {
+    seconds
+        .map(|s| format_duration_secs(s.round() as u64))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::DiscoveryConfig;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn create_engine(temp: &TempDir) -> DiscoveryEngine {
+        let project = temp.path().join("project1");
+        fs::create_dir_all(project.join(".hegel")).unwrap();
+
+        let config = DiscoveryConfig::new(
+            vec![temp.path().to_path_buf()],
+            10,
+            vec![],
+            temp.path().join("cache.json"),
+        );
+        DiscoveryEngine::new(config).unwrap()
+    }
+
+    #[test]
+    fn test_run_phases_command() {
+        let temp = TempDir::new().unwrap();
+        let engine = create_engine(&temp);
+
+        assert!(run(&engine, None, false, false).is_ok());
+        assert!(run(&engine, None, true, false).is_ok());
+    }
+
+    #[test]
+    fn test_run_phases_command_with_project() {
+        let temp = TempDir::new().unwrap();
+        let engine = create_engine(&temp);
+
+        assert!(run(&engine, Some("project1"), false, false).is_ok());
+    }
+
+    #[test]
+    fn test_run_phases_command_unknown_project() {
+        let temp = TempDir::new().unwrap();
+        let engine = create_engine(&temp);
+
+        let result = run(&engine, Some("nonexistent"), false, false);
+        assert!(result.unwrap_err().to_string().contains("not found"));
+    }
+
+    #[test]
+    fn test_format_avg_duration() {
+        assert_eq!(format_avg_duration(None), "-");
+        assert_eq!(format_avg_duration(Some(89.6)), "1m 30s");
+    }
+}
@@ -1,7 +1,10 @@
-use super::format::{format_size, format_timestamp, format_timestamp_iso};
-use crate::discovery::{DiscoveredProject, DiscoveryEngine};
+use super::format::{format_size, format_timestamp_iso, OutputContext};
+use crate::cli::HegelPmError;
+use crate::discovery::{DiscoveredProject, DiscoveryEngine, GitInfo, ProjectStatus};
+use crate::warn;
 use serde::Serialize;
-use std::error::Error;
+use std::collections::BTreeMap;
+use std::io::{self, Write};
 
 /// Run the show command
 pub fn run(
@@ -9,53 +12,38 @@ pub fn run(
     project_name: &str,
     json: bool,
     no_cache: bool,
-) -> Result<(), Box<dyn Error>> {
+) -> Result<(), HegelPmError> {
     // Load projects
     let mut projects = engine.get_projects(no_cache)?;
 
     // Collect available names first for error message
     let available_names: Vec<String> = projects.iter().map(|p| p.name.clone()).collect();
 
-    // Find project by name
+    // Find project by name or ID
     let project = projects
         .iter_mut()
-        .find(|p| p.name == project_name)
-        .ok_or_else(|| {
-            format!(
-                "Project '{}' not found\n\nAvailable projects:\n{}",
-                project_name,
-                available_names
-                    .iter()
-                    .map(|n| format!("  - {}", n))
-                    .collect::<Vec<_>>()
-                    .join("\n")
-            )
+        .find(|p| p.matches_key(project_name))
+        .ok_or_else(|| HegelPmError::ProjectNotFound {
+            name: project_name.to_string(),
+            available: available_names,
         })?;
 
     // Load metrics
-    let _ = project.load_statistics(); // Ignore errors, will show N/A
+    if let Err(e) = project.load_statistics() {
+        // Metrics show as N/A
+        warn!("failed to load statistics for '{}': {:#}", project.name, e);
+    }
 
+    let mut out = io::stdout().lock();
     if json {
-        output_json(project)?;
+        output_json(project, &mut out)?;
     } else {
-        output_human(project)?;
+        output_human(project, &OutputContext::system(), &mut out)?;
     }
 
     Ok(())
 }
 
-fn calculate_dir_size(path: &std::path::Path) -> Result<u64, std::io::Error> {
-    let mut total = 0u64;
-    for entry in std::fs::read_dir(path)? {
-        let entry = entry?;
-        let metadata = entry.metadata()?;
-        if metadata.is_file() {
-            total += metadata.len();
-        }
-    }
-    Ok(total)
-}
-
 #[derive(Serialize)]
 struct WorkflowStateJson {
     mode: String,
@@ -80,11 +68,16 @@ struct ShowProjectJson {
     last_activity: String,
     workflow_state: Option<WorkflowStateJson>,
     metrics: Option<MetricsJson>,
+    git: Option<GitInfo>,
     error: Option<String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    metadata: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    extra: BTreeMap<String, serde_json::Value>,
 }
 
-fn output_json(project: &DiscoveredProject) -> Result<(), Box<dyn Error>> {
-    let size = calculate_dir_size(&project.hegel_dir).unwrap_or(0);
+fn output_json(project: &DiscoveredProject, out: &mut dyn Write) -> Result<(), HegelPmError> {
+    let size = project.hegel_size_bytes;
 
     let workflow_state = project.workflow_state.as_ref().map(|ws| WorkflowStateJson {
         mode: ws.mode.clone(),
@@ -107,61 +100,88 @@ fn output_json(project: &DiscoveredProject) -> Result<(), Box<dyn Error>> {
         last_activity: format_timestamp_iso(project.last_activity),
         workflow_state,
         metrics,
+        git: project.git_info.clone(),
         error: project.error.clone(),
+        metadata: project.metadata.clone(),
+        extra: project.extra.clone(),
     };
 
-    println!("{}", serde_json::to_string_pretty(&output)?);
+    writeln!(out, "{}", serde_json::to_string_pretty(&output)?)?;
     Ok(())
 }
 
-fn output_human(project: &DiscoveredProject) -> Result<(), Box<dyn Error>> {
-    let size = calculate_dir_size(&project.hegel_dir).unwrap_or(0);
-
-    println!("Project: {}", project.name);
-    println!("Path: {}", project.project_path.display());
-    println!(".hegel size: {}", format_size(size));
-    println!(
+fn output_human(
+    project: &DiscoveredProject,
+    ctx: &OutputContext,
+    out: &mut dyn Write,
+) -> Result<(), HegelPmError> {
+    let size = project.hegel_size_bytes;
+
+    writeln!(out, "Project: {}", project.name)?;
+    writeln!(out, "Path: {}", project.project_path.display())?;
+    writeln!(out, ".hegel size: {}", format_size(size))?;
+    if let Some(git) = &project.git_info {
+        writeln!(out, "Git: {}", git.summary())?;
+    }
+    writeln!(
+        out,
         "Last activity: {}\n",
-        format_timestamp(project.last_activity)
-    );
+        ctx.format_timestamp(project.last_activity)
+    )?;
 
     // Workflow state
     if let Some(error) = &project.error {
-        println!("Workflow State: Error loading state");
-        println!("  Error: {}\n", error);
+        writeln!(out, "Workflow State: Error loading state")?;
+        writeln!(out, "  Error: {}\n", error)?;
     } else if let Some(state) = &project.workflow_state {
-        println!("Workflow State:");
-        println!("  Mode: {}", state.mode);
-        println!("  Current node: {}", state.current_node);
-        println!("  History: {}\n", state.history.join(" → "));
+        writeln!(out, "Workflow State:")?;
+        writeln!(out, "  Mode: {}", state.mode)?;
+        writeln!(out, "  Current node: {}", state.current_node)?;
+        writeln!(out, "  History: {}\n", state.history.join(" → "))?;
     } else {
-        println!("Workflow State: None\n");
+        writeln!(out, "Workflow State: None\n")?;
     }
 
     // Metrics
     if let Some(stats) = &project.statistics {
-        println!("Metrics:");
-        println!(
+        writeln!(out, "Metrics:")?;
+        writeln!(
+            out,
             "  Total tokens: {} (input: {}, output: {})",
             stats.token_metrics.total_input_tokens + stats.token_metrics.total_output_tokens,
             stats.token_metrics.total_input_tokens,
             stats.token_metrics.total_output_tokens
-        );
-        println!("  Total events: {}", stats.hook_metrics.total_events);
-        println!("  Phase count: {}", stats.phase_metrics.len());
+        )?;
+        writeln!(out, "  Total events: {}", stats.hook_metrics.total_events)?;
+        writeln!(out, "  Phase count: {}", stats.phase_metrics.len())?;
     } else {
-        println!("Metrics: No metrics available");
+        writeln!(out, "Metrics: No metrics available")?;
+    }
+
+    // User metadata (hegel-pm note set)
+    if !project.metadata.is_empty() {
+        writeln!(out, "\nMetadata:")?;
+        for (key, value) in &project.metadata {
+            writeln!(out, "  {}: {}", key, value)?;
+        }
+    }
+
+    // Discovery plugin output
+    if !project.extra.is_empty() {
+        writeln!(out, "\nPlugins:")?;
+        for (name, value) in &project.extra {
+            writeln!(out, "  {}: {}", name, value)?;
+        }
     }
 
     // Status
-    let status = if project.has_error() {
-        "Error (corrupted state)"
-    } else if project.has_state() {
-        "Active"
-    } else {
-        "Inactive"
+    let status = match project.status() {
+        ProjectStatus::Error => "Error (corrupted state)",
+        ProjectStatus::Completed => "Completed",
+        ProjectStatus::Active => "Active",
+        ProjectStatus::Inactive => "Inactive",
     };
-    println!("\nStatus: {}", status);
+    writeln!(out, "\nStatus: {}", status)?;
 
     Ok(())
 }
@@ -170,6 +190,7 @@ fn output_human(project: &DiscoveredProject) -> Result<(), Box<dyn Error>> {
 mod tests {
     use super::*;
     use crate::discovery::DiscoveryConfig;
+    use crate::test_helpers::fixture_projects;
     use std::fs;
     use tempfile::TempDir;
 
@@ -258,4 +279,135 @@ mod tests {
         let result = run(&engine, "project1", false, false);
         assert!(result.is_ok());
     }
+
+    fn render_human(project: &DiscoveredProject) -> String {
+        let mut out = Vec::new();
+        output_human(project, &OutputContext::fixed(), &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_snapshot_show_human() {
+        let projects = fixture_projects();
+        insta::assert_snapshot!(render_human(&projects[0]), @r"
+        Project: alpha
+        Path: /home/dev/Code/alpha
+        .hegel size: 0 B
+        Git: main* ↑2
+        Last activity: 2023-11-14 22:13:20
+
+        Workflow State:
+          Mode: discovery
+          Current node: code
+          History: spec → plan → code
+
+        Metrics: No metrics available
+
+        Status: Active
+        ");
+    }
+
+    #[test]
+    fn test_snapshot_show_human_error() {
+        let projects = fixture_projects();
+        insta::assert_snapshot!(render_human(&projects[2]), @r"
+        Project: gamma
+        Path: /srv/gamma
+        .hegel size: 0 B
+        Last activity: 2023-11-03 08:26:40
+
+        Workflow State: Error loading state
+          Error: Failed to load state
+
+        Metrics: No metrics available
+
+        Status: Error (corrupted state)
+        ");
+    }
+
+    #[test]
+    fn test_snapshot_show_human_metadata() {
+        let mut project = fixture_projects().remove(1);
+        project
+            .metadata
+            .insert("owner".to_string(), "alice".to_string());
+        project
+            .metadata
+            .insert("status".to_string(), "blocked on review".to_string());
+        insta::assert_snapshot!(render_human(&project), @r"
+        Project: beta-service
+        Path: /home/dev/Code/work/beta-service
+        .hegel size: 0 B
+        Last activity: 2023-11-13 18:26:40
+
+        Workflow State: None
+
+        Metrics: No metrics available
+
+        Metadata:
+          owner: alice
+          status: blocked on review
+
+        Status: Inactive
+        ");
+    }
+
+    #[test]
+    fn test_snapshot_show_human_plugins() {
+        let mut project = fixture_projects().remove(1);
+        project
+            .extra
+            .insert("cargo".to_string(), serde_json::json!({"members": 3}));
+        project
+            .extra
+            .insert("ci".to_string(), serde_json::json!("passing"));
+        insta::assert_snapshot!(render_human(&project), @r#"
+        Project: beta-service
+        Path: /home/dev/Code/work/beta-service
+        .hegel size: 0 B
+        Last activity: 2023-11-13 18:26:40
+
+        Workflow State: None
+
+        Metrics: No metrics available
+
+        Plugins:
+          cargo: {"members":3}
+          ci: "passing"
+
+        Status: Inactive
+        "#);
+    }
+
+    #[test]
+    fn test_snapshot_show_json() {
+        let mut out = Vec::new();
+        output_json(&fixture_projects()[0], &mut out).unwrap();
+        insta::assert_snapshot!(String::from_utf8(out).unwrap(), @r#"
+        {
+          "name": "alpha",
+          "project_path": "/home/dev/Code/alpha",
+          "hegel_dir": "/home/dev/Code/alpha/.hegel",
+          "hegel_size_bytes": 0,
+          "last_activity": "2023-11-14T22:13:20+00:00",
+          "workflow_state": {
+            "mode": "discovery",
+            "current_node": "code",
+            "history": [
+              "spec",
+              "plan",
+              "code"
+            ]
+          },
+          "metrics": null,
+          "git": {
+            "branch": "main",
+            "dirty": true,
+            "ahead": 2,
+            "behind": 0
+          },
+          "error": null
+        }
+        "#);
+    }
 }
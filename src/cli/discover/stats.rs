@@ -0,0 +1,208 @@
+use super::format::{format_duration_secs, format_size, format_timestamp_iso, OutputContext};
+use crate::cli::HegelPmError;
+use crate::discovery::{
+    cache_stats, CacheBackend, CacheStats, CachedProjectStats, DiscoveryConfig,
+};
+use serde::Serialize;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+/// Run the stats command (reads the cache as-is; never scans)
+pub fn run(config: &DiscoveryConfig, json: bool) -> Result<(), HegelPmError> {
+    let stats = cache_stats(config)?;
+    let now = SystemTime::now();
+
+    let mut out = io::stdout().lock();
+    if json {
+        output_json(&stats, now, &mut out)?;
+    } else {
+        output_human(&stats, now, &OutputContext::system(), &mut out)?;
+    }
+
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct StatsJson<'a> {
+    backend: String,
+    cache_dir: &'a PathBuf,
+    project_count: usize,
+    total_bytes: u64,
+    last_saved: Option<String>,
+    last_saved_age_secs: Option<u64>,
+    projects: &'a [CachedProjectStats],
+}
+
+fn output_json(
+    stats: &CacheStats,
+    now: SystemTime,
+    out: &mut dyn Write,
+) -> Result<(), HegelPmError> {
+    let output = StatsJson {
+        backend: backend_name(stats.backend),
+        cache_dir: &stats.cache_dir,
+        project_count: stats.projects.len(),
+        total_bytes: stats.total_bytes,
+        last_saved: stats.last_saved.map(format_timestamp_iso),
+        last_saved_age_secs: stats.last_saved.map(|saved| age_secs(saved, now)),
+        projects: &stats.projects,
+    };
+
+    writeln!(out, "{}", serde_json::to_string_pretty(&output)?)?;
+    Ok(())
+}
+
+fn output_human(
+    stats: &CacheStats,
+    now: SystemTime,
+    ctx: &OutputContext,
+    out: &mut dyn Write,
+) -> Result<(), HegelPmError> {
+    writeln!(
+        out,
+        "Cache directory: {} ({} backend)",
+        ctx.abbreviate_path(&stats.cache_dir),
+        backend_name(stats.backend)
+    )?;
+    writeln!(out, "Indexed projects: {}", stats.projects.len())?;
+    writeln!(out, "Total size: {}", format_size(stats.total_bytes))?;
+    match stats.last_saved {
+        Some(saved) => writeln!(
+            out,
+            "Last saved: {} ({} ago)",
+            ctx.format_timestamp(saved),
+            format_duration_secs(age_secs(saved, now))
+        )?,
+        None => writeln!(
+            out,
+            "Last saved: never (run 'hegel-pm discover list' to populate)"
+        )?,
+    }
+
+    if stats.projects.is_empty() {
+        return Ok(());
+    }
+
+    let name_width = stats
+        .projects
+        .iter()
+        .map(|p| p.name.len())
+        .max()
+        .unwrap_or(0)
+        .max("NAME".len());
+
+    writeln!(out)?;
+    writeln!(
+        out,
+        "{:<width$}  {:>9}  {:<5}  STATISTICS",
+        "NAME",
+        "FILE",
+        "STATE",
+        width = name_width
+    )?;
+    for project in &stats.projects {
+        let size = match project.file_bytes {
+            Some(bytes) => format_size(bytes),
+            None => "-".to_string(),
+        };
+        let (state, statistics) = if !project.loadable {
+            ("-", "unreadable")
+        } else {
+            (
+                if project.has_workflow_state {
+                    "yes"
+                } else {
+                    "no"
+                },
+                if project.has_statistics {
+                    "cached"
+                } else {
+                    "cleared"
+                },
+            )
+        };
+        writeln!(
+            out,
+            "{:<width$}  {:>9}  {:<5}  {}",
+            project.name,
+            size,
+            state,
+            statistics,
+            width = name_width
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Backend name as written in config.json
+fn backend_name(backend: CacheBackend) -> String {
+    format!("{:?}", backend).to_lowercase()
+}
+
+/// Seconds from `saved` to `now` (0 if the clock went backwards)
+fn age_secs(saved: SystemTime, now: SystemTime) -> u64 {
+    now.duration_since(saved).map_or(0, |age| age.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn sample_stats() -> CacheStats {
+        let project =
+            |name: &str, bytes: Option<u64>, loadable: bool, state: bool| CachedProjectStats {
+                name: name.to_string(),
+                file_bytes: bytes,
+                loadable,
+                has_workflow_state: state,
+                has_statistics: false,
+            };
+
+        CacheStats {
+            backend: CacheBackend::Files,
+            cache_dir: PathBuf::from("/home/dev/.config/hegel-pm/cache"),
+            projects: vec![
+                project("alpha", Some(2_048), true, true),
+                project("beta-service", Some(512), true, false),
+                project("gamma", None, false, false),
+            ],
+            total_bytes: 8_192,
+            last_saved: Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000)),
+        }
+    }
+
+    #[test]
+    fn test_snapshot_stats_human() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_003_725);
+        let mut out = Vec::new();
+        output_human(&sample_stats(), now, &OutputContext::fixed(), &mut out).unwrap();
+
+        insta::assert_snapshot!(String::from_utf8(out).unwrap(), @r"
+        Cache directory: ~/.config/hegel-pm/cache (files backend)
+        Indexed projects: 3
+        Total size: 8 KB
+        Last saved: 2023-11-14 22:13:20 (1h 02m ago)
+
+        NAME               FILE  STATE  STATISTICS
+        alpha              2 KB  yes    cleared
+        beta-service      512 B  no     cleared
+        gamma                 -  -      unreadable
+        ");
+    }
+
+    #[test]
+    fn test_stats_json_fields() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_060);
+        let mut out = Vec::new();
+        output_json(&sample_stats(), now, &mut out).unwrap();
+
+        let value: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(value["backend"], "files");
+        assert_eq!(value["project_count"], 3);
+        assert_eq!(value["last_saved_age_secs"], 60);
+        assert_eq!(value["projects"][2]["loadable"], false);
+    }
+}
@@ -0,0 +1,135 @@
+use thiserror::Error;
+
+/// CLI failure categories, each with its own process exit code
+///
+/// Scripts wrapping `hegel-pm` can branch on the exit code instead of parsing stderr.
+#[derive(Debug, Error)]
+pub enum HegelPmError {
+    /// Config file unreadable or invalid (missing roots, bad max depth, unwritable cache)
+    #[error("Invalid configuration: {0:#}")]
+    Config(anyhow::Error),
+
+    /// Named project isn't among the discovered/cached projects
+    #[error("{}", not_found_message(name, available))]
+    ProjectNotFound {
+        name: String,
+        /// Known project names, listed in the message when non-empty
+        available: Vec<String>,
+    },
+
+    /// Bad command-line usage not caught by argument parsing
+    #[error("{0}")]
+    InvalidArgument(String),
+
+    /// A hegel subprocess failed in one or more projects
+    #[error("{failed} project(s) failed")]
+    Subprocess { failed: usize },
+
+    /// Diagnostics reported errors (`discover doctor`)
+    #[error("Doctor found {0} problem(s)")]
+    Unhealthy(usize),
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+
+    /// Discovery, cache, or storage failure
+    #[error("{0:#}")]
+    Other(#[from] anyhow::Error),
+}
+
+impl HegelPmError {
+    /// Process exit code for this error
+    ///
+    /// | Code | Meaning |
+    /// |------|---------|
+    /// | 1 | Other failure (I/O, JSON, cache, discovery) |
+    /// | 2 | Not used here: clap exits with 2 on usage errors (unknown flag, missing value) |
+    /// | 3 | Project not found |
+    /// | 4 | `hegel` subprocess failed |
+    /// | 5 | Invalid argument |
+    /// | 6 | Doctor found problems |
+    /// | 7 | Invalid configuration |
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::ProjectNotFound { .. } => 3,
+            Self::Subprocess { .. } => 4,
+            Self::InvalidArgument(_) => 5,
+            Self::Unhealthy(_) => 6,
+            Self::Config(_) => 7,
+            Self::Io(_) | Self::Json(_) | Self::Other(_) => 1,
+        }
+    }
+
+    /// Project-not-found error without a list of alternatives
+    pub fn not_found(name: &str) -> Self {
+        Self::ProjectNotFound {
+            name: name.to_string(),
+            available: Vec::new(),
+        }
+    }
+}
+
+fn not_found_message(name: &str, available: &[String]) -> String {
+    if available.is_empty() {
+        return format!("Project '{}' not found", name);
+    }
+
+    format!(
+        "Project '{}' not found\n\nAvailable projects:\n{}",
+        name,
+        available
+            .iter()
+            .map(|n| format!("  - {}", n))
+            .collect::<Vec<_>>()
+            .join("\n")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_codes_are_distinct_per_category() {
+        assert_eq!(HegelPmError::Config(anyhow::anyhow!("bad")).exit_code(), 7);
+        assert_eq!(HegelPmError::not_found("x").exit_code(), 3);
+        assert_eq!(HegelPmError::Subprocess { failed: 2 }.exit_code(), 4);
+        assert_eq!(
+            HegelPmError::InvalidArgument("bad".to_string()).exit_code(),
+            5
+        );
+        assert_eq!(HegelPmError::Unhealthy(1).exit_code(), 6);
+        assert_eq!(HegelPmError::from(anyhow::anyhow!("io")).exit_code(), 1);
+    }
+
+    #[test]
+    fn test_not_found_message_lists_available() {
+        assert_eq!(
+            HegelPmError::not_found("x").to_string(),
+            "Project 'x' not found"
+        );
+
+        let err = HegelPmError::ProjectNotFound {
+            name: "x".to_string(),
+            available: vec!["alpha".to_string(), "beta".to_string()],
+        };
+        assert_eq!(
+            err.to_string(),
+            "Project 'x' not found\n\nAvailable projects:\n  - alpha\n  - beta"
+        );
+    }
+
+    #[test]
+    fn test_config_error_includes_cause_chain() {
+        let err = HegelPmError::Config(
+            anyhow::anyhow!("Root directory does not exist: /nope").context("Failed to start"),
+        );
+        assert_eq!(
+            err.to_string(),
+            "Invalid configuration: Failed to start: Root directory does not exist: /nope"
+        );
+    }
+}
@@ -1,5 +1,5 @@
-use crate::discovery::DiscoveryEngine;
-use std::error::Error;
+use crate::cli::HegelPmError;
+use crate::discovery::{DiscoveryEngine, RemoteRoot};
 use std::process::Command;
 
 /// Commands that should not be run across all projects
@@ -9,25 +9,31 @@ const DISALLOWED_COMMANDS: &[&str] = &[
 ];
 
 /// Run a hegel command across all discovered projects
-pub fn run(engine: &DiscoveryEngine, args: &[String]) -> Result<(), Box<dyn Error>> {
+pub fn run(
+    engine: &DiscoveryEngine,
+    args: &[String],
+    include_archived: bool,
+) -> Result<(), HegelPmError> {
     // Validate we have at least a subcommand
     if args.is_empty() {
-        return Err("No hegel command specified. Usage: hegel-pm x <command> [args...]".into());
+        return Err(HegelPmError::InvalidArgument(
+            "No hegel command specified. Usage: hegel-pm x <command> [args...]".to_string(),
+        ));
     }
 
     // Check if the command is disallowed
     let subcommand = &args[0];
     if DISALLOWED_COMMANDS.contains(&subcommand.as_str()) {
-        return Err(format!(
+        return Err(HegelPmError::InvalidArgument(format!(
             "Command 'hegel {}' cannot be run across all projects (interactive/TUI command)\n\nDisallowed commands: {}",
             subcommand,
             DISALLOWED_COMMANDS.join(", ")
-        )
-        .into());
+        )));
     }
 
     // Discover all projects (use cache)
-    let projects = engine.get_projects(false)?;
+    let mut projects = engine.get_projects(false)?;
+    projects.retain(|p| include_archived || !p.archived);
 
     if projects.is_empty() {
         println!("No Hegel projects found");
@@ -47,6 +53,12 @@ pub fn run(engine: &DiscoveryEngine, args: &[String]) -> Result<(), Box<dyn Erro
         println!("=== {} ===", project.name);
         println!("Path: {}", project.project_path.display());
 
+        // hegel runs against the working copy, which only exists on the remote host
+        if RemoteRoot::parse(&project.project_path).is_some() {
+            println!("- Skipped (remote project)\n");
+            continue;
+        }
+
         // Run hegel command with --state-dir pointing to this project's .hegel directory
         let mut cmd = Command::new("hegel");
         cmd.args(args);
@@ -87,7 +99,9 @@ pub fn run(engine: &DiscoveryEngine, args: &[String]) -> Result<(), Box<dyn Erro
     println!("Failed: {}", failure_count);
 
     if failure_count > 0 {
-        Err(format!("{} project(s) failed", failure_count).into())
+        Err(HegelPmError::Subprocess {
+            failed: failure_count,
+        })
     } else {
         Ok(())
     }
@@ -102,7 +116,7 @@ mod tests {
         let config = crate::discovery::DiscoveryConfig::default();
         let engine = DiscoveryEngine::new(config).unwrap();
 
-        let result = run(&engine, &["top".to_string()]);
+        let result = run(&engine, &["top".to_string()], false);
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("interactive/TUI"));
     }
@@ -112,7 +126,11 @@ mod tests {
         let config = crate::discovery::DiscoveryConfig::default();
         let engine = DiscoveryEngine::new(config).unwrap();
 
-        let result = run(&engine, &["reflect".to_string(), "SPEC.md".to_string()]);
+        let result = run(
+            &engine,
+            &["reflect".to_string(), "SPEC.md".to_string()],
+            false,
+        );
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("interactive/TUI"));
     }
@@ -122,7 +140,7 @@ mod tests {
         let config = crate::discovery::DiscoveryConfig::default();
         let engine = DiscoveryEngine::new(config).unwrap();
 
-        let result = run(&engine, &[]);
+        let result = run(&engine, &[], false);
         assert!(result.is_err());
         assert!(result
             .unwrap_err()
@@ -0,0 +1,77 @@
+use crate::cli::{HegelPmError, NoteCommand};
+use crate::discovery::{update_cached_project, DiscoveryConfig};
+
+/// Run a note subcommand (set or unset project metadata in the cache)
+pub fn run(config: &DiscoveryConfig, command: &NoteCommand) -> Result<(), HegelPmError> {
+    match command {
+        NoteCommand::Set {
+            project_name,
+            entries,
+        } => {
+            let pairs = entries
+                .iter()
+                .map(|entry| parse_entry(entry))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let found = update_cached_project(project_name, config, |project| {
+                project.metadata.extend(pairs);
+            })?;
+            if !found {
+                return Err(HegelPmError::not_found(project_name));
+            }
+            println!("✓ Updated metadata for '{}'", project_name);
+        }
+        NoteCommand::Unset { project_name, keys } => {
+            let found = update_cached_project(project_name, config, |project| {
+                for key in keys {
+                    project.metadata.remove(key);
+                }
+            })?;
+            if !found {
+                return Err(HegelPmError::not_found(project_name));
+            }
+            println!("✓ Removed {} key(s) from '{}'", keys.len(), project_name);
+        }
+    }
+
+    Ok(())
+}
+
+/// Parse a `key=value` argument (value may contain `=`, key must be non-empty)
+fn parse_entry(entry: &str) -> Result<(String, String), HegelPmError> {
+    match entry.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => {
+            Ok((key.trim().to_string(), value.to_string()))
+        }
+        _ => Err(HegelPmError::InvalidArgument(format!(
+            "Invalid metadata '{}' (expected key=value, e.g. owner=alice)",
+            entry
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_entry() {
+        assert_eq!(
+            parse_entry("owner=alice").unwrap(),
+            ("owner".to_string(), "alice".to_string())
+        );
+        assert_eq!(
+            parse_entry("link=https://jira.example.com/browse/PM-1?a=b").unwrap(),
+            (
+                "link".to_string(),
+                "https://jira.example.com/browse/PM-1?a=b".to_string()
+            )
+        );
+        assert_eq!(
+            parse_entry("notes=").unwrap(),
+            ("notes".to_string(), String::new())
+        );
+        assert!(parse_entry("owner").is_err());
+        assert!(parse_entry("=alice").is_err());
+    }
+}
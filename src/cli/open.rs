@@ -0,0 +1,244 @@
+use crate::cli::HegelPmError;
+use crate::discovery::{DiscoveredProject, DiscoveryEngine, RemoteRoot};
+use std::path::PathBuf;
+use std::process::Command;
+
+/// What `hegel-pm open` launches for a project
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OpenTarget {
+    /// `$VISUAL` or `$EDITOR` on the project directory
+    #[default]
+    Editor,
+    /// Interactive `$SHELL` started in the project directory
+    Terminal,
+    /// The project's page on the dashboard (`dashboard_url` in config)
+    Browser,
+}
+
+/// Process to launch, resolved from the environment
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Launch {
+    program: String,
+    args: Vec<String>,
+    /// Working directory (None = inherit)
+    dir: Option<PathBuf>,
+}
+
+/// Run the open command: launch `target` for the project and wait for it to exit
+pub fn run(
+    engine: &DiscoveryEngine,
+    project_name: &str,
+    target: OpenTarget,
+) -> Result<(), HegelPmError> {
+    let projects = engine.get_projects(false)?;
+    let project = projects
+        .iter()
+        .find(|p| p.matches_key(project_name))
+        .ok_or_else(|| HegelPmError::ProjectNotFound {
+            name: project_name.to_string(),
+            available: projects.iter().map(|p| p.name.clone()).collect(),
+        })?;
+
+    let launch = launch_for(
+        project,
+        target,
+        engine.config().dashboard_url.as_deref(),
+        |key| std::env::var(key).ok(),
+    )?;
+
+    if target == OpenTarget::Terminal {
+        eprintln!(
+            "Starting a shell in {} (exit to return)",
+            project.project_path.display()
+        );
+    }
+
+    let mut command = Command::new(&launch.program);
+    command.args(&launch.args);
+    if let Some(dir) = &launch.dir {
+        command.current_dir(dir);
+    }
+    let status = command.status().map_err(|e| {
+        HegelPmError::InvalidArgument(format!("Failed to launch '{}': {}", launch.program, e))
+    })?;
+    if !status.success() {
+        return Err(HegelPmError::Subprocess { failed: 1 });
+    }
+
+    Ok(())
+}
+
+/// Resolve what to launch for `target`, reading environment variables through `env`
+fn launch_for(
+    project: &DiscoveredProject,
+    target: OpenTarget,
+    dashboard_url: Option<&str>,
+    env: impl Fn(&str) -> Option<String>,
+) -> Result<Launch, HegelPmError> {
+    let env = |key: &str| env(key).filter(|value| !value.trim().is_empty());
+
+    // The working copy of a remote project only exists on its host
+    if target != OpenTarget::Browser && RemoteRoot::parse(&project.project_path).is_some() {
+        return Err(HegelPmError::InvalidArgument(format!(
+            "'{}' is a remote project ({}); only --browser works for it",
+            project.name,
+            project.project_path.display()
+        )));
+    }
+
+    match target {
+        OpenTarget::Editor => {
+            let Some(editor) = env("VISUAL").or_else(|| env("EDITOR")) else {
+                return Err(HegelPmError::InvalidArgument(
+                    "No editor configured (set $VISUAL or $EDITOR)".to_string(),
+                ));
+            };
+            // `EDITOR="code --wait"` style values carry their own arguments
+            let mut words = editor.split_whitespace().map(String::from);
+            let program = words.next().unwrap_or_default();
+            let mut args: Vec<String> = words.collect();
+            args.push(project.project_path.display().to_string());
+
+            Ok(Launch {
+                program,
+                args,
+                dir: Some(project.project_path.clone()),
+            })
+        }
+        OpenTarget::Terminal => {
+            let program = if cfg!(windows) {
+                env("COMSPEC").unwrap_or_else(|| "cmd.exe".to_string())
+            } else {
+                env("SHELL").unwrap_or_else(|| "/bin/sh".to_string())
+            };
+
+            Ok(Launch {
+                program,
+                args: Vec::new(),
+                dir: Some(project.project_path.clone()),
+            })
+        }
+        OpenTarget::Browser => {
+            let Some(base) = dashboard_url else {
+                return Err(HegelPmError::InvalidArgument(
+                    "No dashboard configured (set \"dashboard_url\" in ~/.config/hegel-pm/config.json)"
+                        .to_string(),
+                ));
+            };
+            let url = format!("{}/projects/{}", base.trim_end_matches('/'), project.id());
+
+            let (program, mut args) = match env("BROWSER") {
+                Some(browser) => (browser, Vec::new()),
+                None if cfg!(target_os = "macos") => ("open".to_string(), Vec::new()),
+                None if cfg!(windows) => (
+                    "cmd".to_string(),
+                    vec!["/C".to_string(), "start".to_string(), String::new()],
+                ),
+                None => ("xdg-open".to_string(), Vec::new()),
+            };
+            args.push(url);
+
+            Ok(Launch {
+                program,
+                args,
+                dir: None,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::fixture_projects;
+    use std::collections::HashMap;
+
+    fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        move |key| vars.get(key).cloned()
+    }
+
+    #[test]
+    fn test_open_editor() {
+        let alpha = &fixture_projects()[0];
+
+        let launch = launch_for(
+            alpha,
+            OpenTarget::Editor,
+            None,
+            env(&[("EDITOR", "code --wait"), ("VISUAL", " ")]),
+        )
+        .unwrap();
+        assert_eq!(launch.program, "code");
+        assert_eq!(launch.args, vec!["--wait", "/home/dev/Code/alpha"]);
+        assert_eq!(launch.dir, Some(PathBuf::from("/home/dev/Code/alpha")));
+
+        // $VISUAL wins over $EDITOR
+        let launch = launch_for(
+            alpha,
+            OpenTarget::Editor,
+            None,
+            env(&[("EDITOR", "vi"), ("VISUAL", "hx")]),
+        )
+        .unwrap();
+        assert_eq!(launch.program, "hx");
+
+        let err = launch_for(alpha, OpenTarget::Editor, None, env(&[])).unwrap_err();
+        assert!(err.to_string().contains("$EDITOR"));
+    }
+
+    #[test]
+    fn test_open_terminal() {
+        let alpha = &fixture_projects()[0];
+        let launch = launch_for(
+            alpha,
+            OpenTarget::Terminal,
+            None,
+            env(&[("SHELL", "/bin/zsh")]),
+        )
+        .unwrap();
+        assert!(launch.args.is_empty());
+        assert_eq!(launch.dir, Some(PathBuf::from("/home/dev/Code/alpha")));
+        if !cfg!(windows) {
+            assert_eq!(launch.program, "/bin/zsh");
+        }
+    }
+
+    #[test]
+    fn test_open_browser() {
+        let alpha = &fixture_projects()[0];
+        let launch = launch_for(
+            alpha,
+            OpenTarget::Browser,
+            Some("http://localhost:3000/"),
+            env(&[("BROWSER", "firefox")]),
+        )
+        .unwrap();
+        assert_eq!(launch.program, "firefox");
+        assert_eq!(
+            launch.args,
+            vec![format!("http://localhost:3000/projects/{}", alpha.id())]
+        );
+
+        let err = launch_for(alpha, OpenTarget::Browser, None, env(&[])).unwrap_err();
+        assert!(err.to_string().contains("dashboard_url"));
+    }
+
+    #[test]
+    fn test_open_remote_project() {
+        let mut remote = fixture_projects().remove(0);
+        remote.project_path = PathBuf::from("ssh://devbox/home/dev/Code/alpha");
+
+        assert!(launch_for(&remote, OpenTarget::Editor, None, env(&[("EDITOR", "vi")])).is_err());
+        assert!(launch_for(
+            &remote,
+            OpenTarget::Browser,
+            Some("http://localhost:3000"),
+            env(&[("BROWSER", "firefox")])
+        )
+        .is_ok());
+    }
+}
@@ -0,0 +1,83 @@
+use crate::cli::discover::format::OutputContext;
+use crate::cli::HegelPmError;
+use crate::discovery::{DiscoveredProject, DiscoveryEngine};
+use dialoguer::theme::ColorfulTheme;
+use dialoguer::FuzzySelect;
+use std::io::{self, IsTerminal};
+
+/// Ask the user to pick a cached project (fuzzy search, most recently active first)
+///
+/// Used when `discover show`, `remove`, or `refresh --pick` run without a project name.
+/// Fails instead of prompting when stdin or stderr isn't a terminal, so scripts get an error.
+pub fn pick_project(engine: &DiscoveryEngine) -> Result<String, HegelPmError> {
+    if !io::stdin().is_terminal() || !io::stderr().is_terminal() {
+        return Err(HegelPmError::InvalidArgument(
+            "Project name required (interactive picker needs a terminal)".to_string(),
+        ));
+    }
+
+    let mut projects = engine.get_projects(false)?;
+    if projects.is_empty() {
+        return Err(HegelPmError::InvalidArgument(
+            "No projects to pick from (run `hegel-pm discover list` to scan)".to_string(),
+        ));
+    }
+    projects.sort();
+
+    let labels = pick_labels(&projects, &OutputContext::system());
+    let selection = FuzzySelect::with_theme(&ColorfulTheme::default())
+        .with_prompt("Project")
+        .items(&labels)
+        .default(0)
+        .interact_opt()
+        .map_err(|e| HegelPmError::Other(e.into()))?;
+
+    match selection {
+        Some(index) => Ok(projects[index].name.clone()),
+        None => Err(HegelPmError::InvalidArgument(
+            "No project selected".to_string(),
+        )),
+    }
+}
+
+/// Picker line per project: name, abbreviated path, last activity (archived ones marked)
+fn pick_labels(projects: &[DiscoveredProject], ctx: &OutputContext) -> Vec<String> {
+    let name_width = projects.iter().map(|p| p.name.len()).max().unwrap_or(0);
+
+    projects
+        .iter()
+        .map(|p| {
+            let mut label = format!(
+                "{:<width$}  {}  {}",
+                p.name,
+                ctx.format_timestamp(p.last_activity),
+                ctx.abbreviate_path(&p.project_path),
+                width = name_width
+            );
+            if p.archived {
+                label.push_str("  (archived)");
+            }
+            label
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::fixture_projects;
+
+    #[test]
+    fn test_pick_labels() {
+        let mut projects = fixture_projects();
+        projects[1].archived = true;
+        projects.sort();
+
+        let labels = pick_labels(&projects, &OutputContext::fixed()).join("\n");
+        insta::assert_snapshot!(labels, @r"
+        alpha         2023-11-14 22:13:20  ~/Code/alpha
+        beta-service  2023-11-13 18:26:40  ~/Code/work/beta-service  (archived)
+        gamma         2023-11-03 08:26:40  /srv/gamma
+        ");
+    }
+}
@@ -0,0 +1,297 @@
+use crate::cli::discover::format::OutputContext;
+use crate::cli::HegelPmError;
+use crate::discovery::{
+    budget_notifications, budget_statuses, current_month, open_store, post_webhook,
+    preview_refresh, refresh_all_projects_with_progress, refresh_project, refresh_project_diff,
+    webhook_notifications, BudgetStatus, DiscoveryConfig, ProgressEvent, ProgressSink, RefreshDiff,
+};
+use chrono::Utc;
+use std::io::{self, Write};
+
+/// How the refresh command reports and applies changes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefreshMode {
+    /// Refresh and print one line per project
+    Apply,
+    /// Refresh and print a before/after summary per project
+    Diff,
+    /// Print what would change, write nothing
+    DryRun,
+}
+
+/// Run the refresh command for the named projects (all cached projects if empty)
+pub fn run(
+    config: &DiscoveryConfig,
+    project_names: &[String],
+    mode: RefreshMode,
+    progress: &dyn ProgressSink,
+) -> Result<(), HegelPmError> {
+    let ctx = OutputContext::system();
+    let mut out = io::stdout().lock();
+
+    if mode == RefreshMode::Apply && project_names.is_empty() && config.webhooks.is_empty() {
+        // Refresh all cached projects (webhooks need a per-project diff, so they take the loop)
+        let count = refresh_all_projects_with_progress(config, progress)?;
+        writeln!(out, "✓ Refreshed {} project(s)", count)?;
+        return Ok(());
+    }
+
+    let names = if project_names.is_empty() {
+        let index = open_store(config)?.load_index()?.ok_or_else(|| {
+            anyhow::anyhow!("No cache found. Run 'hegel-pm discover list' first to populate cache.")
+        })?;
+        index.into_iter().map(|entry| entry.name).collect()
+    } else {
+        project_names.to_vec()
+    };
+
+    if mode == RefreshMode::DryRun {
+        writeln!(out, "Dry run: cache not modified\n")?;
+    }
+
+    // Budget usage before refreshing, to notice thresholds the refreshes cross
+    let budgets_before = if mode != RefreshMode::DryRun && !config.webhooks.is_empty() {
+        cached_budget_statuses(config)
+    } else {
+        None
+    };
+
+    let mut success_count = 0;
+    let mut failed = Vec::new();
+
+    for (i, project_name) in names.iter().enumerate() {
+        let result = refresh_one(config, project_name, mode, &ctx, &mut out);
+
+        // Dry runs don't refresh anything, so there is no progress to report
+        if mode != RefreshMode::DryRun {
+            progress.emit(ProgressEvent::ProjectRefreshed {
+                name: project_name.clone(),
+                current: i + 1,
+                total: names.len(),
+                ok: result.is_ok(),
+            });
+        }
+
+        match result {
+            Ok(true) => success_count += 1,
+            Ok(false) => {}
+            Err(e) => {
+                eprintln!("✗ Failed to refresh '{}': {}", project_name, e);
+                failed.push(project_name.clone());
+            }
+        }
+    }
+
+    if let Some(before) = budgets_before {
+        let after = cached_budget_statuses(config).unwrap_or_default();
+        for (url, payload) in budget_notifications(&config.webhooks, &before, &after) {
+            if let Err(e) = post_webhook(&url, &payload) {
+                eprintln!("Warning: {:#}", e);
+            }
+        }
+    }
+
+    if !failed.is_empty() {
+        return Err(anyhow::anyhow!("Failed to refresh {} project(s)", failed.len()).into());
+    }
+
+    if success_count > 0 && mode != RefreshMode::DryRun {
+        writeln!(
+            out,
+            "\n✓ Successfully refreshed {} project(s)",
+            success_count
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Refresh (or preview) one project, returning whether it was refreshed
+fn refresh_one(
+    config: &DiscoveryConfig,
+    project_name: &str,
+    mode: RefreshMode,
+    ctx: &OutputContext,
+    out: &mut dyn Write,
+) -> Result<bool, HegelPmError> {
+    match mode {
+        RefreshMode::Apply if !config.webhooks.is_empty() => {
+            match refresh_project_diff(project_name, config)? {
+                Some(diff) => {
+                    writeln!(out, "✓ Refreshed '{}'", project_name)?;
+                    notify_webhooks(config, &diff);
+                    Ok(true)
+                }
+                None => {
+                    writeln!(out, "{}", skipped(project_name))?;
+                    Ok(false)
+                }
+            }
+        }
+        RefreshMode::Apply => {
+            let refreshed = refresh_project(project_name, config)?;
+            if refreshed {
+                writeln!(out, "✓ Refreshed '{}'", project_name)?;
+            } else {
+                writeln!(out, "{}", skipped(project_name))?;
+            }
+            Ok(refreshed)
+        }
+        RefreshMode::Diff => match refresh_project_diff(project_name, config)? {
+            Some(diff) => {
+                output_diff(&diff, ctx, out)?;
+                notify_webhooks(config, &diff);
+                Ok(true)
+            }
+            None => {
+                writeln!(out, "{}", skipped(project_name))?;
+                Ok(false)
+            }
+        },
+        RefreshMode::DryRun => {
+            output_diff(&preview_refresh(project_name, config)?, ctx, out)?;
+            Ok(true)
+        }
+    }
+}
+
+/// POST configured webhooks for a completed refresh (failures are warnings: the cache is written)
+fn notify_webhooks(config: &DiscoveryConfig, diff: &RefreshDiff) {
+    for (url, payload) in webhook_notifications(&config.webhooks, diff) {
+        if let Err(e) = post_webhook(&url, &payload) {
+            eprintln!("Warning: {:#}", e);
+        }
+    }
+}
+
+/// This month's budget statuses from the cached summaries (None without budgets or a cache)
+fn cached_budget_statuses(config: &DiscoveryConfig) -> Option<Vec<BudgetStatus>> {
+    if config.budget.is_none() && config.project_budgets.is_empty() {
+        return None;
+    }
+    let projects = open_store(config).ok()?.load_all().ok()??;
+    Some(budget_statuses(
+        &projects,
+        config,
+        &current_month(Utc::now()),
+    ))
+}
+
+fn skipped(project_name: &str) -> String {
+    format!(
+        "- Skipped '{}' (refreshed recently or refresh in progress)",
+        project_name
+    )
+}
+
+/// Print a project's before/after summary (only the fields that change)
+fn output_diff(
+    diff: &RefreshDiff,
+    ctx: &OutputContext,
+    out: &mut dyn Write,
+) -> Result<(), HegelPmError> {
+    if !diff.is_changed() {
+        writeln!(out, "{}: unchanged", diff.name)?;
+        return Ok(());
+    }
+
+    writeln!(out, "{}:", diff.name)?;
+    let Some(before) = &diff.before else {
+        writeln!(out, "  (not cached) → will be written")?;
+        return Ok(());
+    };
+    let after = &diff.after;
+
+    let or_none = |value: &Option<String>| value.clone().unwrap_or_else(|| "none".to_string());
+
+    if before.last_activity != after.last_activity {
+        writeln!(
+            out,
+            "  last activity: {} → {}",
+            ctx.format_timestamp(before.last_activity),
+            ctx.format_timestamp(after.last_activity)
+        )?;
+    }
+    if before.workflow_node != after.workflow_node {
+        writeln!(
+            out,
+            "  workflow node: {} → {}",
+            or_none(&before.workflow_node),
+            or_none(&after.workflow_node)
+        )?;
+    }
+    if before.phase_count != after.phase_count {
+        writeln!(
+            out,
+            "  phases: {} → {}",
+            before.phase_count, after.phase_count
+        )?;
+    }
+    if before.error != after.error {
+        writeln!(
+            out,
+            "  error: {} → {}",
+            or_none(&before.error),
+            or_none(&after.error)
+        )?;
+    }
+    if before.git != after.git {
+        writeln!(
+            out,
+            "  git: {} → {}",
+            or_none(&before.git),
+            or_none(&after.git)
+        )?;
+    }
+    if before.git_head != after.git_head {
+        let short = |head: &Option<String>| {
+            head.as_deref()
+                .map_or_else(|| "none".to_string(), |h| h.chars().take(7).collect())
+        };
+        writeln!(
+            out,
+            "  git head: {} → {}",
+            short(&before.git_head),
+            short(&after.git_head)
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::fixture_projects;
+
+    fn render(diff: &RefreshDiff) -> String {
+        let mut out = Vec::new();
+        output_diff(diff, &OutputContext::fixed(), &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_snapshot_diff_changed() {
+        let projects = fixture_projects();
+        let mut before = projects[0].clone();
+        before.workflow_state = None;
+        before.last_activity = projects[1].last_activity;
+        before.git_info = None;
+
+        let diff = RefreshDiff::new("alpha", Some(&before), &projects[0]);
+        insta::assert_snapshot!(render(&diff), @r"
+        alpha:
+          last activity: 2023-11-13 18:26:40 → 2023-11-14 22:13:20
+          workflow node: none → code
+          phases: 0 → 3
+          git: none → main* ↑2
+        ");
+    }
+
+    #[test]
+    fn test_snapshot_diff_unchanged() {
+        let projects = fixture_projects();
+        let diff = RefreshDiff::new("gamma", Some(&projects[2]), &projects[2]);
+        insta::assert_snapshot!(render(&diff), @"gamma: unchanged");
+    }
+}
@@ -0,0 +1,381 @@
+use crate::cli::discover::format::{format_duration_secs, OutputContext};
+use crate::cli::HegelPmError;
+use crate::discovery::{total_tokens, DiscoveredProject, DiscoveryEngine, ProjectMetricsSummary};
+use crate::warn;
+use clap::ValueEnum;
+use std::fs;
+use std::path::Path;
+
+/// Report document format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ReportFormat {
+    /// Markdown (paste into notes, PRs, wikis)
+    Md,
+    /// Standalone HTML page
+    Html,
+}
+
+/// Run the report command
+pub fn run(
+    engine: &DiscoveryEngine,
+    project_name: &str,
+    format: ReportFormat,
+    output: Option<&Path>,
+) -> Result<(), HegelPmError> {
+    let mut projects = engine.get_projects(false)?;
+    let project = projects
+        .iter_mut()
+        .find(|p| p.matches_key(project_name))
+        .ok_or_else(|| HegelPmError::not_found(project_name))?;
+
+    // Unreadable logs still get a report (workflow, git), flagged as incomplete
+    let statistics_error = project.load_statistics().err();
+    if let Some(e) = &statistics_error {
+        warn!("failed to load statistics for '{}': {:#}", project.name, e);
+    }
+
+    let mut report = Report::build(project, &OutputContext::system());
+    if let Some(e) = &statistics_error {
+        report.mark_incomplete(e);
+    }
+    let rendered = match format {
+        ReportFormat::Md => report.to_markdown(),
+        ReportFormat::Html => report.to_html(),
+    };
+
+    match output {
+        Some(path) => {
+            fs::write(path, rendered)?;
+            println!(
+                "✓ Wrote report for '{}' to {}",
+                project_name,
+                path.display()
+            );
+        }
+        None => print!("{}", rendered),
+    }
+
+    Ok(())
+}
+
+/// Simple table: header row plus string cells
+pub(crate) struct Table {
+    pub(crate) headers: Vec<&'static str>,
+    pub(crate) rows: Vec<Vec<String>>,
+}
+
+/// Format-independent report content (also used by `digest`)
+pub(crate) struct Report {
+    pub(crate) title: String,
+    /// Key/value summary lines shown under the title
+    pub(crate) facts: Vec<(&'static str, String)>,
+    /// Titled sections; `None` renders the fallback message instead of a table
+    pub(crate) sections: Vec<(&'static str, Option<Table>)>,
+}
+
+impl Report {
+    fn build(project: &DiscoveredProject, ctx: &OutputContext) -> Self {
+        let mut facts = vec![
+            ("Path", ctx.abbreviate_path(&project.project_path)),
+            ("Last activity", ctx.format_timestamp(project.last_activity)),
+        ];
+
+        if let Some(error) = &project.error {
+            facts.push(("Workflow", format!("error loading state ({})", error)));
+        } else if let Some(state) = &project.workflow_state {
+            facts.push((
+                "Workflow",
+                format!("{} mode, at {}", state.mode, state.current_node),
+            ));
+            facts.push(("History", state.history.join(" → ")));
+        } else {
+            facts.push(("Workflow", "none".to_string()));
+        }
+
+        if let Some(git) = &project.git_info {
+            facts.push(("Git", git.summary()));
+        }
+
+        let sections = match &project.statistics {
+            Some(stats) => {
+                let summary = ProjectMetricsSummary::from(stats);
+                vec![
+                    ("Metrics", Some(metrics_table(&summary))),
+                    ("Phases", phases_table(project)),
+                    ("Modes", modes_table(&summary)),
+                ]
+            }
+            None => vec![("Metrics", None)],
+        };
+
+        Self {
+            title: format!("Project report: {}", project.name),
+            facts,
+            sections,
+        }
+    }
+
+    /// Note that metrics are missing because the statistics failed to load, not because none
+    /// were recorded
+    fn mark_incomplete(&mut self, error: &anyhow::Error) {
+        self.facts.push((
+            "Incomplete",
+            format!("statistics failed to load ({:#})", error),
+        ));
+    }
+
+    pub(crate) fn to_markdown(&self) -> String {
+        let mut out = format!("# {}\n\n", self.title);
+        for (key, value) in &self.facts {
+            out.push_str(&format!("- **{}**: {}\n", key, value));
+        }
+
+        for (title, table) in &self.sections {
+            out.push_str(&format!("\n## {}\n\n", title));
+            match table {
+                Some(table) => {
+                    out.push_str(&format!("| {} |\n", table.headers.join(" | ")));
+                    out.push_str(&format!("|{}\n", "---|".repeat(table.headers.len())));
+                    for row in &table.rows {
+                        let cells: Vec<String> =
+                            row.iter().map(|c| c.replace('|', "\\|")).collect();
+                        out.push_str(&format!("| {} |\n", cells.join(" | ")));
+                    }
+                }
+                None => out.push_str("None recorded\n"),
+            }
+        }
+
+        out
+    }
+
+    pub(crate) fn to_html(&self) -> String {
+        let mut out = String::from("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+        out.push_str(&format!("<title>{}</title>\n", escape_html(&self.title)));
+        out.push_str(
+            "<style>body{font-family:sans-serif;margin:2em}\
+             table{border-collapse:collapse}\
+             th,td{border:1px solid #ccc;padding:4px 8px;text-align:left}</style>\n",
+        );
+        out.push_str("</head>\n<body>\n");
+        out.push_str(&format!("<h1>{}</h1>\n<ul>\n", escape_html(&self.title)));
+        for (key, value) in &self.facts {
+            out.push_str(&format!(
+                "<li><strong>{}</strong>: {}</li>\n",
+                key,
+                escape_html(value)
+            ));
+        }
+        out.push_str("</ul>\n");
+
+        for (title, table) in &self.sections {
+            out.push_str(&format!("<h2>{}</h2>\n", title));
+            match table {
+                Some(table) => {
+                    out.push_str("<table>\n<tr>");
+                    for header in &table.headers {
+                        out.push_str(&format!("<th>{}</th>", header));
+                    }
+                    out.push_str("</tr>\n");
+                    for row in &table.rows {
+                        out.push_str("<tr>");
+                        for cell in row {
+                            out.push_str(&format!("<td>{}</td>", escape_html(cell)));
+                        }
+                        out.push_str("</tr>\n");
+                    }
+                    out.push_str("</table>\n");
+                }
+                None => out.push_str("<p>None recorded</p>\n"),
+            }
+        }
+
+        out.push_str("</body>\n</html>\n");
+        out
+    }
+}
+
+fn metrics_table(summary: &ProjectMetricsSummary) -> Table {
+    let row = |name: &str, value: String| vec![name.to_string(), value];
+    Table {
+        headers: vec!["Metric", "Value"],
+        rows: vec![
+            row("Total tokens", summary.total_all_tokens.to_string()),
+            row("Input tokens", summary.total_input_tokens.to_string()),
+            row("Output tokens", summary.total_output_tokens.to_string()),
+            row(
+                "Cache creation tokens",
+                summary.total_cache_creation_tokens.to_string(),
+            ),
+            row(
+                "Cache read tokens",
+                summary.total_cache_read_tokens.to_string(),
+            ),
+            row("Events", summary.total_events.to_string()),
+            row("Bash commands", summary.bash_command_count.to_string()),
+            row(
+                "File modifications",
+                summary.file_modification_count.to_string(),
+            ),
+            row("Git commits", summary.git_commit_count.to_string()),
+            row("Phases", summary.phase_count.to_string()),
+        ],
+    }
+}
+
+/// One row per phase in recorded order (None if the project has no phases)
+fn phases_table(project: &DiscoveredProject) -> Option<Table> {
+    let stats = project.statistics.as_ref()?;
+    if stats.phase_metrics.is_empty() {
+        return None;
+    }
+
+    let rows = stats
+        .phase_metrics
+        .iter()
+        .map(|phase| {
+            let duration = if phase.end_time.is_some() {
+                format_duration_secs(phase.duration_seconds)
+            } else {
+                "in progress".to_string()
+            };
+            vec![
+                phase.phase_name.clone(),
+                phase.start_time.clone(),
+                duration,
+                total_tokens(&phase.token_metrics).to_string(),
+                phase.bash_commands.len().to_string(),
+                phase.file_modifications.len().to_string(),
+            ]
+        })
+        .collect();
+
+    Some(Table {
+        headers: vec!["Phase", "Started", "Duration", "Tokens", "Bash", "Files"],
+        rows,
+    })
+}
+
+/// One row per workflow mode (None if no phases were attributed to a mode)
+fn modes_table(summary: &ProjectMetricsSummary) -> Option<Table> {
+    if summary.per_mode.is_empty() {
+        return None;
+    }
+
+    Some(Table {
+        headers: vec!["Mode", "Phases", "Tokens", "Events"],
+        rows: summary
+            .per_mode
+            .iter()
+            .map(|(mode, metrics)| {
+                vec![
+                    mode.clone(),
+                    metrics.phase_count.to_string(),
+                    metrics.total_tokens.to_string(),
+                    metrics.total_events.to_string(),
+                ]
+            })
+            .collect(),
+    })
+}
+
+/// Escape text for inclusion in HTML element content
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::DiscoveryConfig;
+    use crate::test_helpers::fixture_projects;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_markdown_without_metrics() {
+        let projects = fixture_projects();
+        let report = Report::build(&projects[0], &OutputContext::fixed());
+
+        insta::assert_snapshot!(report.to_markdown(), @r"
+        # Project report: alpha
+
+        - **Path**: ~/Code/alpha
+        - **Last activity**: 2023-11-14 22:13:20
+        - **Workflow**: discovery mode, at code
+        - **History**: spec → plan → code
+        - **Git**: main* ↑2
+
+        ## Metrics
+
+        None recorded
+        ");
+    }
+
+    #[test]
+    fn test_markdown_marks_failed_statistics() {
+        let projects = fixture_projects();
+        let mut report = Report::build(&projects[1], &OutputContext::fixed());
+        report.mark_incomplete(&anyhow::anyhow!("hooks.jsonl: invalid JSON at line 3"));
+        let md = report.to_markdown();
+
+        assert!(md.contains(
+            "- **Incomplete**: statistics failed to load (hooks.jsonl: invalid JSON at line 3)\n"
+        ));
+        assert!(md.ends_with("## Metrics\n\nNone recorded\n"));
+    }
+
+    #[test]
+    fn test_markdown_table_escapes_pipes() {
+        let report = Report {
+            title: "t".to_string(),
+            facts: vec![],
+            sections: vec![(
+                "Phases",
+                Some(Table {
+                    headers: vec!["Phase", "Tokens"],
+                    rows: vec![vec!["a|b".to_string(), "10".to_string()]],
+                }),
+            )],
+        };
+
+        let md = report.to_markdown();
+        assert!(md.contains("| Phase | Tokens |\n|---|---|\n"));
+        assert!(md.contains("| a\\|b | 10 |"));
+    }
+
+    #[test]
+    fn test_html_escapes_content() {
+        let projects = fixture_projects();
+        let report = Report::build(&projects[2], &OutputContext::fixed());
+        let html = report.to_html();
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<h1>Project report: gamma</h1>"));
+        assert!(html.contains("<p>None recorded</p>"));
+        assert_eq!(escape_html("<a & \"b\">"), "&lt;a &amp; &quot;b&quot;&gt;");
+    }
+
+    #[test]
+    fn test_run_report_to_file() {
+        let temp = TempDir::new().unwrap();
+        std::fs::create_dir_all(temp.path().join("project1/.hegel")).unwrap();
+        let config = DiscoveryConfig::new(
+            vec![temp.path().to_path_buf()],
+            10,
+            vec![],
+            temp.path().join("cache.json"),
+        );
+        let engine = DiscoveryEngine::new(config).unwrap();
+
+        let output = temp.path().join("report.html");
+        run(&engine, "project1", ReportFormat::Html, Some(&output)).unwrap();
+        let html = std::fs::read_to_string(&output).unwrap();
+        assert!(html.contains("Project report: project1"));
+
+        let result = run(&engine, "missing", ReportFormat::Md, None);
+        assert!(result.unwrap_err().to_string().contains("not found"));
+    }
+}
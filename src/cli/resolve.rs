@@ -0,0 +1,151 @@
+use crate::cli::HegelPmError;
+use crate::discovery::{search_projects, DiscoveredProject, DiscoveryEngine};
+use dialoguer::theme::ColorfulTheme;
+use dialoguer::{Confirm, Select};
+use std::io::{self, IsTerminal};
+
+/// Most candidates offered when a name is ambiguous
+const MAX_CANDIDATES: usize = 10;
+
+/// How a project-name argument matched the known projects
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Resolution {
+    /// Name or ID matched one project exactly
+    Exact(String),
+    /// No exact match; these names match by prefix/substring/path/fuzzy, best first
+    Candidates(Vec<String>),
+}
+
+/// Resolve a project-name argument to a known project name
+///
+/// An exact name or ID wins, then a case-insensitive name. Otherwise prefix, substring, path,
+/// and fuzzy matches (see `search_projects`) are offered on the terminal: a single candidate is
+/// confirmed ("did you mean ...?"), several are picked from a list. Without a terminal that's a
+/// not-found error listing the candidates. With `exact` only the exact name or ID is accepted.
+pub fn resolve_project_name(
+    engine: &DiscoveryEngine,
+    query: &str,
+    exact: bool,
+) -> Result<String, HegelPmError> {
+    let projects = engine.get_projects(false)?;
+    let not_found = |available: Vec<String>| HegelPmError::ProjectNotFound {
+        name: query.to_string(),
+        available,
+    };
+
+    if exact {
+        return projects
+            .iter()
+            .find(|p| p.matches_key(query))
+            .map(|p| p.name.clone())
+            .ok_or_else(|| not_found(projects.iter().map(|p| p.name.clone()).collect()));
+    }
+
+    let candidates = match resolve(&projects, query) {
+        Resolution::Exact(name) => return Ok(name),
+        Resolution::Candidates(candidates) => candidates,
+    };
+
+    let interactive = io::stdin().is_terminal() && io::stderr().is_terminal();
+    if candidates.is_empty() {
+        return Err(not_found(projects.iter().map(|p| p.name.clone()).collect()));
+    }
+    if !interactive {
+        return Err(not_found(candidates));
+    }
+
+    let theme = ColorfulTheme::default();
+    let chosen = if let [only] = candidates.as_slice() {
+        Confirm::with_theme(&theme)
+            .with_prompt(format!("No project '{}'. Did you mean '{}'?", query, only))
+            .default(true)
+            .interact_opt()
+            .map_err(|e| HegelPmError::Other(e.into()))?
+            .unwrap_or(false)
+            .then(|| only.clone())
+    } else {
+        Select::with_theme(&theme)
+            .with_prompt(format!("No project '{}'. Did you mean", query))
+            .items(&candidates)
+            .default(0)
+            .interact_opt()
+            .map_err(|e| HegelPmError::Other(e.into()))?
+            .map(|index| candidates[index].clone())
+    };
+
+    chosen.ok_or_else(|| HegelPmError::InvalidArgument("No project selected".to_string()))
+}
+
+/// Match `query` against projects without prompting
+fn resolve(projects: &[DiscoveredProject], query: &str) -> Resolution {
+    if let Some(project) = projects.iter().find(|p| p.matches_key(query)) {
+        return Resolution::Exact(project.name.clone());
+    }
+
+    // A case-insensitive name match is as good as exact, unless it's ambiguous
+    let same_name: Vec<&DiscoveredProject> = projects
+        .iter()
+        .filter(|p| p.name.eq_ignore_ascii_case(query))
+        .collect();
+    if let [only] = same_name.as_slice() {
+        return Resolution::Exact(only.name.clone());
+    }
+
+    let matches = search_projects(projects, query);
+    Resolution::Candidates(
+        matches
+            .into_iter()
+            .take(MAX_CANDIDATES)
+            .map(|p| p.name.clone())
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::fixture_projects;
+
+    #[test]
+    fn test_resolve_exact_name_or_id() {
+        let projects = fixture_projects();
+        assert_eq!(
+            resolve(&projects, "alpha"),
+            Resolution::Exact("alpha".to_string())
+        );
+        assert_eq!(
+            resolve(&projects, &projects[1].id()),
+            Resolution::Exact("beta-service".to_string())
+        );
+        assert_eq!(
+            resolve(&projects, "Gamma"),
+            Resolution::Exact("gamma".to_string())
+        );
+    }
+
+    #[test]
+    fn test_resolve_candidates() {
+        let projects = fixture_projects();
+
+        // Prefix
+        assert_eq!(
+            resolve(&projects, "bet"),
+            Resolution::Candidates(vec!["beta-service".to_string()])
+        );
+        // Fuzzy
+        assert_eq!(
+            resolve(&projects, "bsvc"),
+            Resolution::Candidates(vec!["beta-service".to_string()])
+        );
+        // Several, best match first: "a" is a prefix of alpha, a substring of the others
+        assert_eq!(
+            resolve(&projects, "a"),
+            Resolution::Candidates(vec![
+                "alpha".to_string(),
+                "beta-service".to_string(),
+                "gamma".to_string(),
+            ])
+        );
+        assert_eq!(resolve(&projects, "zzz"), Resolution::Candidates(vec![]));
+    }
+}
@@ -0,0 +1,122 @@
+use crate::cli::HegelPmError;
+use crate::discovery::{DiscoveryEngine, MetricsSnapshot, SnapshotStore};
+use chrono::{Local, Utc};
+use serde::Serialize;
+
+/// Run the snapshot command: record a new snapshot, or list recorded history
+pub fn run(
+    engine: &DiscoveryEngine,
+    store: &SnapshotStore,
+    list: bool,
+    json: bool,
+) -> Result<(), HegelPmError> {
+    if list {
+        return list_history(store, json);
+    }
+
+    let mut projects = engine.get_projects(false)?;
+    for project in &mut projects {
+        let _ = project.load_statistics(); // Ignore errors, project is omitted from summaries
+    }
+
+    let snapshot = MetricsSnapshot::capture(&projects, Utc::now());
+    let path = store.record(&snapshot)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&snapshot)?);
+    } else {
+        println!(
+            "✓ Recorded snapshot of {} project(s) ({} tokens) to {}",
+            snapshot.project_count,
+            snapshot.total_tokens,
+            path.display()
+        );
+    }
+
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct HistoryRowJson {
+    taken_at: String,
+    project_count: usize,
+    total_tokens: u64,
+    total_events: usize,
+    total_phases: usize,
+}
+
+fn list_history(store: &SnapshotStore, json: bool) -> Result<(), HegelPmError> {
+    let history = store.history(None, None)?;
+
+    if json {
+        let rows: Vec<HistoryRowJson> = history
+            .iter()
+            .map(|s| HistoryRowJson {
+                taken_at: s.taken_at.to_rfc3339(),
+                project_count: s.project_count,
+                total_tokens: s.total_tokens,
+                total_events: s.total_events,
+                total_phases: s.total_phases,
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&rows)?);
+        return Ok(());
+    }
+
+    if history.is_empty() {
+        println!("No snapshots recorded in {}", store.location().display());
+        return Ok(());
+    }
+
+    println!(
+        "{:<19}  {:>8}  {:>12}  {:>8}  {:>7}",
+        "TAKEN AT", "PROJECTS", "TOKENS", "EVENTS", "PHASES"
+    );
+    for snapshot in &history {
+        println!(
+            "{:<19}  {:>8}  {:>12}  {:>8}  {:>7}",
+            snapshot
+                .taken_at
+                .with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string(),
+            snapshot.project_count,
+            snapshot.total_tokens,
+            snapshot.total_events,
+            snapshot.total_phases
+        );
+    }
+    println!("\n{} snapshots recorded", history.len());
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::DiscoveryConfig;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_record_then_list() {
+        let temp = TempDir::new().unwrap();
+        fs::create_dir_all(temp.path().join("project1/.hegel")).unwrap();
+        let config = DiscoveryConfig::new(
+            vec![temp.path().to_path_buf()],
+            10,
+            vec![],
+            temp.path().join("cache.json"),
+        );
+        let engine = DiscoveryEngine::new(config).unwrap();
+        let store = SnapshotStore::new(temp.path().join("snapshots"));
+
+        run(&engine, &store, false, false).unwrap();
+        let history = store.history(None, None).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].project_count, 1);
+
+        assert!(run(&engine, &store, true, false).is_ok());
+        assert!(run(&engine, &store, true, true).is_ok());
+    }
+}
@@ -0,0 +1,180 @@
+use crate::cli::discover::format::OutputContext;
+use crate::cli::HegelPmError;
+use crate::discovery::{
+    matches_filters, DiscoveryEngine, EventFilter, HookEvent, LogFollower, TAIL_FILES,
+};
+use chrono::DateTime;
+use console::style;
+use std::io::{self, Write};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// How often followed files are checked for new lines
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Run the tail command: print the last `lines` matching events, then follow new ones until
+/// interrupted
+///
+/// Remote projects are read from their local mirror, which only changes on refresh.
+pub fn run(
+    engine: &DiscoveryEngine,
+    project_name: &str,
+    filters: &[String],
+    lines: usize,
+) -> Result<(), HegelPmError> {
+    let filters = filters
+        .iter()
+        .map(|expression| EventFilter::parse(expression))
+        .collect::<anyhow::Result<Vec<_>>>()
+        .map_err(|e| HegelPmError::InvalidArgument(e.to_string()))?;
+
+    let projects = engine.get_projects(false)?;
+    let project = projects
+        .iter()
+        .find(|p| p.matches_key(project_name))
+        .ok_or_else(|| HegelPmError::ProjectNotFound {
+            name: project_name.to_string(),
+            available: projects.iter().map(|p| p.name.clone()).collect(),
+        })?;
+
+    // Without a backlog there's no need to read the existing logs at all
+    let mut followers: Vec<LogFollower> = TAIL_FILES
+        .iter()
+        .map(|file| {
+            let path = project.hegel_dir.join(file);
+            if lines == 0 {
+                LogFollower::from_end(&path)
+            } else {
+                LogFollower::from_start(&path)
+            }
+        })
+        .collect();
+
+    let ctx = OutputContext::system();
+    let color = console::colors_enabled();
+    let mut out = io::stdout().lock();
+
+    let mut backlog = Vec::new();
+    for follower in &mut followers {
+        backlog.extend(matching_events(follower.poll()?, &filters));
+    }
+    // Merge both files by time (stable: untimestamped events sort first, in file order)
+    backlog.sort_by(|a, b| a.timestamp().cmp(&b.timestamp()));
+    for event in &backlog[backlog.len().saturating_sub(lines)..] {
+        writeln!(out, "{}", format_event(event, &ctx, color))?;
+    }
+    out.flush()?;
+
+    loop {
+        thread::sleep(POLL_INTERVAL);
+        for follower in &mut followers {
+            for event in matching_events(follower.poll()?, &filters) {
+                writeln!(out, "{}", format_event(&event, &ctx, color))?;
+            }
+        }
+        out.flush()?;
+    }
+}
+
+fn matching_events(lines: Vec<String>, filters: &[EventFilter]) -> Vec<HookEvent> {
+    lines
+        .iter()
+        .filter_map(|line| HookEvent::parse(line))
+        .filter(|event| matches_filters(event, filters))
+        .collect()
+}
+
+/// One output line: time of day, then the event (ANSI-colored when `color` is set)
+fn format_event(event: &HookEvent, ctx: &OutputContext, color: bool) -> String {
+    let time = event
+        .timestamp()
+        .and_then(|ts| DateTime::parse_from_rfc3339(ts).ok())
+        .map(|ts| ctx.format_time_of_day(SystemTime::from(ts)))
+        .unwrap_or_else(|| "--:--:--".to_string());
+
+    let body = match event {
+        HookEvent::Bash { command, .. } => format!(
+            "{} {}",
+            style("$").green().bold().force_styling(color),
+            command
+        ),
+        HookEvent::FileEdit {
+            tool, file_path, ..
+        } => format!(
+            "{} {}",
+            style(format!("{:<5}", tool)).yellow().force_styling(color),
+            ctx.abbreviate_path(std::path::Path::new(file_path))
+        ),
+        HookEvent::Transition {
+            from_node,
+            to_node,
+            mode,
+            ..
+        } => {
+            let mode = mode
+                .as_deref()
+                .map(|m| format!(" ({})", m))
+                .unwrap_or_default();
+            style(format!("→ {} → {}{}", from_node, to_node, mode))
+                .cyan()
+                .bold()
+                .force_styling(color)
+                .to_string()
+        }
+        HookEvent::Other { name, .. } => style(name).dim().force_styling(color).to_string(),
+    };
+
+    format!("{}  {}", style(time).dim().force_styling(color), body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn events() -> Vec<HookEvent> {
+        [
+            r#"{"timestamp":"2023-11-14T22:10:00Z","from_node":"spec","to_node":"code","mode":"execution"}"#,
+            r#"{"timestamp":"2023-11-14T22:11:05Z","tool_name":"Bash","tool_input":{"command":"cargo test"}}"#,
+            r#"{"timestamp":"2023-11-14T22:12:40Z","tool_name":"Edit","tool_input":{"file_path":"/home/dev/Code/alpha/src/lib.rs"}}"#,
+            r#"{"hook_event_name":"Stop"}"#,
+        ]
+        .iter()
+        .filter_map(|line| HookEvent::parse(line))
+        .collect()
+    }
+
+    #[test]
+    fn test_snapshot_tail_events() {
+        let ctx = OutputContext::fixed();
+        let lines: Vec<String> = events()
+            .iter()
+            .map(|event| format_event(event, &ctx, false))
+            .collect();
+        insta::assert_snapshot!(lines.join("\n"), @r"
+        22:10:00  → spec → code (execution)
+        22:11:05  $ cargo test
+        22:12:40  Edit  ~/Code/alpha/src/lib.rs
+        --:--:--  Stop
+        ");
+    }
+
+    #[test]
+    fn test_tail_colors() {
+        let events = events();
+        let bash = &events[1];
+        let ctx = OutputContext::fixed();
+        assert!(format_event(bash, &ctx, true).contains("\u{1b}["));
+        assert!(!format_event(bash, &ctx, false).contains("\u{1b}["));
+    }
+
+    #[test]
+    fn test_matching_events_skips_malformed_lines() {
+        let filters = vec![EventFilter::parse("kind=bash").unwrap()];
+        let lines = vec![
+            r#"{"tool_name":"Bash","tool_input":{"command":"ls"}}"#.to_string(),
+            "{truncated".to_string(),
+            r#"{"tool_name":"Write","tool_input":{"file_path":"a.rs"}}"#.to_string(),
+        ];
+        assert_eq!(matching_events(lines, &filters).len(), 1);
+    }
+}
@@ -1,4 +1,175 @@
-/// Debug printing macro that only prints when DEBUG environment variable is set
+//! Diagnostic logging (`debug!`, `warn!`), kept apart from command output
+//!
+//! Diagnostics go to stderr, or to a file with `--log-file`, never stdout, so command output
+//! (tables, JSON) can be piped safely. Until `init` is called (library use, tests) warnings are
+//! printed and debug messages only when the `DEBUG` environment variable is set.
+
+use anyhow::{bail, Context, Result};
+use chrono::{SecondsFormat, Utc};
+use serde_json::json;
+use std::fmt;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::{Mutex, OnceLock};
+
+/// Message severity, most severe first (a level shows itself and everything above it)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+}
+
+impl LogLevel {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LogLevel::Error => "error",
+            LogLevel::Warn => "warn",
+            LogLevel::Info => "info",
+            LogLevel::Debug => "debug",
+        }
+    }
+}
+
+impl FromStr for LogLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "error" => Ok(LogLevel::Error),
+            "warn" | "warning" => Ok(LogLevel::Warn),
+            "info" => Ok(LogLevel::Info),
+            "debug" => Ok(LogLevel::Debug),
+            _ => Err(format!(
+                "Invalid log level '{}' (expected error, warn, info, or debug)",
+                s
+            )),
+        }
+    }
+}
+
+/// How log lines are written
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Plain text (`warning: ...` for warnings and errors)
+    #[default]
+    Pretty,
+    /// One JSON object per line: `{"ts", "level", "message"}`
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "pretty" => Ok(LogFormat::Pretty),
+            "json" => Ok(LogFormat::Json),
+            _ => Err(format!(
+                "Invalid log format '{}' (expected pretty or json)",
+                s
+            )),
+        }
+    }
+}
+
+struct Logger {
+    level: LogLevel,
+    format: LogFormat,
+    out: Mutex<Box<dyn Write + Send>>,
+}
+
+static LOGGER: OnceLock<Logger> = OnceLock::new();
+
+/// Configure logging for this process (once, before other work)
+///
+/// `level` None keeps the default: warnings, or everything when `DEBUG` is set. With `file`,
+/// lines are appended there instead of stderr.
+pub fn init(level: Option<LogLevel>, format: LogFormat, file: Option<&Path>) -> Result<()> {
+    let out: Box<dyn Write + Send> = match file {
+        Some(path) => Box::new(
+            OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .context(format!("Failed to open log file: {}", path.display()))?,
+        ),
+        None => Box::new(io::stderr()),
+    };
+
+    let logger = Logger {
+        level: level.unwrap_or_else(default_level),
+        format,
+        out: Mutex::new(out),
+    };
+    if LOGGER.set(logger).is_err() {
+        bail!("Logging is already initialized");
+    }
+    Ok(())
+}
+
+fn default_level() -> LogLevel {
+    if std::env::var("DEBUG").is_ok() {
+        LogLevel::Debug
+    } else {
+        LogLevel::Warn
+    }
+}
+
+/// Whether messages at `level` are written
+pub fn enabled(level: LogLevel) -> bool {
+    match LOGGER.get() {
+        Some(logger) => level <= logger.level,
+        None => level <= default_level(),
+    }
+}
+
+/// Write one message (used by the `debug!`/`warn!` macros)
+pub fn log(level: LogLevel, args: fmt::Arguments) {
+    if !enabled(level) {
+        return;
+    }
+
+    let message = args.to_string();
+    match LOGGER.get() {
+        Some(logger) => {
+            let line = format_line(level, logger.format, &message, &timestamp());
+            if let Ok(mut out) = logger.out.lock() {
+                writeln!(out, "{}", line).ok();
+            }
+        }
+        None => eprintln!(
+            "{}",
+            format_line(level, LogFormat::Pretty, &message, &timestamp())
+        ),
+    }
+}
+
+fn timestamp() -> String {
+    Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+/// One log line (without newline)
+fn format_line(level: LogLevel, format: LogFormat, message: &str, ts: &str) -> String {
+    match format {
+        LogFormat::Pretty => match level {
+            LogLevel::Error => format!("error: {}", message),
+            LogLevel::Warn => format!("warning: {}", message),
+            LogLevel::Info | LogLevel::Debug => message.to_string(),
+        },
+        LogFormat::Json => json!({
+            "ts": ts,
+            "level": level.as_str(),
+            "message": message,
+        })
+        .to_string(),
+    }
+}
+
+/// Debug message, shown with `--log-level debug` or the `DEBUG` environment variable
 ///
 /// Usage:
 /// ```
@@ -10,14 +181,21 @@
 #[macro_export]
 macro_rules! debug {
     ($($arg:tt)*) => {
-        if std::env::var("DEBUG").is_ok() {
-            println!($($arg)*);
-        }
+        $crate::debug::log($crate::debug::LogLevel::Debug, format_args!($($arg)*))
+    };
+}
+
+/// Recoverable problem worth telling the user about (skipped entries, unreadable cache files)
+#[macro_export]
+macro_rules! warn {
+    ($($arg:tt)*) => {
+        $crate::debug::log($crate::debug::LogLevel::Warn, format_args!($($arg)*))
     };
 }
 
 #[cfg(test)]
 mod tests {
+    use super::*;
     use std::env;
 
     #[test]
@@ -43,4 +221,32 @@ mod tests {
         debug!("Multiple {} values: {}", "formatted", 123);
         env::remove_var("DEBUG");
     }
+
+    #[test]
+    fn test_level_ordering_and_parsing() {
+        assert!(LogLevel::Error < LogLevel::Warn);
+        assert!(LogLevel::Info < LogLevel::Debug);
+        assert_eq!("WARNING".parse::<LogLevel>(), Ok(LogLevel::Warn));
+        assert!("verbose".parse::<LogLevel>().is_err());
+        assert_eq!("json".parse::<LogFormat>(), Ok(LogFormat::Json));
+    }
+
+    #[test]
+    fn test_format_line() {
+        let ts = "2023-11-14T22:13:20.000Z";
+        assert_eq!(
+            format_line(LogLevel::Warn, LogFormat::Pretty, "skipping entry", ts),
+            "warning: skipping entry"
+        );
+        assert_eq!(
+            format_line(LogLevel::Debug, LogFormat::Pretty, "cache hit", ts),
+            "cache hit"
+        );
+
+        let line = format_line(LogLevel::Error, LogFormat::Json, "boom \"quoted\"", ts);
+        let value: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(value["level"], "error");
+        assert_eq!(value["message"], "boom \"quoted\"");
+        assert_eq!(value["ts"], ts);
+    }
 }
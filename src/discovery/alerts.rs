@@ -0,0 +1,214 @@
+//! Workflows that need attention: phases in progress for too long, unreadable state, budgets
+//! running out
+//!
+//! Stuck detection reads phase metrics, so it only covers projects with loaded statistics;
+//! corrupted state is reported either way. Archived projects are skipped.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{BudgetStatus, DiscoveredProject};
+
+/// Why a project was flagged
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AlertKind {
+    /// The current phase started longer ago than the stuck threshold and hasn't ended
+    StuckPhase {
+        phase: String,
+        started: DateTime<Utc>,
+        age_hours: i64,
+    },
+    /// `state.json` couldn't be loaded
+    CorruptedState { error: String },
+    /// This month's usage reached 80% or 100% of a budget
+    Budget {
+        month: String,
+        threshold: u8,
+        percent_used: f64,
+    },
+}
+
+/// One flagged project
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Alert {
+    pub project_name: String,
+    #[serde(flatten)]
+    pub kind: AlertKind,
+}
+
+/// Flag projects with a phase in progress for at least `stuck_after`, or with corrupted state
+///
+/// Alerts come in project order (corrupted state first when a project has both).
+pub fn find_alerts(
+    projects: &[DiscoveredProject],
+    stuck_after: Duration,
+    now: DateTime<Utc>,
+) -> Vec<Alert> {
+    let mut alerts = Vec::new();
+
+    for project in projects.iter().filter(|p| !p.archived) {
+        if let Some(error) = &project.error {
+            alerts.push(Alert {
+                project_name: project.name.clone(),
+                kind: AlertKind::CorruptedState {
+                    error: error.clone(),
+                },
+            });
+        }
+
+        let Some(stats) = &project.statistics else {
+            continue;
+        };
+        let Some(current) = stats.phase_metrics.last() else {
+            continue;
+        };
+        if current.end_time.is_some() {
+            continue;
+        }
+        if let Some(started) = stuck_since(&current.start_time, stuck_after, now) {
+            alerts.push(Alert {
+                project_name: project.name.clone(),
+                kind: AlertKind::StuckPhase {
+                    phase: current.phase_name.clone(),
+                    started,
+                    age_hours: (now - started).num_hours(),
+                },
+            });
+        }
+    }
+
+    alerts
+}
+
+/// Alerts for budgets at or past a threshold (the global budget is named "all projects")
+pub fn budget_alerts(statuses: &[BudgetStatus]) -> Vec<Alert> {
+    statuses
+        .iter()
+        .filter_map(|status| {
+            Some(Alert {
+                project_name: status.label().to_string(),
+                kind: AlertKind::Budget {
+                    month: status.month.clone(),
+                    threshold: status.threshold_reached()?,
+                    percent_used: status.percent_used,
+                },
+            })
+        })
+        .collect()
+}
+
+/// Start time of an open phase if it began at least `stuck_after` before `now`
+///
+/// Unparseable start times are never considered stuck.
+fn stuck_since(
+    start_time: &str,
+    stuck_after: Duration,
+    now: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    let started = DateTime::parse_from_rfc3339(start_time)
+        .ok()?
+        .with_timezone(&Utc);
+    (now - started >= stuck_after).then_some(started)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::Budget;
+    use crate::test_helpers::fixture_projects;
+
+    fn at(timestamp: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(timestamp)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_stuck_since() {
+        let now = at("2025-01-06T12:00:00Z");
+
+        assert_eq!(
+            stuck_since("2025-01-05T12:00:00Z", Duration::hours(24), now),
+            Some(at("2025-01-05T12:00:00Z"))
+        );
+        assert_eq!(
+            stuck_since("2025-01-05T13:00:00+01:00", Duration::hours(24), now),
+            Some(at("2025-01-05T12:00:00Z"))
+        );
+        assert_eq!(
+            stuck_since("2025-01-06T00:00:00Z", Duration::hours(24), now),
+            None
+        );
+        assert_eq!(stuck_since("yesterday", Duration::hours(24), now), None);
+    }
+
+    #[test]
+    fn test_find_alerts_corrupted_state() {
+        let mut projects = fixture_projects();
+        let alerts = find_alerts(&projects, Duration::hours(24), Utc::now());
+
+        assert_eq!(
+            alerts,
+            vec![Alert {
+                project_name: "gamma".to_string(),
+                kind: AlertKind::CorruptedState {
+                    error: "Failed to load state".to_string(),
+                },
+            }]
+        );
+
+        // Archived projects are left alone
+        projects[2].archived = true;
+        assert!(find_alerts(&projects, Duration::hours(24), Utc::now()).is_empty());
+    }
+
+    #[test]
+    fn test_budget_alerts() {
+        let status = |name: Option<&str>, percent_used: f64| BudgetStatus {
+            project_name: name.map(String::from),
+            month: "2025-01".to_string(),
+            budget: Budget::default(),
+            used_tokens: 0,
+            estimated_cost: 0.0,
+            percent_used,
+        };
+        let alerts = budget_alerts(&[
+            status(None, 104.5),
+            status(Some("alpha"), 79.9),
+            status(Some("beta-service"), 80.0),
+        ]);
+
+        let summary: Vec<(&str, u8)> = alerts
+            .iter()
+            .map(|alert| match alert.kind {
+                AlertKind::Budget { threshold, .. } => (alert.project_name.as_str(), threshold),
+                _ => unreachable!(),
+            })
+            .collect();
+        assert_eq!(summary, [("all projects", 100), ("beta-service", 80)]);
+    }
+
+    #[test]
+    fn test_alert_json_shape() {
+        let alert = Alert {
+            project_name: "alpha".to_string(),
+            kind: AlertKind::StuckPhase {
+                phase: "code".to_string(),
+                started: at("2025-01-05T12:00:00Z"),
+                age_hours: 24,
+            },
+        };
+
+        assert_eq!(
+            serde_json::to_value(&alert).unwrap(),
+            serde_json::json!({
+                "project_name": "alpha",
+                "kind": "stuck_phase",
+                "phase": "code",
+                "started": "2025-01-05T12:00:00Z",
+                "age_hours": 24
+            })
+        );
+    }
+}
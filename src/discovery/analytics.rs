@@ -0,0 +1,398 @@
+//! Cross-project analytics computed from loaded project statistics
+//!
+//! All functions skip projects whose statistics haven't been loaded
+//! (see `DiscoveredProject::load_statistics`), so callers decide how much to load.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::{total_tokens, DiscoveredProject, ModeMetrics, MonthTokens, ProjectStatistics};
+
+/// Duration statistics for one phase name
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PhaseDurationStats {
+    pub phase_name: String,
+    /// Number of completed phases sampled
+    pub count: usize,
+    pub avg_seconds: f64,
+    pub median_seconds: u64,
+    pub max_seconds: u64,
+}
+
+/// Phase duration statistics for a single project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectPhaseDurations {
+    pub project_name: String,
+    pub phases: Vec<PhaseDurationStats>,
+}
+
+/// Phase durations aggregated across projects, with optional per-project breakdown
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhaseDurationReport {
+    pub phases: Vec<PhaseDurationStats>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub per_project: Option<Vec<ProjectPhaseDurations>>,
+}
+
+/// Compute per-phase duration statistics across all projects with loaded statistics
+///
+/// Only completed phases are sampled; in-progress phases would skew averages downward.
+pub fn phase_durations(projects: &[DiscoveredProject], per_project: bool) -> PhaseDurationReport {
+    let mut all_samples = Vec::new();
+    let mut breakdown = Vec::new();
+
+    for project in projects {
+        let samples = completed_phase_durations(project);
+        if samples.is_empty() {
+            continue;
+        }
+
+        if per_project {
+            breakdown.push(ProjectPhaseDurations {
+                project_name: project.name.clone(),
+                phases: summarize_durations(samples.clone()),
+            });
+        }
+        all_samples.extend(samples);
+    }
+
+    PhaseDurationReport {
+        phases: summarize_durations(all_samples),
+        per_project: per_project.then_some(breakdown),
+    }
+}
+
+/// Extract (phase name, duration) samples for completed phases of one project
+fn completed_phase_durations(project: &DiscoveredProject) -> Vec<(String, u64)> {
+    match &project.statistics {
+        Some(stats) => stats
+            .phase_metrics
+            .iter()
+            .filter(|phase| phase.end_time.is_some())
+            .map(|phase| (phase.phase_name.clone(), phase.duration_seconds))
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+/// Group duration samples by phase name and compute avg/median/max (sorted by phase name)
+fn summarize_durations(samples: Vec<(String, u64)>) -> Vec<PhaseDurationStats> {
+    let mut by_phase: BTreeMap<String, Vec<u64>> = BTreeMap::new();
+    for (phase_name, duration) in samples {
+        by_phase.entry(phase_name).or_default().push(duration);
+    }
+
+    by_phase
+        .into_iter()
+        .map(|(phase_name, mut durations)| {
+            durations.sort_unstable();
+            let count = durations.len();
+            let total: u64 = durations.iter().sum();
+            PhaseDurationStats {
+                phase_name,
+                count,
+                avg_seconds: total as f64 / count as f64,
+                median_seconds: median(&durations),
+                max_seconds: durations[count - 1],
+            }
+        })
+        .collect()
+}
+
+/// Median of a sorted, non-empty slice (mean of the two middle values for even lengths)
+fn median(sorted: &[u64]) -> u64 {
+    let mid = sorted.len() / 2;
+    if sorted.len() % 2 == 1 {
+        sorted[mid]
+    } else {
+        (sorted[mid - 1] + sorted[mid]) / 2
+    }
+}
+
+/// Token and duration statistics for one phase name across projects
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PhaseNameStats {
+    pub phase_name: String,
+    /// Number of phases with this name (including in-progress)
+    pub count: usize,
+    /// Number of distinct projects that ran this phase
+    pub project_count: usize,
+    pub avg_tokens: f64,
+    pub max_tokens: u64,
+    /// Average over completed phases only (None if none completed)
+    pub avg_duration_seconds: Option<f64>,
+}
+
+/// One phase occurrence used as input to `summarize_phase_names`
+struct PhaseSample {
+    project_name: String,
+    phase_name: String,
+    tokens: u64,
+    /// Duration if the phase has completed
+    duration_seconds: Option<u64>,
+}
+
+/// Aggregate token and duration statistics by phase name across projects
+///
+/// Pass a single project to get that project's per-phase averages for comparison.
+pub fn phase_name_stats(projects: &[DiscoveredProject]) -> Vec<PhaseNameStats> {
+    let samples = projects
+        .iter()
+        .filter_map(|project| project.statistics.as_ref().map(|s| (project, s)))
+        .flat_map(|(project, stats)| {
+            stats.phase_metrics.iter().map(move |phase| PhaseSample {
+                project_name: project.name.clone(),
+                phase_name: phase.phase_name.clone(),
+                tokens: total_tokens(&phase.token_metrics),
+                duration_seconds: phase.end_time.as_ref().map(|_| phase.duration_seconds),
+            })
+        })
+        .collect();
+
+    summarize_phase_names(samples)
+}
+
+/// Group phase samples by name (sorted by phase name)
+fn summarize_phase_names(samples: Vec<PhaseSample>) -> Vec<PhaseNameStats> {
+    let mut by_phase: BTreeMap<String, Vec<PhaseSample>> = BTreeMap::new();
+    for sample in samples {
+        by_phase
+            .entry(sample.phase_name.clone())
+            .or_default()
+            .push(sample);
+    }
+
+    by_phase
+        .into_iter()
+        .map(|(phase_name, samples)| {
+            let count = samples.len();
+            let total_tokens: u64 = samples.iter().map(|s| s.tokens).sum();
+            let durations: Vec<u64> = samples.iter().filter_map(|s| s.duration_seconds).collect();
+            let mut projects: Vec<&str> = samples.iter().map(|s| s.project_name.as_str()).collect();
+            projects.sort_unstable();
+            projects.dedup();
+
+            PhaseNameStats {
+                phase_name,
+                count,
+                project_count: projects.len(),
+                avg_tokens: total_tokens as f64 / count as f64,
+                max_tokens: samples.iter().map(|s| s.tokens).max().unwrap_or(0),
+                avg_duration_seconds: (!durations.is_empty())
+                    .then(|| durations.iter().sum::<u64>() as f64 / durations.len() as f64),
+            }
+        })
+        .collect()
+}
+
+/// Split a project's phase metrics by the workflow mode active when each phase started
+///
+/// The mode comes from the most recent state transition at or before the phase start.
+/// Phases with no preceding transition (or an unparseable start time) count under "unknown";
+/// transitions with unparseable timestamps are ignored.
+pub fn mode_breakdown(stats: &ProjectStatistics) -> BTreeMap<String, ModeMetrics> {
+    let mut transitions: Vec<(DateTime<Utc>, &str)> = stats
+        .state_transitions
+        .iter()
+        .filter_map(|t| Some((parse_time(&t.timestamp)?, t.mode.as_str())))
+        .collect();
+    // Stable, so transitions at the same instant keep log order (the later one wins)
+    transitions.sort_by_key(|(time, _)| *time);
+
+    let mut per_mode: BTreeMap<String, ModeMetrics> = BTreeMap::new();
+    for phase in &stats.phase_metrics {
+        let mode = parse_time(&phase.start_time)
+            .and_then(|start| mode_at(&transitions, start))
+            .unwrap_or("unknown");
+        let entry = per_mode.entry(mode.to_string()).or_default();
+        entry.total_tokens += total_tokens(&phase.token_metrics);
+        entry.total_events += phase.bash_commands.len() + phase.file_modifications.len();
+        entry.phase_count += 1;
+    }
+
+    per_mode
+}
+
+/// Split a project's per-phase token usage by the month (UTC) each phase started in
+///
+/// Phases with unparseable start times are skipped.
+pub fn month_breakdown(stats: &ProjectStatistics) -> BTreeMap<String, MonthTokens> {
+    let mut per_month: BTreeMap<String, MonthTokens> = BTreeMap::new();
+    for phase in &stats.phase_metrics {
+        let Some(start) = parse_time(&phase.start_time) else {
+            continue;
+        };
+        let month = start.format("%Y-%m").to_string();
+        let tokens = &phase.token_metrics;
+        per_month.entry(month).or_default().merge(&MonthTokens {
+            input_tokens: tokens.total_input_tokens,
+            output_tokens: tokens.total_output_tokens,
+            cache_creation_tokens: tokens.total_cache_creation_tokens,
+            cache_read_tokens: tokens.total_cache_read_tokens,
+        });
+    }
+    per_month
+}
+
+/// Find the mode of the latest transition at or before `time` (transitions sorted)
+fn mode_at<'a>(transitions: &[(DateTime<Utc>, &'a str)], time: DateTime<Utc>) -> Option<&'a str> {
+    transitions
+        .iter()
+        .take_while(|(at, _)| *at <= time)
+        .last()
+        .map(|(_, mode)| *mode)
+}
+
+/// Parse an RFC 3339 timestamp from the metrics logs (any offset, optional fractional seconds)
+fn parse_time(timestamp: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .ok()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::{phase_json, stats_with, transition_json};
+    use serde_json::json;
+    use std::time::SystemTime;
+    use tempfile::TempDir;
+
+    fn samples(pairs: &[(&str, u64)]) -> Vec<(String, u64)> {
+        pairs.iter().map(|(n, d)| (n.to_string(), *d)).collect()
+    }
+
+    #[test]
+    fn test_summarize_durations() {
+        let stats = summarize_durations(samples(&[
+            ("code", 100),
+            ("review", 300),
+            ("code", 200),
+            ("code", 600),
+        ]));
+
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].phase_name, "code");
+        assert_eq!(stats[0].count, 3);
+        assert_eq!(stats[0].avg_seconds, 300.0);
+        assert_eq!(stats[0].median_seconds, 200);
+        assert_eq!(stats[0].max_seconds, 600);
+        assert_eq!(stats[1].phase_name, "review");
+        assert_eq!(stats[1].count, 1);
+    }
+
+    #[test]
+    fn test_median_even_length() {
+        assert_eq!(median(&[10, 20, 30, 40]), 25);
+        assert_eq!(median(&[7]), 7);
+    }
+
+    #[test]
+    fn test_phase_durations_skips_unloaded_projects() {
+        let temp = TempDir::new().unwrap();
+        let project = DiscoveredProject::new(
+            "project1".to_string(),
+            temp.path().to_path_buf(),
+            temp.path().join(".hegel"),
+            None,
+            SystemTime::now(),
+            None,
+        );
+
+        let report = phase_durations(&[project], true);
+        assert!(report.phases.is_empty());
+        assert_eq!(report.per_project.unwrap().len(), 0);
+    }
+
+    #[test]
+    fn test_summarize_phase_names() {
+        let sample = |project: &str, phase: &str, tokens: u64, duration: Option<u64>| PhaseSample {
+            project_name: project.to_string(),
+            phase_name: phase.to_string(),
+            tokens,
+            duration_seconds: duration,
+        };
+
+        let stats = summarize_phase_names(vec![
+            sample("a", "spec", 100, Some(60)),
+            sample("a", "spec", 300, Some(120)),
+            sample("b", "spec", 200, None),
+            sample("b", "code", 1000, None),
+        ]);
+
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].phase_name, "code");
+        assert_eq!(stats[0].avg_duration_seconds, None);
+        assert_eq!(stats[1].phase_name, "spec");
+        assert_eq!(stats[1].count, 3);
+        assert_eq!(stats[1].project_count, 2);
+        assert_eq!(stats[1].avg_tokens, 200.0);
+        assert_eq!(stats[1].max_tokens, 300);
+        assert_eq!(stats[1].avg_duration_seconds, Some(90.0));
+    }
+
+    #[test]
+    fn test_mode_at() {
+        let at = |s: &str| parse_time(s).unwrap();
+        let transitions = vec![
+            (at("2025-01-01T00:00:00Z"), "discovery"),
+            (at("2025-01-02T00:00:00Z"), "execution"),
+        ];
+        assert_eq!(mode_at(&transitions, at("2024-12-31T00:00:00Z")), None);
+        assert_eq!(
+            mode_at(&transitions, at("2025-01-01T00:00:00Z")),
+            Some("discovery")
+        );
+        // 2025-01-02T01:00:00Z, though it sorts before the execution transition as text
+        assert_eq!(
+            mode_at(&transitions, at("2025-01-01T20:00:00-05:00")),
+            Some("execution")
+        );
+    }
+
+    #[test]
+    fn test_mode_breakdown() {
+        let mut code = phase_json("code", "2025-01-01T10:45:00.5Z", None, 1_000);
+        code["bash_commands"] = json!([{"command": "cargo test"}]);
+        code["file_modifications"] = json!([
+            {"file_path": "src/lib.rs", "tool": "Edit"},
+            {"file_path": "src/main.rs", "tool": "Edit"},
+        ]);
+        let stats = stats_with(json!({
+            "state_transitions": [
+                transition_json("2025-01-01T10:00:00Z", "spec", "discovery"),
+                // 10:30 UTC: before the code phase, though not as text
+                transition_json("2025-01-01T12:30:00+02:00", "code", "execution"),
+            ],
+            "phase_metrics": [
+                phase_json("init", "2025-01-01T09:00:00Z", Some("2025-01-01T10:00:00Z"), 5),
+                phase_json("spec", "2025-01-01T10:15:00Z", Some("2025-01-01T10:45:00Z"), 100),
+                code,
+            ],
+        }));
+
+        let modes = mode_breakdown(&stats);
+
+        assert_eq!(modes.len(), 3);
+        assert_eq!(modes["unknown"].total_tokens, 5);
+        assert_eq!(modes["discovery"].total_tokens, 100);
+        assert_eq!(modes["discovery"].total_events, 0);
+        assert_eq!(modes["execution"].total_tokens, 1_000);
+        assert_eq!(modes["execution"].total_events, 3);
+        assert_eq!(modes["execution"].phase_count, 1);
+    }
+
+    #[test]
+    fn test_mode_breakdown_empty() {
+        let stats = ProjectStatistics::default();
+        assert!(mode_breakdown(&stats).is_empty());
+        assert!(month_breakdown(&stats).is_empty());
+    }
+
+    #[test]
+    fn test_phase_durations_without_breakdown() {
+        let report = phase_durations(&[], false);
+        assert!(report.per_project.is_none());
+    }
+}
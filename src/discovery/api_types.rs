@@ -1,15 +1,70 @@
-use super::{ProjectStatistics, WorkflowState};
+use super::analytics::{mode_breakdown, month_breakdown};
+use super::{total_tokens, DiscoveredProject, ProjectStatistics, WorkflowState};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// Lightweight API response for project list - contains only data needed by sidebar
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProjectListItem {
     pub name: String,
+    /// Stable ID (hash of the project path) for URLs; unlike the name, never changes on collision
+    pub id: String,
     pub workflow_state: Option<WorkflowState>,
+    /// Most recent modification in `.hegel/` (RFC 3339), for sorting by recent activity
+    #[serde(default)]
+    pub last_activity: DateTime<Utc>,
+    /// User metadata (owner, ticket link, status, notes...)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, String>,
+    /// Discovery plugin output, keyed by plugin name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub extra: BTreeMap<String, serde_json::Value>,
 }
 
-/// Lightweight API response for metrics - contains only summary data, not raw events
+impl From<&DiscoveredProject> for ProjectListItem {
+    fn from(project: &DiscoveredProject) -> Self {
+        Self {
+            name: project.name.clone(),
+            id: project.id(),
+            workflow_state: project.workflow_state.clone(),
+            last_activity: project.last_activity.into(),
+            metadata: project.metadata.clone(),
+            extra: project.extra.clone(),
+        }
+    }
+}
+
+/// Paginated API response: one page of items plus the offset of the next page
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Total number of items across all pages
+    pub total: usize,
+    /// Offset to request for the next page (None on the last page)
+    pub next: Option<usize>,
+}
+
+impl<T: Clone> Page<T> {
+    /// Slice a page out of `all` starting at `offset` (no limit returns everything after offset)
+    pub fn from_slice(all: &[T], offset: usize, limit: Option<usize>) -> Self {
+        let total = all.len();
+        let start = offset.min(total);
+        let end = match limit {
+            Some(limit) => start.saturating_add(limit).min(total),
+            None => total,
+        };
+
+        Self {
+            items: all[start..end].to_vec(),
+            total,
+            next: (end < total).then_some(end),
+        }
+    }
+}
+
+/// Lightweight API response for metrics - contains only summary data, not raw events
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ProjectMetricsSummary {
     pub total_input_tokens: u64,
     pub total_output_tokens: u64,
@@ -21,26 +76,197 @@ pub struct ProjectMetricsSummary {
     pub file_modification_count: usize,
     pub git_commit_count: usize,
     pub phase_count: usize,
+    /// Token/event/phase split by workflow mode (e.g. "discovery", "execution")
+    #[serde(default)]
+    pub per_mode: BTreeMap<String, ModeMetrics>,
+    /// Token split by the calendar month (`2025-01`, UTC) each phase started in (for budgets)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub per_month: BTreeMap<String, MonthTokens>,
+}
+
+impl ProjectMetricsSummary {
+    /// Add another project's totals into this one (per-mode totals merged by mode)
+    pub fn merge(&mut self, other: &ProjectMetricsSummary) {
+        self.total_input_tokens += other.total_input_tokens;
+        self.total_output_tokens += other.total_output_tokens;
+        self.total_cache_creation_tokens += other.total_cache_creation_tokens;
+        self.total_cache_read_tokens += other.total_cache_read_tokens;
+        self.total_all_tokens += other.total_all_tokens;
+        self.total_events += other.total_events;
+        self.bash_command_count += other.bash_command_count;
+        self.file_modification_count += other.file_modification_count;
+        self.git_commit_count += other.git_commit_count;
+        self.phase_count += other.phase_count;
+        for (mode, metrics) in &other.per_mode {
+            self.per_mode
+                .entry(mode.clone())
+                .or_default()
+                .merge(metrics);
+        }
+        for (month, tokens) in &other.per_month {
+            self.per_month
+                .entry(month.clone())
+                .or_default()
+                .merge(tokens);
+        }
+    }
+}
+
+/// Tokens used by the phases that started in one month, by kind (for cost estimates)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MonthTokens {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+}
+
+impl MonthTokens {
+    pub fn total(&self) -> u64 {
+        self.input_tokens + self.output_tokens + self.cache_creation_tokens + self.cache_read_tokens
+    }
+
+    /// Add another month's (or project's) tokens into this one
+    pub fn merge(&mut self, other: &MonthTokens) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cache_creation_tokens += other.cache_creation_tokens;
+        self.cache_read_tokens += other.cache_read_tokens;
+    }
+}
+
+/// Metrics attributed to a single workflow mode
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModeMetrics {
+    pub total_tokens: u64,
+    /// Bash commands plus file modifications recorded during the mode's phases
+    pub total_events: usize,
+    pub phase_count: usize,
+}
+
+impl ModeMetrics {
+    /// Add another mode's totals into this one
+    pub fn merge(&mut self, other: &ModeMetrics) {
+        self.total_tokens += other.total_tokens;
+        self.total_events += other.total_events;
+        self.phase_count += other.phase_count;
+    }
 }
 
 impl From<&ProjectStatistics> for ProjectMetricsSummary {
     fn from(stats: &ProjectStatistics) -> Self {
-        let total_all_tokens = stats.token_metrics.total_input_tokens
-            + stats.token_metrics.total_output_tokens
-            + stats.token_metrics.total_cache_creation_tokens
-            + stats.token_metrics.total_cache_read_tokens;
-
         Self {
             total_input_tokens: stats.token_metrics.total_input_tokens,
             total_output_tokens: stats.token_metrics.total_output_tokens,
             total_cache_creation_tokens: stats.token_metrics.total_cache_creation_tokens,
             total_cache_read_tokens: stats.token_metrics.total_cache_read_tokens,
-            total_all_tokens,
+            total_all_tokens: total_tokens(&stats.token_metrics),
             total_events: stats.hook_metrics.total_events,
             bash_command_count: stats.hook_metrics.bash_commands.len(),
             file_modification_count: stats.hook_metrics.file_modifications.len(),
             git_commit_count: stats.git_commits.len(),
             phase_count: stats.phase_metrics.len(),
+            per_mode: mode_breakdown(stats),
+            per_month: month_breakdown(stats),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary_from_empty_statistics() {
+        let stats = ProjectStatistics::default();
+        let summary = ProjectMetricsSummary::from(&stats);
+        assert_eq!(summary.total_all_tokens, 0);
+        assert_eq!(summary.phase_count, 0);
+        assert!(summary.per_mode.is_empty());
+    }
+
+    #[test]
+    fn test_summary_merge() {
+        let mode = |tokens| ModeMetrics {
+            total_tokens: tokens,
+            total_events: 1,
+            phase_count: 1,
+        };
+        let mut total = ProjectMetricsSummary {
+            total_all_tokens: 100,
+            phase_count: 1,
+            per_mode: BTreeMap::from([("discovery".to_string(), mode(100))]),
+            ..Default::default()
+        };
+        total.merge(&ProjectMetricsSummary {
+            total_all_tokens: 50,
+            bash_command_count: 3,
+            phase_count: 2,
+            per_mode: BTreeMap::from([
+                ("discovery".to_string(), mode(20)),
+                ("execution".to_string(), mode(30)),
+            ]),
+            ..Default::default()
+        });
+
+        assert_eq!(total.total_all_tokens, 150);
+        assert_eq!(total.bash_command_count, 3);
+        assert_eq!(total.phase_count, 3);
+        assert_eq!(total.per_mode["discovery"].total_tokens, 120);
+        assert_eq!(total.per_mode["execution"].total_tokens, 30);
+    }
+
+    #[test]
+    fn test_page_from_slice() {
+        let all: Vec<u32> = (0..10).collect();
+
+        let first = Page::from_slice(&all, 0, Some(4));
+        assert_eq!(first.items, vec![0, 1, 2, 3]);
+        assert_eq!(first.total, 10);
+        assert_eq!(first.next, Some(4));
+
+        let last = Page::from_slice(&all, 8, Some(4));
+        assert_eq!(last.items, vec![8, 9]);
+        assert_eq!(last.next, None);
+    }
+
+    #[test]
+    fn test_page_unbounded_and_out_of_range() {
+        let all: Vec<u32> = (0..3).collect();
+
+        let everything = Page::from_slice(&all, 0, None);
+        assert_eq!(everything.items.len(), 3);
+        assert_eq!(everything.next, None);
+
+        let past_end = Page::from_slice(&all, 10, Some(5));
+        assert!(past_end.items.is_empty());
+        assert_eq!(past_end.total, 3);
+        assert_eq!(past_end.next, None);
+    }
+
+    #[test]
+    fn test_list_item_last_activity() {
+        let project = &crate::test_helpers::fixture_projects()[0];
+        let json = serde_json::to_value(ProjectListItem::from(project)).unwrap();
+        assert_eq!(json["name"], "alpha");
+        assert_eq!(json["last_activity"], "2023-11-14T22:13:20Z");
+    }
+
+    #[test]
+    fn test_mode_metrics_merge() {
+        let mut total = ModeMetrics::default();
+        total.merge(&ModeMetrics {
+            total_tokens: 100,
+            total_events: 5,
+            phase_count: 2,
+        });
+        total.merge(&ModeMetrics {
+            total_tokens: 50,
+            total_events: 1,
+            phase_count: 1,
+        });
+        assert_eq!(total.total_tokens, 150);
+        assert_eq!(total.total_events, 6);
+        assert_eq!(total.phase_count, 3);
+    }
+}
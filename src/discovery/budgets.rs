@@ -0,0 +1,214 @@
+//! Monthly token/cost budgets, global (`"budget"`) or per project (`"project_budgets"`)
+//!
+//! Usage comes from each project's cached `ProjectMetricsSummary::per_month`: tokens of the
+//! phases that started in the month, so a project counts from its last refresh. Sources without
+//! phases (see `MetricsSource`) have no monthly usage.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{DiscoveredProject, DiscoveryConfig, MonthTokens, TokenPricing};
+
+/// Percentages of a budget that raise an alert (warning, then exceeded)
+pub const BUDGET_THRESHOLDS: [u8; 2] = [80, 100];
+
+/// A monthly budget: tokens, estimated USD cost, or both (the tighter one counts)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Budget {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub monthly_tokens: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub monthly_cost_usd: Option<f64>,
+}
+
+impl Budget {
+    /// Percent of the budget `tokens` use: the higher of the token and cost percentages
+    ///
+    /// None when the budget sets no (positive) limit.
+    pub fn percent_used(&self, tokens: &MonthTokens, pricing: &TokenPricing) -> Option<f64> {
+        let by_tokens = self
+            .monthly_tokens
+            .filter(|&limit| limit > 0)
+            .map(|limit| tokens.total() as f64 * 100.0 / limit as f64);
+        let by_cost = self
+            .monthly_cost_usd
+            .filter(|&limit| limit > 0.0)
+            .map(|limit| pricing.tokens_cost(tokens) * 100.0 / limit);
+        match (by_tokens, by_cost) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        }
+    }
+}
+
+/// Usage against one budget in one month
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BudgetStatus {
+    /// Project the budget belongs to (None = the global budget, all projects)
+    pub project_name: Option<String>,
+    /// Calendar month (`2025-01`, UTC)
+    pub month: String,
+    pub budget: Budget,
+    pub used_tokens: u64,
+    pub estimated_cost: f64,
+    pub percent_used: f64,
+}
+
+impl BudgetStatus {
+    /// Project name, or "all projects" for the global budget
+    pub fn label(&self) -> &str {
+        self.project_name.as_deref().unwrap_or("all projects")
+    }
+
+    /// Highest of `BUDGET_THRESHOLDS` reached (None below 80%)
+    pub fn threshold_reached(&self) -> Option<u8> {
+        BUDGET_THRESHOLDS
+            .into_iter()
+            .rev()
+            .find(|&threshold| self.percent_used >= threshold as f64)
+    }
+}
+
+/// Month key for `now` (the format of `ProjectMetricsSummary::per_month`)
+pub fn current_month(now: DateTime<Utc>) -> String {
+    now.format("%Y-%m").to_string()
+}
+
+/// Budget statuses for `month`: the global budget first (over non-archived projects), then
+/// each project with its own budget, in project order
+///
+/// Projects without a cached summary count as no usage.
+pub fn budget_statuses(
+    projects: &[DiscoveredProject],
+    config: &DiscoveryConfig,
+    month: &str,
+) -> Vec<BudgetStatus> {
+    let pricing = config.token_pricing();
+    let usage = |project: &DiscoveredProject| {
+        project
+            .metrics_summary
+            .as_ref()
+            .and_then(|summary| summary.per_month.get(month))
+            .cloned()
+            .unwrap_or_default()
+    };
+    let status = |project_name: Option<String>, budget: &Budget, tokens: &MonthTokens| {
+        Some(BudgetStatus {
+            project_name,
+            month: month.to_string(),
+            budget: budget.clone(),
+            used_tokens: tokens.total(),
+            estimated_cost: pricing.tokens_cost(tokens),
+            percent_used: budget.percent_used(tokens, &pricing)?,
+        })
+    };
+
+    let mut statuses = Vec::new();
+    let active = projects.iter().filter(|p| !p.archived);
+    if let Some(budget) = &config.budget {
+        let mut total = MonthTokens::default();
+        for project in active.clone() {
+            total.merge(&usage(project));
+        }
+        statuses.extend(status(None, budget, &total));
+    }
+    for project in active {
+        if let Some(budget) = config.project_budget(project) {
+            statuses.extend(status(Some(project.name.clone()), budget, &usage(project)));
+        }
+    }
+    statuses
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::ProjectMetricsSummary;
+    use crate::test_helpers::fixture_projects;
+    use std::collections::BTreeMap;
+
+    fn tokens(input: u64, output: u64) -> MonthTokens {
+        MonthTokens {
+            input_tokens: input,
+            output_tokens: output,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_percent_used() {
+        let pricing = TokenPricing::default();
+        let usage = tokens(400_000, 100_000); // $1.20 + $1.50
+
+        let by_tokens = Budget {
+            monthly_tokens: Some(1_000_000),
+            monthly_cost_usd: None,
+        };
+        assert_eq!(by_tokens.percent_used(&usage, &pricing), Some(50.0));
+
+        // Both limits: the tighter one counts
+        let both = Budget {
+            monthly_cost_usd: Some(3.0),
+            ..by_tokens
+        };
+        let percent = both.percent_used(&usage, &pricing).unwrap();
+        assert!((percent - 90.0).abs() < 1e-9);
+
+        assert_eq!(Budget::default().percent_used(&usage, &pricing), None);
+    }
+
+    #[test]
+    fn test_budget_statuses() {
+        let mut projects = fixture_projects();
+        let month = "2025-01";
+        let with_usage = |tokens: MonthTokens| {
+            Some(ProjectMetricsSummary {
+                per_month: BTreeMap::from([
+                    (month.to_string(), tokens),
+                    ("2024-12".to_string(), self::tokens(9_000_000, 0)),
+                ]),
+                ..Default::default()
+            })
+        };
+        projects[0].metrics_summary = with_usage(tokens(850, 0));
+        projects[1].metrics_summary = with_usage(tokens(100, 50));
+
+        let mut config = DiscoveryConfig {
+            budget: Some(Budget {
+                monthly_tokens: Some(10_000),
+                monthly_cost_usd: None,
+            }),
+            ..Default::default()
+        };
+        config.project_budgets.insert(
+            "alpha".to_string(),
+            Budget {
+                monthly_tokens: Some(1_000),
+                monthly_cost_usd: None,
+            },
+        );
+
+        let statuses = budget_statuses(&projects, &config, month);
+        assert_eq!(statuses.len(), 2);
+        assert_eq!(statuses[0].label(), "all projects");
+        assert_eq!(statuses[0].used_tokens, 1_000);
+        assert_eq!(statuses[0].threshold_reached(), None);
+        assert_eq!(statuses[1].label(), "alpha");
+        assert_eq!(statuses[1].percent_used, 85.0);
+        assert_eq!(statuses[1].threshold_reached(), Some(80));
+
+        // Archived projects neither count toward the global budget nor get a status
+        projects[0].archived = true;
+        let statuses = budget_statuses(&projects, &config, month);
+        assert_eq!(statuses.len(), 1);
+        assert_eq!(statuses[0].used_tokens, 150);
+    }
+
+    #[test]
+    fn test_current_month() {
+        let now = DateTime::parse_from_rfc3339("2025-01-31T23:30:00-02:00")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(current_month(now), "2025-02");
+    }
+}
@@ -1,19 +1,30 @@
 //! Project discovery cache implementation
 //!
 //! Dual-cache strategy:
-//! - **Binary cache** (CLI): Multi-file structure at `~/.config/hegel-pm/cache/` with `index.bin` + per-project `.bin` files
+//! - **Binary cache** (CLI): Multi-file structure at `~/.config/hegel-pm/cache/` with `index.bin` + per-project `<id>.bin` files
 //! - **JSON cache** (Server): Single file at `~/.config/hegel-pm/cache.json` for data_layer compatibility
 //!
 //! Note: "Binary" cache uses JSON serialization (not bincode) due to `InvalidBoolEncoding` errors with `DiscoveredProject`.
 //! Multi-file structure enables future incremental updates.
+//!
+//! The CLI cache is accessed through `CacheStore` (see `store.rs`); `FileCacheStore` below is the
+//! multi-file implementation and the default backend. Every file here can be encrypted at rest
+//! (`cache_key_file`, see `cache_crypto.rs`).
 
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::SystemTime;
 
-use super::DiscoveredProject;
+use super::cache_crypto::{self, CacheCipher};
+use super::cache_lock::IndexLock;
+use super::refresh_lock::{refreshed_within, RefreshLock};
+use super::{
+    open_store, project_id, CacheStore, DiscoveredProject, NoProgress, ProgressEvent, ProgressSink,
+};
+use crate::warn;
 
 /// Lightweight index entry for fast project listing without loading full project data
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -22,10 +33,32 @@ pub struct ProjectIndexEntry {
     pub project_path: PathBuf,
     pub hegel_dir: PathBuf,
     pub last_activity: SystemTime,
+    /// Size of the project's `.hegel/` when last discovered or refreshed (0 in older caches)
+    #[serde(default)]
+    pub hegel_size_bytes: u64,
+}
+
+impl ProjectIndexEntry {
+    /// Stable project ID (see `project_id`)
+    pub fn id(&self) -> String {
+        project_id(&self.project_path)
+    }
 }
 
-/// Save discovered projects to cache
+/// Distinguishes `cache.json` temp files written by one process (server threads share a pid)
+static NEXT_TEMP_FILE: AtomicU64 = AtomicU64::new(0);
+
+/// Save discovered projects to cache (plaintext)
 pub fn save_cache(projects: &[DiscoveredProject], cache_location: &PathBuf) -> Result<()> {
+    save_cache_with_cipher(projects, cache_location, None)
+}
+
+/// Save discovered projects to cache (encrypted when `cipher` is set, see `cache_crypto`)
+pub fn save_cache_with_cipher(
+    projects: &[DiscoveredProject],
+    cache_location: &PathBuf,
+    cipher: Option<&CacheCipher>,
+) -> Result<()> {
     // Create parent directory if needed
     if let Some(parent) = cache_location.parent() {
         fs::create_dir_all(parent).context(format!(
@@ -35,11 +68,17 @@ pub fn save_cache(projects: &[DiscoveredProject], cache_location: &PathBuf) -> R
     }
 
     // Serialize projects to JSON
-    let json = serde_json::to_string_pretty(projects).context("Failed to serialize projects")?;
-
-    // Atomic write: write to temp file, then rename
-    let temp_file = cache_location.with_extension("json.tmp");
-    fs::write(&temp_file, json).context(format!(
+    let json = serde_json::to_vec_pretty(projects).context("Failed to serialize projects")?;
+    let contents = cache_crypto::seal(cipher, json)?;
+
+    // Atomic write: write to temp file, then rename. The temp name is unique per writer, so
+    // concurrent CLI and server saves never rename each other's half-written file.
+    let temp_file = cache_location.with_extension(format!(
+        "json.{}-{}.tmp",
+        std::process::id(),
+        NEXT_TEMP_FILE.fetch_add(1, Ordering::Relaxed)
+    ));
+    fs::write(&temp_file, contents).context(format!(
         "Failed to write temp cache file: {}",
         temp_file.display()
     ))?;
@@ -52,25 +91,38 @@ pub fn save_cache(projects: &[DiscoveredProject], cache_location: &PathBuf) -> R
     Ok(())
 }
 
-/// Load discovered projects from cache
+/// Load discovered projects from a plaintext cache (an encrypted one is an error)
 pub fn load_cache(cache_location: &PathBuf) -> Result<Option<Vec<DiscoveredProject>>> {
+    load_cache_with_cipher(cache_location, None)
+}
+
+/// Load discovered projects from cache (plaintext or encrypted; encrypted needs `cipher`)
+pub fn load_cache_with_cipher(
+    cache_location: &PathBuf,
+    cipher: Option<&CacheCipher>,
+) -> Result<Option<Vec<DiscoveredProject>>> {
     if !cache_location.exists() {
         return Ok(None);
     }
 
-    let content = fs::read_to_string(cache_location).context(format!(
+    let content = fs::read(cache_location).context(format!(
         "Failed to read cache file: {}",
         cache_location.display()
     ))?;
+    let content = cache_crypto::open(cipher, &content, cache_location)?;
 
     let projects: Vec<DiscoveredProject> =
-        serde_json::from_str(&content).context("Failed to parse cache file")?;
+        serde_json::from_slice(&content).context("Failed to parse cache file")?;
 
     Ok(Some(projects))
 }
 
 /// Write index to binary file with atomic write
-fn write_index(index: &[ProjectIndexEntry], cache_dir: &PathBuf) -> Result<()> {
+fn write_index(
+    index: &[ProjectIndexEntry],
+    cache_dir: &PathBuf,
+    cipher: Option<&CacheCipher>,
+) -> Result<()> {
     // Ensure cache directory exists
     fs::create_dir_all(cache_dir).context(format!(
         "Failed to create cache directory: {}",
@@ -82,6 +134,7 @@ fn write_index(index: &[ProjectIndexEntry], cache_dir: &PathBuf) -> Result<()> {
 
     // Serialize to JSON (bincode has issues with DiscoveredProject types)
     let encoded = serde_json::to_vec(index).context("Failed to serialize index")?;
+    let encoded = cache_crypto::seal(cipher, encoded)?;
 
     // Atomic write
     fs::write(&temp_path, encoded).context(format!(
@@ -97,8 +150,11 @@ fn write_index(index: &[ProjectIndexEntry], cache_dir: &PathBuf) -> Result<()> {
     Ok(())
 }
 
-/// Read index from binary file using memmap
-fn read_index(cache_dir: &PathBuf) -> Result<Option<Vec<ProjectIndexEntry>>> {
+/// Read index from binary file
+fn read_index(
+    cache_dir: &PathBuf,
+    cipher: Option<&CacheCipher>,
+) -> Result<Option<Vec<ProjectIndexEntry>>> {
     let index_path = cache_dir.join("index.bin");
 
     if !index_path.exists() {
@@ -110,6 +166,7 @@ fn read_index(cache_dir: &PathBuf) -> Result<Option<Vec<ProjectIndexEntry>>> {
         "Failed to read index file: {}",
         index_path.display()
     ))?;
+    let contents = cache_crypto::open(cipher, &contents, &index_path)?;
 
     // Deserialize from JSON
     let index: Vec<ProjectIndexEntry> =
@@ -118,23 +175,52 @@ fn read_index(cache_dir: &PathBuf) -> Result<Option<Vec<ProjectIndexEntry>>> {
     Ok(Some(index))
 }
 
-/// Write individual project to binary file with atomic write
-fn write_project(project: &DiscoveredProject, cache_dir: &PathBuf) -> Result<()> {
-    // Sanitize project name for filename
-    let safe_name = project
-        .name
-        .replace(|c: char| !c.is_alphanumeric() && c != '-' && c != '_', "_");
-
-    let project_path = cache_dir.join(format!("{}.bin", safe_name));
-    let temp_path = cache_dir.join(format!("{}.bin.tmp", safe_name));
-
-    // Clear statistics and workflow_state before caching (lazy loaded/can be re-parsed)
+/// Copy of a project with statistics cleared (lazy loaded)
+///
+/// The workflow state is kept: it is small, and refresh previews compare against it. So is the
+/// metrics summary, which stands in for statistics until the next refresh.
+pub(super) fn cacheable_copy(project: &DiscoveredProject) -> DiscoveredProject {
     let mut project_copy = project.clone();
     project_copy.statistics = None;
-    project_copy.workflow_state = None;
+    project_copy
+}
+
+/// Index entry describing a project
+pub(super) fn index_entry(project: &DiscoveredProject) -> ProjectIndexEntry {
+    ProjectIndexEntry {
+        name: project.name.clone(),
+        project_path: project.project_path.clone(),
+        hegel_dir: project.hegel_dir.clone(),
+        last_activity: project.last_activity,
+        hegel_size_bytes: project.hegel_size_bytes,
+    }
+}
+
+/// Project file name: `<id>.bin`, so projects sharing a name never share a file
+fn project_file_name(id: &str) -> String {
+    format!("{}.bin", id)
+}
+
+/// Project file name used before stable IDs (sanitized project name), still read as a fallback
+fn legacy_file_name(name: &str) -> String {
+    let safe_name = name.replace(|c: char| !c.is_alphanumeric() && c != '-' && c != '_', "_");
+    format!("{}.bin", safe_name)
+}
+
+/// Write individual project to binary file with atomic write
+fn write_project(
+    project: &DiscoveredProject,
+    cache_dir: &PathBuf,
+    cipher: Option<&CacheCipher>,
+) -> Result<()> {
+    let file_name = project_file_name(&project.id());
+    let project_path = cache_dir.join(&file_name);
+    let temp_path = cache_dir.join(format!("{}.tmp", file_name));
 
     // Serialize to JSON
-    let encoded = serde_json::to_vec(&project_copy).context("Failed to serialize project")?;
+    let encoded =
+        serde_json::to_vec(&cacheable_copy(project)).context("Failed to serialize project")?;
+    let encoded = cache_crypto::seal(cipher, encoded)?;
 
     // Atomic write
     fs::write(&temp_path, encoded).context(format!(
@@ -150,12 +236,27 @@ fn write_project(project: &DiscoveredProject, cache_dir: &PathBuf) -> Result<()>
     Ok(())
 }
 
-/// Read individual project from binary file using memmap
-fn read_project(name: &str, cache_dir: &PathBuf) -> Result<Option<DiscoveredProject>> {
-    // Sanitize project name for filename
-    let safe_name = name.replace(|c: char| !c.is_alphanumeric() && c != '-' && c != '_', "_");
+/// Path of an indexed project's file (the legacy name-based file if no ID file exists yet)
+fn project_file(entry: &ProjectIndexEntry, cache_dir: &PathBuf) -> PathBuf {
+    let by_id = cache_dir.join(project_file_name(&entry.id()));
+    if by_id.exists() {
+        return by_id;
+    }
+    let legacy = cache_dir.join(legacy_file_name(&entry.name));
+    if legacy.exists() {
+        legacy
+    } else {
+        by_id
+    }
+}
 
-    let project_path = cache_dir.join(format!("{}.bin", safe_name));
+/// Read individual project from binary file
+fn read_project(
+    entry: &ProjectIndexEntry,
+    cache_dir: &PathBuf,
+    cipher: Option<&CacheCipher>,
+) -> Result<Option<DiscoveredProject>> {
+    let project_path = project_file(entry, cache_dir);
 
     if !project_path.exists() {
         return Ok(None);
@@ -166,6 +267,7 @@ fn read_project(name: &str, cache_dir: &PathBuf) -> Result<Option<DiscoveredProj
         "Failed to read project file: {}",
         project_path.display()
     ))?;
+    let contents = cache_crypto::open(cipher, &contents, &project_path)?;
 
     // Deserialize from JSON
     let project: DiscoveredProject =
@@ -174,118 +276,277 @@ fn read_project(name: &str, cache_dir: &PathBuf) -> Result<Option<DiscoveredProj
     Ok(Some(project))
 }
 
-/// Save discovered projects to binary cache (multi-file: index.bin + per-project files)
-///
-/// Index written last to ensure consistency. Project write failures logged as warnings but don't abort.
-pub fn save_binary_cache(
-    projects: &[DiscoveredProject],
-    config: &super::DiscoveryConfig,
-) -> Result<()> {
-    let cache_dir = config.cache_dir();
+/// Multi-file cache store: `index.bin` plus one `<id>.bin` file per project
+pub struct FileCacheStore {
+    cache_dir: PathBuf,
+    cipher: Option<CacheCipher>,
+}
 
-    // Create cache directory if needed
-    fs::create_dir_all(&cache_dir).context(format!(
-        "Failed to create cache directory: {}",
-        cache_dir.display()
-    ))?;
+impl FileCacheStore {
+    /// Create a store rooted at `cache_dir` (created on first write)
+    pub fn new(cache_dir: PathBuf) -> Self {
+        Self {
+            cache_dir,
+            cipher: None,
+        }
+    }
 
-    // Write each project file (skip failures, log warnings)
-    for project in projects {
-        if let Err(e) = write_project(project, &cache_dir) {
-            eprintln!("Failed to write project '{}': {}", project.name, e);
+    /// Store for `config`: its cache directory, encrypted if `cache_key_file` is set
+    pub fn for_config(config: &super::DiscoveryConfig) -> Result<Self> {
+        Ok(Self::new(config.cache_dir()).with_cipher(config.cache_cipher()?))
+    }
+
+    /// Encrypt files written from now on (and decrypt encrypted files on read)
+    pub fn with_cipher(mut self, cipher: Option<CacheCipher>) -> Self {
+        self.cipher = cipher;
+        self
+    }
+
+    /// Size in bytes of an indexed project's file (None if the file is missing)
+    pub fn project_file_size(&self, entry: &ProjectIndexEntry) -> Option<u64> {
+        fs::metadata(project_file(entry, &self.cache_dir))
+            .ok()
+            .map(|m| m.len())
+    }
+
+    /// Project files not referenced by the index (left behind by interrupted writes or renames)
+    pub fn orphaned_files(&self) -> Result<Vec<PathBuf>> {
+        let index = read_index(&self.cache_dir, self.cipher.as_ref())?.unwrap_or_default();
+        let expected: Vec<PathBuf> = index
+            .iter()
+            .map(|entry| project_file(entry, &self.cache_dir))
+            .collect();
+
+        if !self.cache_dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut orphans = Vec::new();
+        for entry in fs::read_dir(&self.cache_dir).context(format!(
+            "Failed to read cache directory: {}",
+            self.cache_dir.display()
+        ))? {
+            let path = entry?.path();
+            let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            if file_name.ends_with(".bin") && file_name != "index.bin" && !expected.contains(&path)
+            {
+                orphans.push(path);
+            }
         }
+
+        orphans.sort();
+        Ok(orphans)
     }
 
-    // Build index from all projects
-    let index: Vec<ProjectIndexEntry> = projects
-        .iter()
-        .map(|p| ProjectIndexEntry {
-            name: p.name.clone(),
-            project_path: p.project_path.clone(),
-            hegel_dir: p.hegel_dir.clone(),
-            last_activity: p.last_activity,
-        })
-        .collect();
+    /// Write one project file and its index entry (callers hold the index lock)
+    fn write_project_entry(&self, project: &DiscoveredProject) -> Result<()> {
+        let mut index = read_index(&self.cache_dir, self.cipher.as_ref())?.unwrap_or_default();
 
-    // Write index last to ensure consistency (atomic write)
-    write_index(&index, &cache_dir)?;
+        // Update index entry in place, or append a new one
+        match index.iter_mut().find(|e| e.name == project.name) {
+            Some(entry) => *entry = index_entry(project),
+            None => index.push(index_entry(project)),
+        }
 
-    Ok(())
+        // Project file first, index last (same ordering as save_all)
+        fs::create_dir_all(&self.cache_dir).context(format!(
+            "Failed to create cache directory: {}",
+            self.cache_dir.display()
+        ))?;
+        write_project(project, &self.cache_dir, self.cipher.as_ref())?;
+        write_index(&index, &self.cache_dir, self.cipher.as_ref())?;
+
+        Ok(())
+    }
+
+    /// Write every project file, then the index (callers hold the index lock)
+    fn write_all(&self, projects: &[DiscoveredProject]) -> Result<()> {
+        // Write each project file (skip failures, log warnings)
+        for project in projects {
+            if let Err(e) = write_project(project, &self.cache_dir, self.cipher.as_ref()) {
+                warn!("failed to write project '{}': {}", project.name, e);
+            }
+        }
+
+        // Build index from all projects
+        let index: Vec<ProjectIndexEntry> = projects.iter().map(index_entry).collect();
+
+        // Write index last to ensure consistency (atomic write)
+        write_index(&index, &self.cache_dir, self.cipher.as_ref())?;
+
+        Ok(())
+    }
 }
 
-/// Load discovered projects from binary cache
-///
-/// Returns `Ok(None)` if cache missing, `Err` if index corrupted. Missing/corrupted project files skipped with warnings.
-pub fn load_binary_cache(
-    config: &super::DiscoveryConfig,
-) -> Result<Option<Vec<DiscoveredProject>>> {
-    let cache_dir = config.cache_dir();
+impl CacheStore for FileCacheStore {
+    fn load_index(&self) -> Result<Option<Vec<ProjectIndexEntry>>> {
+        read_index(&self.cache_dir, self.cipher.as_ref())
+    }
 
-    let index = match read_index(&cache_dir)? {
-        Some(idx) => idx,
-        None => return Ok(None), // Cache miss
-    };
+    /// Returns `Err` if index corrupted. Missing/corrupted project files skipped with warnings.
+    fn load_all(&self) -> Result<Option<Vec<DiscoveredProject>>> {
+        let index = match read_index(&self.cache_dir, self.cipher.as_ref())? {
+            Some(idx) => idx,
+            None => return Ok(None), // Cache miss
+        };
 
-    // Skip missing/corrupted project files, continue with valid ones
-    let mut projects = Vec::new();
-    for entry in index {
-        match read_project(&entry.name, &cache_dir) {
-            Ok(Some(project)) => projects.push(project),
-            Ok(None) => {
-                eprintln!("Project file missing for: {}", entry.name);
-            }
-            Err(e) => {
-                eprintln!("Failed to load project '{}': {}", entry.name, e);
+        // Skip missing/corrupted project files, continue with valid ones
+        let mut projects = Vec::new();
+        for entry in index {
+            match read_project(&entry, &self.cache_dir, self.cipher.as_ref()) {
+                Ok(Some(project)) => projects.push(project),
+                Ok(None) => {
+                    warn!("project file missing for: {}", entry.name);
+                }
+                Err(e) => {
+                    warn!("failed to load project '{}': {}", entry.name, e);
+                }
             }
         }
+
+        Ok(Some(projects))
     }
 
-    Ok(Some(projects))
-}
+    /// Index written last to ensure consistency. Project write failures logged as warnings but don't abort.
+    fn save_all(&self, projects: &[DiscoveredProject]) -> Result<()> {
+        // Creates the cache directory if needed
+        let _lock = IndexLock::acquire(&self.cache_dir)?;
+        self.write_all(projects)
+    }
 
-/// Remove a project from the binary cache (both index and project file)
-///
-/// Returns `Ok(true)` if project was found and removed, `Ok(false)` if project not in cache.
-pub fn remove_from_cache(project_name: &str, config: &super::DiscoveryConfig) -> Result<bool> {
-    let cache_dir = config.cache_dir();
+    fn merge_and_save_all(
+        &self,
+        projects: &mut [DiscoveredProject],
+        merge: &dyn Fn(&mut [DiscoveredProject], &[DiscoveredProject]),
+    ) -> Result<()> {
+        // Held across the read too, so no other writer lands between it and the write
+        let _lock = IndexLock::acquire(&self.cache_dir)?;
+        let cached = self.load_all().ok().flatten().unwrap_or_default();
+        merge(projects, &cached);
+        self.write_all(projects)
+    }
 
-    // Load current index
-    let mut index = match read_index(&cache_dir)? {
-        Some(idx) => idx,
-        None => return Ok(false), // No cache, nothing to remove
-    };
+    fn load_project(&self, name: &str) -> Result<Option<DiscoveredProject>> {
+        let index = read_index(&self.cache_dir, self.cipher.as_ref())?.unwrap_or_default();
+        match index.iter().find(|entry| entry.name == name) {
+            Some(entry) => read_project(entry, &self.cache_dir, self.cipher.as_ref()),
+            None => Ok(None),
+        }
+    }
 
-    // Check if project exists in index
-    let original_len = index.len();
-    index.retain(|entry| entry.name != project_name);
+    fn save_project(&self, project: &DiscoveredProject) -> Result<()> {
+        // Held across the whole read-modify-write so concurrent saves don't drop entries
+        let _lock = IndexLock::acquire(&self.cache_dir)?;
+        self.write_project_entry(project)
+    }
 
-    if index.len() == original_len {
-        // Project not found in index
-        return Ok(false);
+    fn update_project(
+        &self,
+        name: &str,
+        update: Box<dyn FnOnce(&mut DiscoveredProject) + '_>,
+    ) -> Result<bool> {
+        let _lock = IndexLock::acquire(&self.cache_dir)?;
+        let Some(mut project) = self.load_project(name)? else {
+            return Ok(false);
+        };
+        update(&mut project);
+        self.write_project_entry(&project)?;
+        Ok(true)
     }
 
-    // Write updated index (atomic)
-    write_index(&index, &cache_dir)?;
+    fn remove_project(&self, name: &str) -> Result<bool> {
+        let _lock = IndexLock::acquire(&self.cache_dir)?;
 
-    // Delete individual project file (best effort, don't fail if already gone)
-    let safe_name =
-        project_name.replace(|c: char| !c.is_alphanumeric() && c != '-' && c != '_', "_");
-    let project_path = cache_dir.join(format!("{}.bin", safe_name));
-    if project_path.exists() {
-        fs::remove_file(&project_path).ok(); // Ignore errors
+        // Load current index
+        let mut index = match read_index(&self.cache_dir, self.cipher.as_ref())? {
+            Some(idx) => idx,
+            None => return Ok(false), // No cache, nothing to remove
+        };
+
+        // Check if project exists in index
+        let Some(position) = index.iter().position(|entry| entry.name == name) else {
+            // Project not found in index
+            return Ok(false);
+        };
+        let removed = index.remove(position);
+
+        // Write updated index (atomic)
+        write_index(&index, &self.cache_dir, self.cipher.as_ref())?;
+
+        // Delete individual project file (best effort, don't fail if already gone)
+        let project_path = project_file(&removed, &self.cache_dir);
+        if project_path.exists() {
+            fs::remove_file(&project_path).ok(); // Ignore errors
+        }
+
+        Ok(true)
     }
+}
 
-    Ok(true)
+/// Save discovered projects to binary cache (multi-file: index.bin + per-project files)
+///
+/// Index written last to ensure consistency. Project write failures logged as warnings but don't abort.
+pub fn save_binary_cache(
+    projects: &[DiscoveredProject],
+    config: &super::DiscoveryConfig,
+) -> Result<()> {
+    FileCacheStore::for_config(config)?.save_all(projects)
+}
+
+/// Load discovered projects from binary cache
+///
+/// Returns `Ok(None)` if cache missing, `Err` if index corrupted. Missing/corrupted project files skipped with warnings.
+pub fn load_binary_cache(
+    config: &super::DiscoveryConfig,
+) -> Result<Option<Vec<DiscoveredProject>>> {
+    FileCacheStore::for_config(config)?.load_all()
+}
+
+/// Remove a project from the configured cache store (index entry and project data)
+///
+/// Returns `Ok(true)` if project was found and removed, `Ok(false)` if project not in cache.
+pub fn remove_from_cache(project_name: &str, config: &super::DiscoveryConfig) -> Result<bool> {
+    open_store(config)?.remove_project(project_name)
+}
+
+/// Apply `update` to a cached project and write it back
+///
+/// Returns `Ok(true)` if project was found and updated, `Ok(false)` if project not in cache.
+pub fn update_cached_project(
+    project_name: &str,
+    config: &super::DiscoveryConfig,
+    update: impl FnOnce(&mut DiscoveredProject),
+) -> Result<bool> {
+    open_store(config)?.update_project(project_name, Box::new(update))
+}
+
+/// Mark a cached project archived (or unarchived), keeping its cached data
+///
+/// Returns `Ok(true)` if project was found and updated, `Ok(false)` if project not in cache.
+pub fn set_archived(
+    project_name: &str,
+    archived: bool,
+    config: &super::DiscoveryConfig,
+) -> Result<bool> {
+    update_cached_project(project_name, config, |p| p.archived = archived)
 }
 
 /// Refresh all projects in the cache (rediscover and update each one)
 ///
-/// Returns count of successfully refreshed projects.
+/// Returns count of refreshed projects (debounced or already-refreshing projects not counted).
 pub fn refresh_all_projects(config: &super::DiscoveryConfig) -> Result<usize> {
-    let cache_dir = config.cache_dir();
+    refresh_all_projects_with_progress(config, &NoProgress)
+}
+
+/// Refresh all cached projects, reporting each one to `progress`
+pub fn refresh_all_projects_with_progress(
+    config: &super::DiscoveryConfig,
+    progress: &dyn ProgressSink,
+) -> Result<usize> {
+    let store = open_store(config)?;
 
     // Load current index
-    let index = match read_index(&cache_dir)? {
+    let index = match store.load_index()? {
         Some(idx) => idx,
         None => {
             anyhow::bail!("No cache found. Run 'hegel-pm discover list' first to populate cache.")
@@ -297,46 +558,128 @@ pub fn refresh_all_projects(config: &super::DiscoveryConfig) -> Result<usize> {
     }
 
     let mut refreshed_count = 0;
-    let mut errors = Vec::new();
-
-    for entry in &index {
-        match refresh_project(&entry.name, config) {
-            Ok(_) => refreshed_count += 1,
-            Err(e) => errors.push(format!("  - {}: {}", entry.name, e)),
-        }
-    }
 
-    if !errors.is_empty() {
-        eprintln!("\nWarnings during refresh:");
-        for error in &errors {
-            eprintln!("{}", error);
+    for (i, entry) in index.iter().enumerate() {
+        let result = refresh_in_store(config, store.as_ref(), &index, &entry.name);
+        progress.emit(ProgressEvent::ProjectRefreshed {
+            name: entry.name.clone(),
+            current: i + 1,
+            total: index.len(),
+            ok: result.is_ok(),
+        });
+        match result {
+            Ok(true) => refreshed_count += 1,
+            Ok(false) => {}
+            Err(e) => warn!("failed to refresh '{}': {:#}", entry.name, e),
         }
     }
+    progress.emit(ProgressEvent::CacheWritten {
+        projects: refreshed_count,
+    });
 
     Ok(refreshed_count)
 }
 
 /// Refresh a single project in the cache (rediscover and update)
 ///
-/// Returns `Ok(true)` if project was found and refreshed, error if not in cache or path invalid.
+/// Returns `Ok(true)` if project was found and refreshed, `Ok(false)` if skipped because it was
+/// refreshed within `refresh_debounce_secs` or another process is refreshing it, error if not in
+/// cache or path invalid.
 pub fn refresh_project(project_name: &str, config: &super::DiscoveryConfig) -> Result<bool> {
-    let cache_dir = config.cache_dir();
+    let store = open_store(config)?;
 
     // Load current index
-    let mut index = match read_index(&cache_dir)? {
+    let index = match store.load_index()? {
         Some(idx) => idx,
         None => {
             anyhow::bail!("No cache found. Run 'hegel-pm discover list' first to populate cache.")
         }
     };
 
+    refresh_in_store(config, store.as_ref(), &index, project_name)
+}
+
+/// Rediscover one indexed project under its refresh lock, honoring the debounce window
+fn refresh_in_store(
+    config: &super::DiscoveryConfig,
+    store: &dyn CacheStore,
+    index: &[ProjectIndexEntry],
+    project_name: &str,
+) -> Result<bool> {
     // Find project in index
     let project_entry = index
         .iter()
         .find(|e| e.name == project_name)
         .ok_or_else(|| anyhow::anyhow!("Project '{}' not found in cache", project_name))?;
 
+    let cache_dir = config.cache_dir();
+    if let Some(window) = config.refresh_debounce() {
+        if refreshed_within(&cache_dir, &project_entry.project_path, window) {
+            return Ok(false);
+        }
+    }
+
+    // Another process (CLI or server worker) is already refreshing this project
+    let Some(lock) = RefreshLock::try_acquire(&cache_dir, &project_entry.project_path)? else {
+        return Ok(false);
+    };
+
+    let previous = store.load_project(project_name)?;
+    let mut refreshed_project = rediscover_entry(project_entry, previous.as_ref(), true)?;
+    super::run_plugins(&config.plugins, &mut refreshed_project);
+    refreshed_project.metrics_source = config.metrics_source_for(&refreshed_project);
+    // Cached totals let `discover all` and aggregates skip re-parsing the logs
+    refreshed_project.update_summary(config.statistics_budget_bytes());
+
+    // Re-read the cached project under the store lock: an archive or `note set` may have landed
+    // while the logs were parsed. The freshly computed summary wins over the cached one.
+    let updated = store.update_project(
+        project_name,
+        Box::new(|cached| {
+            let summary = refreshed_project.metrics_summary.take();
+            refreshed_project.carry_user_state(cached);
+            refreshed_project.metrics_summary = summary;
+            *cached = refreshed_project;
+        }),
+    )?;
+    if !updated {
+        // Removed from the cache while refreshing; don't bring it back
+        return Ok(false);
+    }
+    lock.mark_refreshed()?;
+
+    Ok(true)
+}
+
+/// Rediscover one indexed project from disk (nothing is written to the store)
+///
+/// User state (archive flag, metadata) is carried over from `previous`. Remote projects are
+/// re-fetched into their mirror when `fetch_remote` is set, otherwise read from the last mirror.
+pub(super) fn rediscover_entry(
+    project_entry: &ProjectIndexEntry,
+    previous: Option<&DiscoveredProject>,
+    fetch_remote: bool,
+) -> Result<DiscoveredProject> {
+    let project_name = project_entry.name.as_str();
     let project_path = project_entry.project_path.clone();
+
+    // Remote projects: re-fetch the local mirror instead of reading the path directly
+    if super::RemoteRoot::parse(&project_path).is_some() {
+        let mut refreshed_project = if fetch_remote {
+            super::remote::refresh_remote(project_name, &project_path, &project_entry.hegel_dir)?
+        } else {
+            super::remote::load_mirrored(
+                project_name.to_string(),
+                project_path,
+                project_entry.hegel_dir.clone(),
+            )
+        };
+        if let Some(previous) = previous {
+            refreshed_project.carry_user_state(previous);
+        }
+        return Ok(refreshed_project);
+    }
+
     let hegel_dir = project_path.join(".hegel");
 
     // Verify .hegel directory exists
@@ -358,32 +701,24 @@ pub fn refresh_project(project_name: &str, config: &super::DiscoveryConfig) -> R
     let last_activity = super::DiscoveredProject::calculate_last_activity(&hegel_dir)
         .unwrap_or(SystemTime::UNIX_EPOCH);
 
-    let refreshed_project = super::DiscoveredProject::new(
+    let mut refreshed_project = super::DiscoveredProject::new(
         project_name.to_string(),
         project_path.clone(),
-        hegel_dir.clone(),
+        hegel_dir,
         workflow_state,
         last_activity,
         error,
     );
-
-    // Update index entry with new last_activity
-    for entry in index.iter_mut() {
-        if entry.name == project_name {
-            entry.last_activity = last_activity;
-            entry.project_path = project_path.clone();
-            entry.hegel_dir = hegel_dir.clone();
-            break;
-        }
+    refreshed_project.git_info = super::GitInfo::collect(&project_path);
+    refreshed_project.hegel_size_bytes =
+        super::DiscoveredProject::calculate_hegel_size(&refreshed_project.hegel_dir);
+    refreshed_project.fs_identity =
+        super::DiscoveredProject::read_fs_identity(&refreshed_project.hegel_dir);
+    if let Some(previous) = previous {
+        refreshed_project.carry_user_state(previous);
     }
 
-    // Write updated index
-    write_index(&index, &cache_dir)?;
-
-    // Write refreshed project file
-    write_project(&refreshed_project, &cache_dir)?;
-
-    Ok(true)
+    Ok(refreshed_project)
 }
 
 #[cfg(test)]
@@ -447,6 +782,22 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_save_and_load_encrypted_cache() {
+        let temp = TempDir::new().unwrap();
+        let cache_file = temp.path().join("cache.json");
+        let cipher = CacheCipher::from_key([5; 32]);
+
+        let projects = vec![create_test_project("project1")];
+        save_cache_with_cipher(&projects, &cache_file, Some(&cipher)).unwrap();
+
+        let loaded = load_cache_with_cipher(&cache_file, Some(&cipher)).unwrap();
+        assert_eq!(loaded.unwrap()[0].name, "project1");
+
+        // The plaintext entry point refuses an encrypted file
+        assert!(load_cache(&cache_file).is_err());
+    }
+
     #[test]
     fn test_save_creates_parent_directory() {
         let temp = TempDir::new().unwrap();
@@ -459,6 +810,29 @@ mod tests {
         assert!(cache_file.exists());
     }
 
+    #[test]
+    fn test_concurrent_json_cache_saves() {
+        let temp = TempDir::new().unwrap();
+        let cache_file = temp.path().join("cache.json");
+
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let cache_file = cache_file.clone();
+                std::thread::spawn(move || {
+                    let projects = vec![create_test_project(&format!("project-{}", i))];
+                    save_cache(&projects, &cache_file).unwrap();
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        // One writer's complete file wins; no temp files are left behind
+        assert_eq!(load_cache(&cache_file).unwrap().unwrap().len(), 1);
+        assert_eq!(fs::read_dir(temp.path()).unwrap().count(), 1);
+    }
+
     #[test]
     fn test_atomic_write() {
         let temp = TempDir::new().unwrap();
@@ -499,7 +873,6 @@ mod tests {
         let mut project = projects[0].clone();
         // Clear fields that aren't cached
         project.statistics = None;
-        project.workflow_state = None;
 
         // Test JSON serialization (used by cache)
         let encoded = serde_json::to_vec(&project).unwrap();
@@ -523,6 +896,7 @@ mod tests {
             project_path: temp.path().to_path_buf(),
             hegel_dir: temp.path().join(".hegel"),
             last_activity: SystemTime::now(),
+            hegel_size_bytes: 0,
         };
 
         // Test JSON serialization round-trip
@@ -549,6 +923,7 @@ mod tests {
                 project_path: p.project_path.clone(),
                 hegel_dir: p.hegel_dir.clone(),
                 last_activity: p.last_activity,
+                hegel_size_bytes: p.hegel_size_bytes,
             })
             .collect();
 
@@ -568,23 +943,25 @@ mod tests {
                 project_path: temp.path().join("project1"),
                 hegel_dir: temp.path().join("project1/.hegel"),
                 last_activity: SystemTime::now(),
+                hegel_size_bytes: 0,
             },
             ProjectIndexEntry {
                 name: "project2".to_string(),
                 project_path: temp.path().join("project2"),
                 hegel_dir: temp.path().join("project2/.hegel"),
                 last_activity: SystemTime::now(),
+                hegel_size_bytes: 0,
             },
         ];
 
         // Write index
-        write_index(&index, &cache_dir).unwrap();
+        write_index(&index, &cache_dir, None).unwrap();
 
         // Verify file exists
         assert!(cache_dir.join("index.bin").exists());
 
         // Read index back
-        let loaded_index = read_index(&cache_dir).unwrap().unwrap();
+        let loaded_index = read_index(&cache_dir, None).unwrap().unwrap();
         assert_eq!(loaded_index.len(), 2);
         assert_eq!(loaded_index[0].name, "project1");
         assert_eq!(loaded_index[1].name, "project2");
@@ -595,7 +972,7 @@ mod tests {
         let temp = TempDir::new().unwrap();
         let cache_dir = temp.path().join("cache");
 
-        let result = read_index(&cache_dir).unwrap();
+        let result = read_index(&cache_dir, None).unwrap();
         assert!(result.is_none());
     }
 
@@ -616,16 +993,15 @@ mod tests {
         let project = &projects[0];
 
         // Write project
-        write_project(project, &cache_dir).unwrap();
+        write_project(project, &cache_dir, None).unwrap();
 
-        // Verify file exists (sanitized name)
-        let safe_name = project
-            .name
-            .replace(|c: char| !c.is_alphanumeric() && c != '-' && c != '_', "_");
-        assert!(cache_dir.join(format!("{}.bin", safe_name)).exists());
+        // Verify file exists (named by project ID)
+        assert!(cache_dir.join(format!("{}.bin", project.id())).exists());
 
         // Read project back
-        let loaded_project = read_project(&project.name, &cache_dir).unwrap().unwrap();
+        let loaded_project = read_project(&index_entry(project), &cache_dir, None)
+            .unwrap()
+            .unwrap();
         assert_eq!(loaded_project.name, project.name);
         assert!(loaded_project.statistics.is_none());
     }
@@ -635,43 +1011,199 @@ mod tests {
         let temp = TempDir::new().unwrap();
         let cache_dir = temp.path().join("cache");
 
-        let result = read_project("nonexistent", &cache_dir).unwrap();
+        let entry = ProjectIndexEntry {
+            name: "nonexistent".to_string(),
+            project_path: temp.path().join("nonexistent"),
+            hegel_dir: temp.path().join("nonexistent").join(".hegel"),
+            last_activity: SystemTime::UNIX_EPOCH,
+            hegel_size_bytes: 0,
+        };
+        let result = read_project(&entry, &cache_dir, None).unwrap();
         assert!(result.is_none());
     }
 
     #[test]
-    fn test_project_name_sanitization() {
+    fn test_project_files_named_by_id() {
+        let temp = TempDir::new().unwrap();
+        let cache_dir = temp.path().join("cache");
+        let store = FileCacheStore::new(cache_dir.clone());
+
+        // Same name, different paths: one file each
+        let mut first = create_test_project("api");
+        first.project_path = temp.path().join("client-a").join("api");
+        let mut second = first.clone();
+        second.project_path = temp.path().join("client-b").join("api");
+        second.name = "api (client-b)".to_string();
+        first.name = "api (client-a)".to_string();
+
+        store.save_all(&[first.clone(), second.clone()]).unwrap();
+
+        assert!(cache_dir.join(format!("{}.bin", first.id())).exists());
+        assert!(cache_dir.join(format!("{}.bin", second.id())).exists());
+        assert_eq!(
+            store
+                .load_project("api (client-b)")
+                .unwrap()
+                .unwrap()
+                .project_path,
+            second.project_path
+        );
+        assert!(store.orphaned_files().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_read_legacy_name_file() {
         let temp = TempDir::new().unwrap();
         let cache_dir = temp.path().join("cache");
         fs::create_dir_all(&cache_dir).unwrap();
 
-        let config = super::super::DiscoveryConfig::new(
-            vec![dirs::home_dir().unwrap().join("Code")],
-            10,
-            vec![],
-            temp.path().join("cache.json"),
+        // Caches written before stable IDs named files after the sanitized project name
+        let mut project = create_test_project("bad/name:here");
+        project.project_path = temp.path().join("bad");
+        fs::write(
+            cache_dir.join("bad_name_here.bin"),
+            serde_json::to_vec(&project).unwrap(),
+        )
+        .unwrap();
+        write_index(&[index_entry(&project)], &cache_dir, None).unwrap();
+
+        let store = FileCacheStore::new(cache_dir.clone());
+        let loaded = store.load_project("bad/name:here").unwrap().unwrap();
+        assert_eq!(loaded.name, "bad/name:here");
+        assert!(store.orphaned_files().unwrap().is_empty());
+
+        // Rewriting moves it to the ID-named file; the legacy file is now an orphan
+        store.save_project(&loaded).unwrap();
+        assert!(cache_dir.join(format!("{}.bin", project.id())).exists());
+        assert_eq!(
+            store.orphaned_files().unwrap(),
+            vec![cache_dir.join("bad_name_here.bin")]
         );
+    }
 
-        // Discover real project and rename it with bad chars for testing
-        let engine = super::super::DiscoveryEngine::new(config).unwrap();
-        let projects = engine.get_projects(true).unwrap();
+    #[test]
+    fn test_concurrent_saves_keep_every_entry() {
+        let temp = TempDir::new().unwrap();
+        let cache_dir = temp.path().join("cache");
 
-        if projects.is_empty() {
-            return;
+        // Separate stores stand in for separate processes sharing the cache directory
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let cache_dir = cache_dir.clone();
+                std::thread::spawn(move || {
+                    let store = FileCacheStore::new(cache_dir);
+                    let mut project = create_test_project(&format!("project-{}", i));
+                    project.project_path = PathBuf::from(format!("/tmp/project-{}", i));
+                    store.save_project(&project).unwrap();
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
         }
 
-        let mut project = projects[0].clone();
-        project.name = "bad/name:here".to_string();
+        let index = FileCacheStore::new(cache_dir)
+            .load_index()
+            .unwrap()
+            .unwrap();
+        assert_eq!(index.len(), 8);
+    }
 
-        // Write project with special characters
-        write_project(&project, &cache_dir).unwrap();
+    #[test]
+    fn test_concurrent_updates_keep_every_change() {
+        let temp = TempDir::new().unwrap();
+        let cache_dir = temp.path().join("cache");
+        let store = FileCacheStore::new(cache_dir.clone());
+        store.save_all(&[create_test_project("alpha")]).unwrap();
+
+        // Like concurrent `note set` calls from separate processes
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let cache_dir = cache_dir.clone();
+                std::thread::spawn(move || {
+                    FileCacheStore::new(cache_dir)
+                        .update_project(
+                            "alpha",
+                            Box::new(move |p| {
+                                p.metadata.insert(format!("key-{}", i), i.to_string());
+                            }),
+                        )
+                        .unwrap()
+                })
+            })
+            .collect();
+        for handle in handles {
+            assert!(handle.join().unwrap());
+        }
 
-        // Should sanitize to safe filename
-        assert!(cache_dir.join("bad_name_here.bin").exists());
+        let project = store.load_project("alpha").unwrap().unwrap();
+        assert_eq!(project.metadata.len(), 8);
+        assert!(!store.update_project("missing", Box::new(|_| {})).unwrap());
+    }
 
-        // Should still be able to read it back
-        let loaded = read_project("bad/name:here", &cache_dir).unwrap().unwrap();
-        assert_eq!(loaded.name, "bad/name:here");
+    #[test]
+    fn test_merge_and_save_all_reads_under_the_lock() {
+        let temp = TempDir::new().unwrap();
+        let cache_dir = temp.path().join("cache");
+        let store = FileCacheStore::new(cache_dir.clone());
+
+        // Archived by another process after this one started scanning
+        let mut cached = create_test_project("alpha");
+        store.save_all(std::slice::from_ref(&cached)).unwrap();
+        cached.archived = true;
+        store.save_project(&cached).unwrap();
+
+        let late = std::sync::Mutex::new(None);
+        let mut scanned = vec![create_test_project("alpha")];
+        store
+            .merge_and_save_all(&mut scanned, &|projects, cached| {
+                projects[0].archived = cached[0].archived;
+                // A concurrent save waits for the write instead of being overwritten by it
+                let cache_dir = cache_dir.clone();
+                *late.lock().unwrap() = Some(std::thread::spawn(move || {
+                    let mut project = create_test_project("beta");
+                    project.project_path = PathBuf::from("/tmp/beta");
+                    FileCacheStore::new(cache_dir)
+                        .save_project(&project)
+                        .unwrap();
+                }));
+            })
+            .unwrap();
+        late.into_inner().unwrap().unwrap().join().unwrap();
+
+        let projects = store.load_all().unwrap().unwrap();
+        let names: Vec<&str> = projects.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["alpha", "beta"]);
+        assert!(projects[0].archived);
+    }
+
+    #[test]
+    fn test_encrypted_store() {
+        let temp = TempDir::new().unwrap();
+        let cache_dir = temp.path().join("cache");
+        let plain = FileCacheStore::new(cache_dir.clone());
+        let encrypted = FileCacheStore::new(cache_dir.clone())
+            .with_cipher(Some(CacheCipher::from_key([3; 32])));
+
+        // A plaintext cache stays readable once encryption is turned on
+        let project = create_test_project("secret-project");
+        plain.save_all(std::slice::from_ref(&project)).unwrap();
+        assert_eq!(encrypted.load_all().unwrap().unwrap().len(), 1);
+
+        // ...and is encrypted on the next write
+        encrypted.save_project(&project).unwrap();
+        let index = fs::read(cache_dir.join("index.bin")).unwrap();
+        let data = fs::read(cache_dir.join(format!("{}.bin", project.id()))).unwrap();
+        assert!(cache_crypto::is_encrypted(&index));
+        assert!(cache_crypto::is_encrypted(&data));
+        assert!(!String::from_utf8_lossy(&data).contains("secret-project"));
+
+        let loaded = encrypted.load_project("secret-project").unwrap().unwrap();
+        assert_eq!(loaded.project_path, project.project_path);
+
+        // Without the key the cache can't be read
+        let err = plain.load_index().unwrap_err();
+        assert!(err.to_string().contains("encrypted"));
     }
 
     #[test]
@@ -744,10 +1276,7 @@ mod tests {
 
         // Delete one project file
         let cache_dir = config.cache_dir();
-        let first_project_name = projects[0]
-            .name
-            .replace(|c: char| !c.is_alphanumeric() && c != '-' && c != '_', "_");
-        fs::remove_file(cache_dir.join(format!("{}.bin", first_project_name))).unwrap();
+        fs::remove_file(cache_dir.join(format!("{}.bin", projects[0].id()))).unwrap();
 
         // Load should skip missing file but return others
         let loaded = load_binary_cache(&config).unwrap().unwrap();
@@ -807,9 +1336,7 @@ mod tests {
 
         // Verify project file is deleted
         let cache_dir = config.cache_dir();
-        let safe_name =
-            project_to_remove.replace(|c: char| !c.is_alphanumeric() && c != '-' && c != '_', "_");
-        assert!(!cache_dir.join(format!("{}.bin", safe_name)).exists());
+        assert!(!cache_dir.join(format!("{}.bin", projects[0].id())).exists());
     }
 
     #[test]
@@ -1003,4 +1530,45 @@ mod tests {
         assert!(err_msg.contains("not found at cached path"));
         assert!(err_msg.contains("Use 'hegel-pm remove"));
     }
+
+    #[test]
+    fn test_refresh_project_debounce_and_lock() {
+        let temp = TempDir::new().unwrap();
+        let project_path = temp.path().join("alpha");
+        fs::create_dir_all(project_path.join(".hegel")).unwrap();
+
+        let mut config = super::super::DiscoveryConfig::new(
+            vec![temp.path().to_path_buf()],
+            10,
+            vec![],
+            temp.path().join("config").join("cache.json"),
+        );
+        let project = DiscoveredProject::new(
+            "alpha".to_string(),
+            project_path.clone(),
+            project_path.join(".hegel"),
+            None,
+            SystemTime::now(),
+            None,
+        );
+        save_binary_cache(&[project], &config).unwrap();
+
+        // No debounce configured: every refresh runs
+        assert!(refresh_project("alpha", &config).unwrap());
+        assert!(refresh_project("alpha", &config).unwrap());
+
+        // Within the debounce window the refresh is skipped
+        config.refresh_debounce_secs = Some(60);
+        assert!(!refresh_project("alpha", &config).unwrap());
+        assert_eq!(refresh_all_projects(&config).unwrap(), 0);
+
+        // A held lock skips the refresh even without debounce
+        config.refresh_debounce_secs = None;
+        let lock = RefreshLock::try_acquire(&config.cache_dir(), &project_path)
+            .unwrap()
+            .unwrap();
+        assert!(!refresh_project("alpha", &config).unwrap());
+        drop(lock);
+        assert!(refresh_project("alpha", &config).unwrap());
+    }
 }
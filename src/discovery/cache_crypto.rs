@@ -0,0 +1,210 @@
+//! Optional encryption at rest for cache files (`cache_key_file` in config)
+//!
+//! Cached projects carry workflow state and metrics summaries derived from hook logs, which can
+//! name commands and file paths. With a key file configured, the project cache (the files
+//! backend's index and per-project files, and `cache.json`) is written as `HPMENC01` + 24-byte
+//! nonce + XChaCha20-Poly1305 ciphertext. Files without the magic prefix are read as plaintext,
+//! so existing caches keep loading and are encrypted on their next write.
+//!
+//! Nothing else is encrypted: remote `.hegel` mirrors (raw `hooks.jsonl`/`states.jsonl` copies
+//! under the cache directory), scan/refresh bookkeeping files, and `SnapshotStore` snapshots
+//! (kept in the config directory) are written in the clear.
+
+use anyhow::{anyhow, bail, Context, Result};
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use std::borrow::Cow;
+use std::path::Path;
+
+/// Prefix marking an encrypted cache file
+const MAGIC: &[u8; 8] = b"HPMENC01";
+
+/// XChaCha20 nonce length
+const NONCE_LEN: usize = 24;
+
+/// Cipher for cache files, keyed from a key file
+#[derive(Clone)]
+pub struct CacheCipher {
+    cipher: XChaCha20Poly1305,
+}
+
+impl std::fmt::Debug for CacheCipher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("CacheCipher(..)")
+    }
+}
+
+impl CacheCipher {
+    pub fn from_key(key: [u8; 32]) -> Self {
+        Self {
+            cipher: XChaCha20Poly1305::new(Key::from_slice(&key)),
+        }
+    }
+
+    /// Load a key file: 32 raw bytes, or 64 hex characters (surrounding whitespace ignored)
+    ///
+    /// Generate one with `head -c 32 /dev/urandom > key` or `openssl rand -hex 32 > key`.
+    pub fn load(key_file: &Path) -> Result<Self> {
+        let contents = std::fs::read(key_file).context(format!(
+            "Failed to read cache key file: {}",
+            key_file.display()
+        ))?;
+
+        let key: Vec<u8> = if contents.len() == 32 {
+            contents
+        } else {
+            let text = String::from_utf8_lossy(&contents);
+            decode_hex(text.trim()).ok_or_else(|| {
+                anyhow!(
+                    "Invalid cache key file {} (expected 32 raw bytes or 64 hex characters)",
+                    key_file.display()
+                )
+            })?
+        };
+
+        let mut bytes = [0u8; 32];
+        bytes.copy_from_slice(&key);
+        Ok(Self::from_key(bytes))
+    }
+
+    /// Encrypt `plaintext` with a fresh random nonce
+    pub fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, plaintext)
+            .map_err(|_| anyhow!("Failed to encrypt cache data"))?;
+
+        let mut out = Vec::with_capacity(MAGIC.len() + NONCE_LEN + ciphertext.len());
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&nonce);
+        out.extend_from_slice(&ciphertext);
+        Ok(out)
+    }
+
+    /// Decrypt file contents (plaintext contents are returned as-is)
+    pub fn decrypt<'a>(&self, data: &'a [u8]) -> Result<Cow<'a, [u8]>> {
+        if !is_encrypted(data) {
+            return Ok(Cow::Borrowed(data));
+        }
+        if data.len() < MAGIC.len() + NONCE_LEN {
+            bail!("Encrypted cache data is truncated");
+        }
+
+        let (nonce, ciphertext) = data[MAGIC.len()..].split_at(NONCE_LEN);
+        let plaintext = self
+            .cipher
+            .decrypt(XNonce::from_slice(nonce), ciphertext)
+            .map_err(|_| {
+                anyhow!("Failed to decrypt cache data (wrong cache_key_file, or file corrupted)")
+            })?;
+        Ok(Cow::Owned(plaintext))
+    }
+}
+
+/// Whether file contents were written by `CacheCipher::encrypt`
+pub fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+/// Encrypt `plaintext` if a cipher is configured
+pub(super) fn seal(cipher: Option<&CacheCipher>, plaintext: Vec<u8>) -> Result<Vec<u8>> {
+    match cipher {
+        Some(cipher) => cipher.encrypt(&plaintext),
+        None => Ok(plaintext),
+    }
+}
+
+/// Plaintext of file contents at `path` (decrypted if encrypted)
+///
+/// Fails if the contents are encrypted and no cipher is configured.
+pub(super) fn open<'a>(
+    cipher: Option<&CacheCipher>,
+    data: &'a [u8],
+    path: &Path,
+) -> Result<Cow<'a, [u8]>> {
+    match cipher {
+        Some(cipher) => cipher
+            .decrypt(data)
+            .context(format!("Failed to read cache file: {}", path.display())),
+        None if is_encrypted(data) => bail!(
+            "Cache file {} is encrypted, but no cache_key_file is configured",
+            path.display()
+        ),
+        None => Ok(Cow::Borrowed(data)),
+    }
+}
+
+fn decode_hex(text: &str) -> Option<Vec<u8>> {
+    if text.len() != 64 || !text.is_ascii() {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&text[i..i + 2], 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_encrypt_round_trip() {
+        let cipher = CacheCipher::from_key([7; 32]);
+        let sealed = cipher.encrypt(b"cargo test --secret").unwrap();
+
+        assert!(is_encrypted(&sealed));
+        assert!(!sealed
+            .windows(b"cargo".len())
+            .any(|window| window == b"cargo"));
+        assert_eq!(
+            cipher.decrypt(&sealed).unwrap().as_ref(),
+            b"cargo test --secret"
+        );
+
+        // Plaintext passes through (caches written before encryption was enabled)
+        assert_eq!(cipher.decrypt(b"[]").unwrap().as_ref(), b"[]");
+
+        // A different key can't read it
+        assert!(CacheCipher::from_key([8; 32]).decrypt(&sealed).is_err());
+    }
+
+    #[test]
+    fn test_open_without_cipher() {
+        let cipher = CacheCipher::from_key([7; 32]);
+        let sealed = cipher.encrypt(b"{}").unwrap();
+        let path = Path::new("index.bin");
+
+        let err = open(None, &sealed, path).unwrap_err();
+        assert!(err.to_string().contains("no cache_key_file"));
+        assert_eq!(open(None, b"{}", path).unwrap().as_ref(), b"{}");
+        assert_eq!(open(Some(&cipher), &sealed, path).unwrap().as_ref(), b"{}");
+    }
+
+    #[test]
+    fn test_load_key_file() {
+        let temp = TempDir::new().unwrap();
+
+        let raw = temp.path().join("raw.key");
+        std::fs::write(&raw, [1u8; 32]).unwrap();
+        let hex = temp.path().join("hex.key");
+        std::fs::write(&hex, format!("{}\n", "01".repeat(32))).unwrap();
+
+        let sealed = CacheCipher::load(&raw).unwrap().encrypt(b"x").unwrap();
+        assert_eq!(
+            CacheCipher::load(&hex)
+                .unwrap()
+                .decrypt(&sealed)
+                .unwrap()
+                .as_ref(),
+            b"x"
+        );
+
+        let short = temp.path().join("short.key");
+        std::fs::write(&short, "abc").unwrap();
+        assert!(CacheCipher::load(&short).is_err());
+        assert!(CacheCipher::load(&temp.path().join("missing.key")).is_err());
+    }
+}
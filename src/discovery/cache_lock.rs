@@ -0,0 +1,135 @@
+//! Cross-process lock around cache index writes
+//!
+//! The CLI (`discover`, `refresh`, `archive`...) and the server's cache manager can write the
+//! same cache directory at once. Each index read-modify-write cycle holds an advisory exclusive
+//! lock on `<cache_dir>/index.lock` (`fs2`, `flock`/`LockFileEx`), so concurrent writers queue
+//! instead of dropping each other's index entries. Readers don't lock: index writes are atomic
+//! renames, so a reader always sees a complete index.
+
+use anyhow::{bail, Context, Result};
+use fs2::FileExt;
+use std::fs::{self, File, OpenOptions};
+use std::io::ErrorKind;
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// First retry delay; doubled after each failed attempt up to `MAX_BACKOFF`
+const INITIAL_BACKOFF: Duration = Duration::from_millis(5);
+const MAX_BACKOFF: Duration = Duration::from_millis(200);
+
+/// Give up waiting for the lock after this long (a writer holds it for one index write)
+const LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Exclusive lock on a cache directory's index, released when dropped
+#[derive(Debug)]
+pub struct IndexLock {
+    file: File,
+}
+
+impl IndexLock {
+    /// Take the index lock, retrying with exponential backoff until `LOCK_TIMEOUT`
+    pub fn acquire(cache_dir: &Path) -> Result<Self> {
+        Self::acquire_within(cache_dir, LOCK_TIMEOUT)
+    }
+
+    fn acquire_within(cache_dir: &Path, timeout: Duration) -> Result<Self> {
+        fs::create_dir_all(cache_dir).context(format!(
+            "Failed to create cache directory: {}",
+            cache_dir.display()
+        ))?;
+        let lock_path = cache_dir.join("index.lock");
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&lock_path)
+            .context(format!("Failed to open lock file: {}", lock_path.display()))?;
+
+        let started = Instant::now();
+        let mut backoff = INITIAL_BACKOFF;
+        loop {
+            match file.try_lock_exclusive() {
+                Ok(()) => return Ok(Self { file }),
+                Err(e) if is_contended(&e) => {}
+                Err(e) => {
+                    return Err(e).context(format!("Failed to lock {}", lock_path.display()));
+                }
+            }
+
+            if started.elapsed() >= timeout {
+                bail!(
+                    "Timed out after {}s waiting for the cache index lock ({}); another hegel-pm \
+                     process is writing the cache",
+                    timeout.as_secs(),
+                    lock_path.display()
+                );
+            }
+            thread::sleep(backoff);
+            backoff = (backoff * 2).min(MAX_BACKOFF);
+        }
+    }
+}
+
+impl Drop for IndexLock {
+    fn drop(&mut self) {
+        // Closing the file releases the lock too; unlocking explicitly just makes it prompt
+        FileExt::unlock(&self.file).ok();
+    }
+}
+
+/// Whether a failed `try_lock_exclusive` means someone else holds the lock
+fn is_contended(error: &std::io::Error) -> bool {
+    error.kind() == ErrorKind::WouldBlock
+        || error.raw_os_error() == fs2::lock_contended_error().raw_os_error()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_lock_is_exclusive() {
+        let temp = TempDir::new().unwrap();
+        let held = IndexLock::acquire(temp.path()).unwrap();
+
+        let err = IndexLock::acquire_within(temp.path(), Duration::from_millis(20)).unwrap_err();
+        assert!(err.to_string().contains("Timed out"));
+
+        drop(held);
+        assert!(IndexLock::acquire_within(temp.path(), Duration::from_millis(20)).is_ok());
+    }
+
+    #[test]
+    fn test_waiters_take_turns() {
+        let temp = TempDir::new().unwrap();
+        let cache_dir = Arc::new(temp.path().to_path_buf());
+        let log = Arc::new(Mutex::new(Vec::new()));
+
+        let handles: Vec<_> = (0..4)
+            .map(|i| {
+                let cache_dir = Arc::clone(&cache_dir);
+                let log = Arc::clone(&log);
+                thread::spawn(move || {
+                    let _lock = IndexLock::acquire(&cache_dir).unwrap();
+                    log.lock().unwrap().push((i, "in"));
+                    thread::sleep(Duration::from_millis(10));
+                    log.lock().unwrap().push((i, "out"));
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        // Every holder leaves before the next one enters
+        let log = log.lock().unwrap();
+        assert_eq!(log.len(), 8);
+        for pair in log.chunks(2) {
+            assert_eq!(pair[0].0, pair[1].0);
+            assert_eq!((pair[0].1, pair[1].1), ("in", "out"));
+        }
+    }
+}
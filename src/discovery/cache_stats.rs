@@ -0,0 +1,133 @@
+//! Cache introspection: what is stored under `cache_dir()` and how big it is
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use super::{open_store, CacheBackend, DiscoveryConfig, FileCacheStore};
+
+/// One indexed project as stored in the cache
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CachedProjectStats {
+    pub name: String,
+    /// Size of the project's cache file (None for the sqlite backend or a missing file)
+    pub file_bytes: Option<u64>,
+    /// Project data could be read back (false for missing or corrupted entries)
+    pub loadable: bool,
+    pub has_workflow_state: bool,
+    /// Statistics stored with the project (normally cleared; loaded on demand)
+    pub has_statistics: bool,
+}
+
+/// Summary of the CLI cache
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheStats {
+    pub backend: CacheBackend,
+    pub cache_dir: PathBuf,
+    /// Index entries, in index order (empty if the cache hasn't been populated)
+    pub projects: Vec<CachedProjectStats>,
+    /// Every file under `cache_dir` (project data, index, locks, remote mirrors...)
+    pub total_bytes: u64,
+    /// When the index (or sqlite database) was last written
+    pub last_saved: Option<SystemTime>,
+}
+
+/// Collect cache statistics without scanning or modifying anything
+pub fn cache_stats(config: &DiscoveryConfig) -> Result<CacheStats> {
+    let cache_dir = config.cache_dir();
+    let store = open_store(config)?;
+    let index = store.load_index()?.unwrap_or_default();
+    let loaded = store.load_all()?.unwrap_or_default();
+    let files = FileCacheStore::new(cache_dir.clone());
+
+    let projects = index
+        .iter()
+        .map(|entry| {
+            let project = loaded.iter().find(|p| p.name == entry.name);
+            CachedProjectStats {
+                name: entry.name.clone(),
+                file_bytes: match config.cache_backend {
+                    CacheBackend::Files => files.project_file_size(entry),
+                    CacheBackend::Sqlite => None,
+                },
+                loadable: project.is_some(),
+                has_workflow_state: project.is_some_and(|p| p.has_state()),
+                has_statistics: project.is_some_and(|p| p.has_statistics()),
+            }
+        })
+        .collect();
+
+    let saved_file = match config.cache_backend {
+        CacheBackend::Files => cache_dir.join("index.bin"),
+        CacheBackend::Sqlite => cache_dir.join("cache.sqlite"),
+    };
+    let last_saved = fs::metadata(&saved_file).and_then(|m| m.modified()).ok();
+
+    Ok(CacheStats {
+        backend: config.cache_backend,
+        total_bytes: dir_size(&cache_dir),
+        cache_dir,
+        projects,
+        last_saved,
+    })
+}
+
+/// Total size of all files under `dir`, recursively (0 if missing; symlinks not followed)
+fn dir_size(dir: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+
+    entries
+        .filter_map(|e| e.ok())
+        .map(|entry| match entry.file_type() {
+            Ok(file_type) if file_type.is_dir() => dir_size(&entry.path()),
+            Ok(file_type) if file_type.is_file() => entry.metadata().map_or(0, |m| m.len()),
+            _ => 0,
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::{save_binary_cache, DiscoveredProject};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_cache_stats() {
+        let temp = TempDir::new().unwrap();
+        let config = DiscoveryConfig::new(
+            vec![temp.path().to_path_buf()],
+            10,
+            vec![],
+            temp.path().join("config").join("cache.json"),
+        );
+
+        let empty = cache_stats(&config).unwrap();
+        assert!(empty.projects.is_empty());
+        assert_eq!(empty.total_bytes, 0);
+        assert!(empty.last_saved.is_none());
+
+        let project = DiscoveredProject::new(
+            "alpha".to_string(),
+            temp.path().join("alpha"),
+            temp.path().join("alpha").join(".hegel"),
+            None,
+            SystemTime::now(),
+            None,
+        );
+        save_binary_cache(&[project], &config).unwrap();
+
+        let stats = cache_stats(&config).unwrap();
+        assert_eq!(stats.projects.len(), 1);
+        let alpha = &stats.projects[0];
+        assert!(alpha.loadable);
+        assert!(alpha.file_bytes.unwrap() > 0);
+        assert!(!alpha.has_statistics);
+        assert!(stats.total_bytes > alpha.file_bytes.unwrap());
+        assert!(stats.last_saved.is_some());
+    }
+}
@@ -0,0 +1,148 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::ProjectStatistics;
+
+/// Filter for `command_log` (all fields optional; empty query returns every command)
+#[derive(Debug, Clone, Default)]
+pub struct CommandQuery {
+    /// Keep commands containing this text (case-insensitive)
+    pub contains: Option<String>,
+    /// Keep commands run at or after this time (commands without a timestamp are dropped)
+    pub since: Option<DateTime<Utc>>,
+}
+
+/// One bash command event as recorded by the hooks
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CommandEvent {
+    pub command: String,
+    /// RFC 3339 timestamp (None if the hook didn't record one)
+    pub timestamp: Option<String>,
+}
+
+/// Raw bash command events from a project's hook metrics, in recorded order
+pub fn command_log(stats: &ProjectStatistics, query: &CommandQuery) -> Vec<CommandEvent> {
+    let events = stats
+        .hook_metrics
+        .bash_commands
+        .iter()
+        .map(|c| (c.command.as_str(), c.timestamp.as_deref()));
+
+    filter_commands(events, query)
+}
+
+fn filter_commands<'a>(
+    events: impl Iterator<Item = (&'a str, Option<&'a str>)>,
+    query: &CommandQuery,
+) -> Vec<CommandEvent> {
+    let needle = query.contains.as_ref().map(|text| text.to_lowercase());
+
+    events
+        .filter(|(command, _)| match &needle {
+            Some(needle) => command.to_lowercase().contains(needle),
+            None => true,
+        })
+        .filter(|(_, timestamp)| match query.since {
+            Some(since) => timestamp
+                .and_then(|ts| DateTime::parse_from_rfc3339(ts).ok())
+                .is_some_and(|time| time.with_timezone(&Utc) >= since),
+            None => true,
+        })
+        .map(|(command, timestamp)| CommandEvent {
+            command: command.to_string(),
+            timestamp: timestamp.map(str::to_string),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::{bash_json, stats_with};
+    use serde_json::json;
+
+    fn events() -> Vec<(&'static str, Option<&'static str>)> {
+        vec![
+            ("cargo test", Some("2025-01-06T09:00:00Z")),
+            ("rm -rf target", Some("2025-01-06T10:00:00Z")),
+            ("git status", None),
+            ("RM -RF /tmp/scratch", Some("2025-01-07T08:00:00+00:00")),
+        ]
+    }
+
+    fn commands(found: Vec<CommandEvent>) -> Vec<String> {
+        found.into_iter().map(|event| event.command).collect()
+    }
+
+    #[test]
+    fn test_filter_commands_contains_is_case_insensitive() {
+        let query = CommandQuery {
+            contains: Some("rm -rf".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            commands(filter_commands(events().into_iter(), &query)),
+            vec!["rm -rf target", "RM -RF /tmp/scratch"]
+        );
+    }
+
+    #[test]
+    fn test_filter_commands_since_drops_untimed() {
+        let query = CommandQuery {
+            since: Some(
+                DateTime::parse_from_rfc3339("2025-01-06T10:00:00Z")
+                    .unwrap()
+                    .with_timezone(&Utc),
+            ),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            commands(filter_commands(events().into_iter(), &query)),
+            vec!["rm -rf target", "RM -RF /tmp/scratch"]
+        );
+        assert_eq!(
+            filter_commands(events().into_iter(), &CommandQuery::default()).len(),
+            4
+        );
+    }
+
+    #[test]
+    fn test_command_log_from_hook_events() {
+        let stats = stats_with(json!({
+            "hook_metrics": {
+                "bash_commands": [
+                    bash_json("cargo build", Some("2025-01-06T08:59:59Z")),
+                    bash_json("cargo test --lib", Some("2025-01-06T11:30:00+02:00")),
+                    bash_json("Cargo fmt", Some("2025-01-06T09:00:00.5Z")),
+                    bash_json("cargo clippy", None),
+                ],
+            },
+        }));
+        let query = CommandQuery {
+            contains: Some("CARGO".to_string()),
+            since: Some(
+                DateTime::parse_from_rfc3339("2025-01-06T09:00:00Z")
+                    .unwrap()
+                    .with_timezone(&Utc),
+            ),
+        };
+
+        // 11:30+02:00 is 09:30 UTC; recorded order is kept
+        assert_eq!(
+            command_log(&stats, &query),
+            [
+                CommandEvent {
+                    command: "cargo test --lib".to_string(),
+                    timestamp: Some("2025-01-06T11:30:00+02:00".to_string()),
+                },
+                CommandEvent {
+                    command: "Cargo fmt".to_string(),
+                    timestamp: Some("2025-01-06T09:00:00.5Z".to_string()),
+                },
+            ]
+        );
+        assert_eq!(command_log(&stats, &CommandQuery::default()).len(), 4);
+    }
+}
@@ -1,18 +1,82 @@
 use anyhow::{bail, Context, Result};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use super::{
+    project_id, Budget, CacheBackend, CacheCipher, DiscoveredProject, Exclusions, MetricsSource,
+    NameCollisions, PluginConfig, RemoteRoot, TokenPricing, WebhookConfig,
+};
 
 /// Configuration for project discovery
+///
+/// Persisted as JSON at `~/.config/hegel-pm/config.json`; missing fields fall back to defaults.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct DiscoveryConfig {
-    /// Root directories to scan for Hegel projects
+    /// Root directories to scan for Hegel projects (local paths or `ssh://host/path`)
     pub root_directories: Vec<PathBuf>,
     /// Maximum recursion depth
     pub max_depth: usize,
-    /// Directory names to exclude from scanning
+    /// Directories to skip while scanning: names, absolute paths, or globs (see `Exclusions`)
     pub exclusions: Vec<String>,
     /// Cache file location
     pub cache_location: PathBuf,
+    /// Storage backend for the CLI cache under `cache_dir()` (and metrics snapshots)
+    pub cache_backend: CacheBackend,
+    /// How projects sharing a directory name are renamed (`parent` or `id`)
+    pub name_collisions: NameCollisions,
+    /// Skip statistics for projects whose `.hegel` logs exceed this many MB (None = no cap)
+    ///
+    /// Parsed metrics grow with the on-disk hook/state logs, so this bounds peak memory per project.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub statistics_budget_mb: Option<u64>,
+    /// Reuse directory listings whose mtime hasn't changed since the last scan (see `DirSnapshot`)
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub fast_rescan: bool,
+    /// Skip refreshing a project that was refreshed less than this many seconds ago (None = always)
+    ///
+    /// Shared by every process using the same cache directory (CLI and server workers).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refresh_debounce_secs: Option<u64>,
+    /// Flag a phase as stuck after it has been in progress this many hours (None = 24)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alert_stuck_hours: Option<u64>,
+    /// USD per million tokens for cost estimates in `digest` (None = Sonnet list prices)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_pricing: Option<TokenPricing>,
+    /// Monthly token/cost budget across all projects (None = no budget)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub budget: Option<Budget>,
+    /// Monthly budgets for single projects, keyed by project name or absolute path
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub project_budgets: BTreeMap<String, Budget>,
+    /// URLs notified when a refresh sees a workflow complete or new commits
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub webhooks: Vec<WebhookConfig>,
+    /// Commands run per project during scans; their JSON output lands in `extra`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub plugins: Vec<PluginConfig>,
+    /// Where project statistics are parsed from (None = hegel-cli's `.hegel` logs)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metrics_source: Option<MetricsSource>,
+    /// Per-project overrides of `metrics_source`, keyed by project name or absolute path
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub project_metrics_sources: BTreeMap<String, MetricsSource>,
+    /// Sort column remembered from the last `discover all --sort-by` (None = last-activity)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub all_sort_by: Option<String>,
+    /// Columns remembered from the last `discover all --columns` (None = all)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub all_columns: Option<Vec<String>>,
+    /// Key file for encrypting cache files at rest (None = plaintext; see `CacheCipher::load`)
+    ///
+    /// Files backend only. Existing plaintext files stay readable and are encrypted when next written.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_key_file: Option<PathBuf>,
+    /// Base URL of the hegel-pm-web dashboard, for `open --browser` deep links
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dashboard_url: Option<String>,
 }
 
 impl DiscoveryConfig {
@@ -28,7 +92,146 @@ impl DiscoveryConfig {
             max_depth,
             exclusions,
             cache_location,
+            cache_backend: CacheBackend::default(),
+            name_collisions: NameCollisions::default(),
+            statistics_budget_mb: None,
+            fast_rescan: false,
+            refresh_debounce_secs: None,
+            alert_stuck_hours: None,
+            token_pricing: None,
+            budget: None,
+            project_budgets: BTreeMap::new(),
+            webhooks: Vec::new(),
+            plugins: Vec::new(),
+            metrics_source: None,
+            project_metrics_sources: BTreeMap::new(),
+            all_sort_by: None,
+            all_columns: None,
+            cache_key_file: None,
+            dashboard_url: None,
+        }
+    }
+
+    /// Default config file location (`~/.config/hegel-pm/config.json`)
+    pub fn default_config_file() -> PathBuf {
+        default_config_dir().join("config.json")
+    }
+
+    /// Default metrics snapshot directory (`~/.config/hegel-pm/snapshots`)
+    pub fn default_snapshot_dir() -> PathBuf {
+        default_config_dir().join("snapshots")
+    }
+
+    /// Load configuration from the default config file, or defaults if it doesn't exist
+    pub fn load_or_default() -> Result<Self> {
+        Ok(Self::load(&Self::default_config_file())?.unwrap_or_default())
+    }
+
+    /// Load configuration from a JSON file (`Ok(None)` if the file doesn't exist)
+    pub fn load(path: &Path) -> Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let content = std::fs::read_to_string(path)
+            .context(format!("Failed to read config file: {}", path.display()))?;
+        let config = serde_json::from_str(&content)
+            .context(format!("Failed to parse config file: {}", path.display()))?;
+
+        Ok(Some(config))
+    }
+
+    /// Save configuration to a JSON file (atomic write)
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self).context("Failed to serialize config")?;
+        write_config_file(path, &json)
+    }
+
+    /// Save only the remembered `discover all` layout into a JSON config file (atomic write)
+    ///
+    /// Other keys in the file are kept as written and unset ones stay unset, so they keep
+    /// tracking the code defaults.
+    pub fn save_all_layout(&self, path: &Path) -> Result<()> {
+        let mut file: serde_json::Map<String, serde_json::Value> = if path.exists() {
+            let content = std::fs::read_to_string(path)
+                .context(format!("Failed to read config file: {}", path.display()))?;
+            serde_json::from_str(&content)
+                .context(format!("Failed to parse config file: {}", path.display()))?
+        } else {
+            serde_json::Map::new()
+        };
+
+        for (key, value) in [
+            ("all_sort_by", serde_json::to_value(&self.all_sort_by)?),
+            ("all_columns", serde_json::to_value(&self.all_columns)?),
+        ] {
+            match value {
+                serde_json::Value::Null => file.remove(key),
+                value => file.insert(key.to_string(), value),
+            };
+        }
+
+        let json = serde_json::to_string_pretty(&file).context("Failed to serialize config")?;
+        write_config_file(path, &json)
+    }
+
+    /// Add an entry (name, absolute path, or glob) to the exclusion list
+    ///
+    /// Returns `false` if it was already excluded.
+    pub fn add_exclusion(&mut self, name: &str) -> bool {
+        if self.exclusions.iter().any(|e| e == name) {
+            return false;
+        }
+        self.exclusions.push(name.to_string());
+        true
+    }
+
+    /// Remove an entry from the exclusion list
+    ///
+    /// Returns `false` if it wasn't excluded.
+    pub fn remove_exclusion(&mut self, name: &str) -> bool {
+        let original_len = self.exclusions.len();
+        self.exclusions.retain(|e| e != name);
+        self.exclusions.len() != original_len
+    }
+
+    /// Scan `roots` (if any) and/or to `max_depth` instead of the configured values
+    ///
+    /// For one-off scans: an overridden config gets its own cache under `scans/<id>/` next to
+    /// the regular cache, keyed by roots and depth, so it never replaces the regular project
+    /// list. Local roots are made absolute (missing ones are left for `validate` to report).
+    pub fn with_overrides(mut self, roots: Vec<PathBuf>, max_depth: Option<usize>) -> Self {
+        if roots.is_empty() && max_depth.is_none() {
+            return self;
+        }
+        if !roots.is_empty() {
+            self.root_directories = roots
+                .into_iter()
+                .map(|root| match RemoteRoot::parse(&root) {
+                    Some(_) => root,
+                    None => root.canonicalize().unwrap_or(root),
+                })
+                .collect();
         }
+        if let Some(depth) = max_depth {
+            self.max_depth = depth;
+        }
+
+        let key: Vec<String> = self
+            .root_directories
+            .iter()
+            .map(|root| root.display().to_string())
+            .chain([format!("depth={}", self.max_depth)])
+            .collect();
+        let scan_id = project_id(Path::new(&key.join("\n")));
+        self.cache_location = self
+            .cache_location
+            .parent()
+            .expect("Cache location must have a parent")
+            .join("scans")
+            .join(scan_id)
+            .join("cache.json");
+        self
     }
 
     /// Get the binary cache directory path
@@ -39,6 +242,53 @@ impl DiscoveryConfig {
             .join("cache")
     }
 
+    /// Per-project statistics budget in bytes (from `statistics_budget_mb`)
+    pub fn statistics_budget_bytes(&self) -> Option<u64> {
+        self.statistics_budget_mb.map(|mb| mb * 1024 * 1024)
+    }
+
+    /// Cipher for cache files, loaded from `cache_key_file` (None when encryption is off)
+    pub fn cache_cipher(&self) -> Result<Option<CacheCipher>> {
+        self.cache_key_file
+            .as_deref()
+            .map(CacheCipher::load)
+            .transpose()
+    }
+
+    /// Refresh debounce window (from `refresh_debounce_secs`)
+    pub fn refresh_debounce(&self) -> Option<std::time::Duration> {
+        self.refresh_debounce_secs
+            .map(std::time::Duration::from_secs)
+    }
+
+    /// How long a phase may stay in progress before `find_alerts` flags it
+    pub fn stuck_phase_threshold(&self) -> chrono::Duration {
+        chrono::Duration::hours(self.alert_stuck_hours.unwrap_or(24) as i64)
+    }
+
+    /// Prices used to estimate token cost
+    pub fn token_pricing(&self) -> TokenPricing {
+        self.token_pricing.clone().unwrap_or_default()
+    }
+
+    /// Metrics source for `project`: its override, else `metrics_source`
+    ///
+    /// Remote projects always use hegel (only their `.hegel` directory is mirrored).
+    pub fn metrics_source_for(&self, project: &DiscoveredProject) -> MetricsSource {
+        if RemoteRoot::parse(&project.project_path).is_some() {
+            return MetricsSource::Hegel;
+        }
+        project_entry(&self.project_metrics_sources, project)
+            .or(self.metrics_source.as_ref())
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Monthly budget for `project` alone (from `project_budgets`)
+    pub fn project_budget(&self, project: &DiscoveredProject) -> Option<&Budget> {
+        project_entry(&self.project_budgets, project)
+    }
+
     /// Validate the configuration
     pub fn validate(&self) -> Result<()> {
         // At least one root directory required
@@ -46,8 +296,14 @@ impl DiscoveryConfig {
             bail!("At least one root directory must be provided");
         }
 
-        // Check all root directories exist and are readable
+        // Check all local root directories exist and are readable (remote roots are
+        // checked when scanned, after their host and port parse)
         for root in &self.root_directories {
+            let remote = RemoteRoot::try_parse(root)
+                .context(format!("Invalid remote root: {}", root.display()))?;
+            if remote.is_some() {
+                continue;
+            }
             if !root.exists() {
                 bail!("Root directory does not exist: {}", root.display());
             }
@@ -68,6 +324,9 @@ impl DiscoveryConfig {
             bail!("Max depth must be at least 1, got {}", self.max_depth);
         }
 
+        // Exclusion globs must compile
+        Exclusions::new(&self.exclusions)?;
+
         // Verify cache location parent directory is writable
         if let Some(parent) = self.cache_location.parent() {
             if !parent.exists() {
@@ -84,21 +343,77 @@ impl DiscoveryConfig {
             std::fs::remove_file(test_file).ok();
         }
 
+        // Cache key file must be readable and well-formed
+        self.cache_cipher()?;
+
         Ok(())
     }
 }
 
+/// Per-project config entry for `project`, keyed by its name or else its absolute path
+fn project_entry<'a, T>(
+    entries: &'a BTreeMap<String, T>,
+    project: &DiscoveredProject,
+) -> Option<&'a T> {
+    entries
+        .get(&project.name)
+        .or_else(|| entries.get(project.project_path.to_string_lossy().as_ref()))
+}
+
+/// hegel-pm config directory (`~/.config/hegel-pm` or platform equivalent)
+fn default_config_dir() -> PathBuf {
+    let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    dirs::config_dir()
+        .unwrap_or_else(|| home.join(".config"))
+        .join("hegel-pm")
+}
+
+/// Write `json` to `path` via a temp file and rename, creating the parent directory
+fn write_config_file(path: &Path, json: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).context(format!(
+            "Failed to create config directory: {}",
+            parent.display()
+        ))?;
+    }
+
+    let temp_file = path.with_extension("json.tmp");
+    std::fs::write(&temp_file, json).context(format!(
+        "Failed to write temp config file: {}",
+        temp_file.display()
+    ))?;
+    std::fs::rename(&temp_file, path)
+        .context(format!("Failed to rename config file: {}", path.display()))?;
+
+    Ok(())
+}
+
+/// Default project roots under `home`
+///
+/// `~/Code`, plus `~/source` on Windows (where Visual Studio clones repositories). Only
+/// candidates that exist are used; `~/Code` if none do, so validation names the usual one.
+fn default_roots(home: &Path, windows: bool) -> Vec<PathBuf> {
+    let mut candidates = vec![home.join("Code")];
+    if windows {
+        candidates.push(home.join("source"));
+    }
+
+    let existing: Vec<PathBuf> = candidates.into_iter().filter(|p| p.is_dir()).collect();
+    if existing.is_empty() {
+        vec![home.join("Code")]
+    } else {
+        existing
+    }
+}
+
 impl Default for DiscoveryConfig {
     fn default() -> Self {
         let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
-        let code_dir = home.join("Code");
 
-        let config_dir = dirs::config_dir()
-            .unwrap_or_else(|| home.join(".config"))
-            .join("hegel-pm");
+        let config_dir = default_config_dir();
 
         Self {
-            root_directories: vec![code_dir],
+            root_directories: default_roots(&home, cfg!(windows)),
             max_depth: 10,
             exclusions: vec![
                 "node_modules".to_string(),
@@ -107,6 +422,23 @@ impl Default for DiscoveryConfig {
                 "vendor".to_string(),
             ],
             cache_location: config_dir.join("cache.json"),
+            cache_backend: CacheBackend::default(),
+            name_collisions: NameCollisions::default(),
+            statistics_budget_mb: None,
+            fast_rescan: false,
+            refresh_debounce_secs: None,
+            alert_stuck_hours: None,
+            token_pricing: None,
+            budget: None,
+            project_budgets: BTreeMap::new(),
+            webhooks: Vec::new(),
+            plugins: Vec::new(),
+            metrics_source: None,
+            project_metrics_sources: BTreeMap::new(),
+            all_sort_by: None,
+            all_columns: None,
+            cache_key_file: None,
+            dashboard_url: None,
         }
     }
 }
@@ -114,6 +446,7 @@ impl Default for DiscoveryConfig {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::fs;
     use tempfile::TempDir;
 
     #[test]
@@ -125,6 +458,23 @@ mod tests {
         assert!(config.exclusions.contains(&"node_modules".to_string()));
     }
 
+    #[test]
+    fn test_default_roots() {
+        let home = TempDir::new().unwrap();
+        let code = home.path().join("Code");
+        let source = home.path().join("source");
+
+        // Nothing exists yet: fall back to ~/Code everywhere
+        assert_eq!(default_roots(home.path(), true), vec![code.clone()]);
+
+        fs::create_dir(&source).unwrap();
+        assert_eq!(default_roots(home.path(), true), vec![source.clone()]);
+        assert_eq!(default_roots(home.path(), false), vec![code.clone()]);
+
+        fs::create_dir(&code).unwrap();
+        assert_eq!(default_roots(home.path(), true), vec![code, source]);
+    }
+
     #[test]
     fn test_config_creation() {
         let temp = TempDir::new().unwrap();
@@ -186,6 +536,21 @@ mod tests {
         assert!(result.unwrap_err().to_string().contains("at least 1"));
     }
 
+    #[test]
+    fn test_validation_invalid_exclusion() {
+        let temp = TempDir::new().unwrap();
+        let config = DiscoveryConfig::new(
+            vec![temp.path().to_path_buf()],
+            10,
+            vec!["**/examples/*".to_string(), "vendor/big".to_string()],
+            temp.path().join("cache.json"),
+        );
+
+        let result = config.validate();
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("relative path"));
+    }
+
     #[test]
     fn test_validation_success() {
         let temp = TempDir::new().unwrap();
@@ -218,6 +583,170 @@ mod tests {
         assert_eq!(config.exclusions, deserialized.exclusions);
     }
 
+    #[test]
+    fn test_save_and_load_config() {
+        let temp = TempDir::new().unwrap();
+        let config_file = temp.path().join("nested").join("config.json");
+        let config = DiscoveryConfig::new(
+            vec![temp.path().to_path_buf()],
+            3,
+            vec!["build".to_string()],
+            temp.path().join("cache.json"),
+        );
+
+        config.save(&config_file).unwrap();
+        let loaded = DiscoveryConfig::load(&config_file).unwrap().unwrap();
+
+        assert_eq!(loaded.root_directories, config.root_directories);
+        assert_eq!(loaded.max_depth, 3);
+        assert_eq!(loaded.exclusions, vec!["build".to_string()]);
+    }
+
+    #[test]
+    fn test_save_all_layout_keeps_other_keys() {
+        let temp = TempDir::new().unwrap();
+        let config_file = temp.path().join("config.json");
+        let mut config = DiscoveryConfig::default();
+        config.all_sort_by = Some("name".to_string());
+
+        // No file yet: only the layout is written, not the resolved defaults
+        config.save_all_layout(&config_file).unwrap();
+        let written: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&config_file).unwrap()).unwrap();
+        assert_eq!(written, serde_json::json!({"all_sort_by": "name"}));
+
+        // Existing keys survive; the layout keys are replaced
+        std::fs::write(&config_file, r#"{"max_depth": 4, "all_sort_by": "phase"}"#).unwrap();
+        config.all_columns = Some(vec!["name".to_string()]);
+        config.save_all_layout(&config_file).unwrap();
+        let loaded = DiscoveryConfig::load(&config_file).unwrap().unwrap();
+        assert_eq!(loaded.max_depth, 4);
+        assert_eq!(loaded.all_sort_by.as_deref(), Some("name"));
+        assert_eq!(loaded.all_columns, Some(vec!["name".to_string()]));
+        assert_eq!(
+            loaded.root_directories,
+            DiscoveryConfig::default().root_directories
+        );
+    }
+
+    #[test]
+    fn test_load_missing_config() {
+        let temp = TempDir::new().unwrap();
+        let loaded = DiscoveryConfig::load(&temp.path().join("config.json")).unwrap();
+        assert!(loaded.is_none());
+    }
+
+    #[test]
+    fn test_load_partial_config_uses_defaults() {
+        let temp = TempDir::new().unwrap();
+        let config_file = temp.path().join("config.json");
+        std::fs::write(&config_file, r#"{"max_depth": 4}"#).unwrap();
+
+        let loaded = DiscoveryConfig::load(&config_file).unwrap().unwrap();
+        assert_eq!(loaded.max_depth, 4);
+        assert_eq!(loaded.exclusions, DiscoveryConfig::default().exclusions);
+        assert_eq!(loaded.statistics_budget_bytes(), None);
+    }
+
+    #[test]
+    fn test_validation_skips_remote_roots() {
+        let temp = TempDir::new().unwrap();
+        let config = DiscoveryConfig::new(
+            vec![PathBuf::from("ssh://devbox/home/me/Code")],
+            10,
+            vec![],
+            temp.path().join("cache.json"),
+        );
+
+        assert!(config.validate().is_ok());
+
+        let config = DiscoveryConfig::new(
+            vec![PathBuf::from("ssh://-oProxyCommand=x/srv")],
+            10,
+            vec![],
+            temp.path().join("cache.json"),
+        );
+        let err = format!("{:#}", config.validate().unwrap_err());
+        assert!(err.contains("Invalid host '-oProxyCommand=x'"), "{}", err);
+    }
+
+    #[test]
+    fn test_statistics_budget() {
+        let config: DiscoveryConfig =
+            serde_json::from_str(r#"{"statistics_budget_mb": 64}"#).unwrap();
+        assert_eq!(config.statistics_budget_bytes(), Some(64 * 1024 * 1024));
+    }
+
+    #[test]
+    fn test_stuck_phase_threshold() {
+        assert_eq!(
+            DiscoveryConfig::default().stuck_phase_threshold(),
+            chrono::Duration::hours(24)
+        );
+        let config: DiscoveryConfig = serde_json::from_str(r#"{"alert_stuck_hours": 4}"#).unwrap();
+        assert_eq!(config.stuck_phase_threshold(), chrono::Duration::hours(4));
+    }
+
+    #[test]
+    fn test_metrics_source_for() {
+        let config: DiscoveryConfig = serde_json::from_str(
+            r#"{
+                "metrics_source": {"kind": "claude-code"},
+                "project_metrics_sources": {
+                    "alpha": {"kind": "hegel"},
+                    "/srv/gamma": {"kind": "jsonl", "path": "usage.jsonl"}
+                }
+            }"#,
+        )
+        .unwrap();
+        let project = |name: &str, path: &str| {
+            DiscoveredProject::new(
+                name.to_string(),
+                PathBuf::from(path),
+                PathBuf::from(path).join(".hegel"),
+                None,
+                std::time::SystemTime::UNIX_EPOCH,
+                None,
+            )
+        };
+
+        assert_eq!(
+            config.metrics_source_for(&project("alpha", "/home/dev/Code/alpha")),
+            MetricsSource::Hegel
+        );
+        assert_eq!(
+            config.metrics_source_for(&project("gamma", "/srv/gamma")),
+            MetricsSource::Jsonl {
+                path: PathBuf::from("usage.jsonl")
+            }
+        );
+        assert_eq!(
+            config.metrics_source_for(&project("beta", "/home/dev/Code/beta")),
+            MetricsSource::ClaudeCode
+        );
+        assert_eq!(
+            config.metrics_source_for(&project("remote", "ssh://devbox/srv/remote")),
+            MetricsSource::Hegel
+        );
+        assert_eq!(
+            DiscoveryConfig::default().metrics_source_for(&project("beta", "/home/dev/Code/beta")),
+            MetricsSource::Hegel
+        );
+    }
+
+    #[test]
+    fn test_add_and_remove_exclusion() {
+        let mut config = DiscoveryConfig::default();
+
+        assert!(config.add_exclusion("dist"));
+        assert!(!config.add_exclusion("dist"));
+        assert!(config.exclusions.contains(&"dist".to_string()));
+
+        assert!(config.remove_exclusion("dist"));
+        assert!(!config.remove_exclusion("dist"));
+        assert!(!config.exclusions.contains(&"dist".to_string()));
+    }
+
     #[test]
     fn test_cache_dir() {
         let temp = TempDir::new().unwrap();
@@ -231,4 +760,34 @@ mod tests {
         let cache_dir = config.cache_dir();
         assert_eq!(cache_dir, temp.path().join("config").join("cache"));
     }
+
+    #[test]
+    fn test_with_overrides_uses_separate_cache() {
+        let temp = TempDir::new().unwrap();
+        let usb = temp.path().join("usb");
+        fs::create_dir(&usb).unwrap();
+        let config = DiscoveryConfig::new(
+            vec![temp.path().join("code")],
+            10,
+            vec![],
+            temp.path().join("config").join("cache.json"),
+        );
+
+        // No overrides: unchanged
+        let same = config.clone().with_overrides(vec![], None);
+        assert_eq!(same.cache_location, config.cache_location);
+
+        let scan = config.clone().with_overrides(vec![usb.clone()], Some(3));
+        assert_eq!(scan.root_directories, vec![usb.canonicalize().unwrap()]);
+        assert_eq!(scan.max_depth, 3);
+        assert!(scan
+            .cache_dir()
+            .starts_with(temp.path().join("config").join("scans")));
+
+        // Same overrides share a cache; a different depth doesn't
+        let again = config.clone().with_overrides(vec![usb.clone()], Some(3));
+        assert_eq!(again.cache_location, scan.cache_location);
+        let deeper = config.with_overrides(vec![usb], Some(6));
+        assert_ne!(deeper.cache_location, scan.cache_location);
+    }
 }
@@ -0,0 +1,305 @@
+//! Period digests: what changed between two metrics snapshots
+//!
+//! Snapshot metrics are cumulative, so a project's activity over the period is the growth of
+//! its totals between the baseline snapshot and the latest one.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::{MetricsSnapshot, MonthTokens, ProjectMetricsSummary, ProjectSnapshot};
+
+/// Workflow node hegel moves to when a workflow finishes
+const DONE_NODE: &str = "done";
+
+/// USD per million tokens, used to estimate cost (`"token_pricing"` in config)
+///
+/// Defaults are Claude Sonnet list prices; fields left out of the config keep them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TokenPricing {
+    pub input_per_mtok: f64,
+    pub output_per_mtok: f64,
+    pub cache_creation_per_mtok: f64,
+    pub cache_read_per_mtok: f64,
+}
+
+impl Default for TokenPricing {
+    fn default() -> Self {
+        Self {
+            input_per_mtok: 3.0,
+            output_per_mtok: 15.0,
+            cache_creation_per_mtok: 3.75,
+            cache_read_per_mtok: 0.30,
+        }
+    }
+}
+
+impl TokenPricing {
+    /// Estimated USD cost of the tokens in `metrics`
+    pub fn cost(&self, metrics: &ProjectMetricsSummary) -> f64 {
+        self.tokens_cost(&MonthTokens {
+            input_tokens: metrics.total_input_tokens,
+            output_tokens: metrics.total_output_tokens,
+            cache_creation_tokens: metrics.total_cache_creation_tokens,
+            cache_read_tokens: metrics.total_cache_read_tokens,
+        })
+    }
+
+    /// Estimated USD cost of one month's tokens (`ProjectMetricsSummary::per_month`)
+    pub fn tokens_cost(&self, tokens: &MonthTokens) -> f64 {
+        let per_token = |tokens: u64, per_mtok: f64| tokens as f64 * per_mtok / 1_000_000.0;
+        per_token(tokens.input_tokens, self.input_per_mtok)
+            + per_token(tokens.output_tokens, self.output_per_mtok)
+            + per_token(tokens.cache_creation_tokens, self.cache_creation_per_mtok)
+            + per_token(tokens.cache_read_tokens, self.cache_read_per_mtok)
+    }
+}
+
+/// One project's activity over the digest period
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DigestEntry {
+    pub project_name: String,
+    pub tokens: u64,
+    pub events: usize,
+    pub phases: usize,
+    pub estimated_cost: f64,
+}
+
+/// Activity between a baseline snapshot and the latest one
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Digest {
+    /// When the baseline snapshot was taken (None = no earlier snapshot; everything is new)
+    pub since: Option<DateTime<Utc>>,
+    /// When the latest snapshot was taken
+    pub until: DateTime<Utc>,
+    pub total_tokens: u64,
+    pub total_events: usize,
+    pub estimated_cost: f64,
+    /// Projects with activity in the period, most tokens first (ties by name)
+    pub projects: Vec<DigestEntry>,
+    /// Projects in the latest snapshot but not the baseline
+    pub new_projects: Vec<String>,
+    /// Projects whose workflow reached `done` during the period
+    pub completed_workflows: Vec<String>,
+}
+
+/// Digest of the latest snapshot in `history` against the one a `period` earlier
+///
+/// The baseline is the latest snapshot taken at least `period` before the latest one; when
+/// none is that old, the oldest snapshot stands in (a shorter period, reported in `since`).
+/// Returns None when `history` is empty.
+pub fn digest(
+    history: &[MetricsSnapshot],
+    period: Duration,
+    pricing: &TokenPricing,
+) -> Option<Digest> {
+    let latest = history.iter().max_by_key(|s| s.taken_at)?;
+    let cutoff = latest.taken_at - period;
+    let baseline = history
+        .iter()
+        .filter(|s| s.taken_at <= cutoff)
+        .max_by_key(|s| s.taken_at)
+        .or_else(|| {
+            history
+                .iter()
+                .filter(|s| s.taken_at < latest.taken_at)
+                .min_by_key(|s| s.taken_at)
+        });
+
+    let before: HashMap<&str, &ProjectSnapshot> = baseline
+        .map(|s| s.projects.iter().map(|p| (p.name.as_str(), p)).collect())
+        .unwrap_or_default();
+
+    let mut projects = Vec::new();
+    let mut new_projects = Vec::new();
+    let mut completed_workflows = Vec::new();
+    for project in &latest.projects {
+        let previous = before.get(project.name.as_str());
+        if baseline.is_some() && previous.is_none() {
+            new_projects.push(project.name.clone());
+        }
+        let was_done = previous.is_some_and(|p| p.workflow_node.as_deref() == Some(DONE_NODE));
+        if project.workflow_node.as_deref() == Some(DONE_NODE) && !was_done {
+            completed_workflows.push(project.name.clone());
+        }
+
+        let growth = match previous {
+            Some(previous) => growth(&previous.metrics, &project.metrics),
+            None => project.metrics.clone(),
+        };
+        if growth.total_all_tokens > 0 || growth.total_events > 0 || growth.phase_count > 0 {
+            projects.push(DigestEntry {
+                project_name: project.name.clone(),
+                tokens: growth.total_all_tokens,
+                events: growth.total_events,
+                phases: growth.phase_count,
+                estimated_cost: pricing.cost(&growth),
+            });
+        }
+    }
+
+    projects.sort_by(|a, b| {
+        b.tokens
+            .cmp(&a.tokens)
+            .then_with(|| a.project_name.cmp(&b.project_name))
+    });
+    new_projects.sort();
+    completed_workflows.sort();
+
+    Some(Digest {
+        since: baseline.map(|s| s.taken_at),
+        until: latest.taken_at,
+        total_tokens: projects.iter().map(|p| p.tokens).sum(),
+        total_events: projects.iter().map(|p| p.events).sum(),
+        estimated_cost: projects.iter().map(|p| p.estimated_cost).sum(),
+        projects,
+        new_projects,
+        completed_workflows,
+    })
+}
+
+/// Totals added between two snapshots of a project (zero where a total shrank, e.g. after
+/// logs were archived)
+fn growth(before: &ProjectMetricsSummary, after: &ProjectMetricsSummary) -> ProjectMetricsSummary {
+    ProjectMetricsSummary {
+        total_input_tokens: after
+            .total_input_tokens
+            .saturating_sub(before.total_input_tokens),
+        total_output_tokens: after
+            .total_output_tokens
+            .saturating_sub(before.total_output_tokens),
+        total_cache_creation_tokens: after
+            .total_cache_creation_tokens
+            .saturating_sub(before.total_cache_creation_tokens),
+        total_cache_read_tokens: after
+            .total_cache_read_tokens
+            .saturating_sub(before.total_cache_read_tokens),
+        total_all_tokens: after
+            .total_all_tokens
+            .saturating_sub(before.total_all_tokens),
+        total_events: after.total_events.saturating_sub(before.total_events),
+        phase_count: after.phase_count.saturating_sub(before.phase_count),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn project(name: &str, tokens: u64, node: &str) -> ProjectSnapshot {
+        ProjectSnapshot {
+            name: name.to_string(),
+            metrics: ProjectMetricsSummary {
+                total_input_tokens: tokens,
+                total_all_tokens: tokens,
+                total_events: tokens as usize / 100,
+                ..Default::default()
+            },
+            workflow_node: Some(node.to_string()),
+        }
+    }
+
+    fn snapshot(taken_at: &str, projects: Vec<ProjectSnapshot>) -> MetricsSnapshot {
+        MetricsSnapshot {
+            taken_at: at(taken_at),
+            project_count: projects.len(),
+            total_tokens: projects.iter().map(|p| p.metrics.total_all_tokens).sum(),
+            total_events: 0,
+            total_phases: 0,
+            projects,
+        }
+    }
+
+    #[test]
+    fn test_cost() {
+        let metrics = ProjectMetricsSummary {
+            total_input_tokens: 1_000_000,
+            total_output_tokens: 100_000,
+            total_cache_read_tokens: 2_000_000,
+            ..Default::default()
+        };
+        let cost = TokenPricing::default().cost(&metrics);
+        assert!((cost - (3.0 + 1.5 + 0.6)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_weekly_digest() {
+        let history = vec![
+            snapshot(
+                "2025-01-01T09:00:00Z",
+                vec![
+                    project("alpha", 1_000, "code"),
+                    project("beta", 500, "spec"),
+                ],
+            ),
+            // Baseline: the latest snapshot a week or more before the latest
+            snapshot(
+                "2025-01-06T09:00:00Z",
+                vec![
+                    project("alpha", 2_000, "code"),
+                    project("beta", 500, "spec"),
+                ],
+            ),
+            snapshot(
+                "2025-01-13T09:00:00Z",
+                vec![
+                    project("alpha", 2_500, "done"),
+                    project("beta", 900, "plan"),
+                    project("gamma", 3_000, "spec"),
+                ],
+            ),
+        ];
+
+        let digest = digest(&history, Duration::weeks(1), &TokenPricing::default()).unwrap();
+        assert_eq!(digest.since, Some(at("2025-01-06T09:00:00Z")));
+        assert_eq!(digest.until, at("2025-01-13T09:00:00Z"));
+
+        let ranked: Vec<(&str, u64)> = digest
+            .projects
+            .iter()
+            .map(|p| (p.project_name.as_str(), p.tokens))
+            .collect();
+        assert_eq!(ranked, [("gamma", 3_000), ("alpha", 500), ("beta", 400)]);
+        assert_eq!(digest.total_tokens, 3_900);
+        assert!((digest.estimated_cost - 3_900.0 * 3.0 / 1_000_000.0).abs() < 1e-9);
+        assert_eq!(digest.new_projects, ["gamma"]);
+        assert_eq!(digest.completed_workflows, ["alpha"]);
+    }
+
+    #[test]
+    fn test_digest_without_old_enough_baseline() {
+        assert!(digest(&[], Duration::weeks(1), &TokenPricing::default()).is_none());
+
+        // Only one snapshot: no baseline, totals count in full
+        let history = vec![snapshot(
+            "2025-01-13T09:00:00Z",
+            vec![project("alpha", 2_500, "done")],
+        )];
+        let digest_one = digest(&history, Duration::weeks(1), &TokenPricing::default()).unwrap();
+        assert_eq!(digest_one.since, None);
+        assert_eq!(digest_one.total_tokens, 2_500);
+        assert!(digest_one.new_projects.is_empty());
+        assert_eq!(digest_one.completed_workflows, ["alpha"]);
+
+        // Nothing a week old: the oldest snapshot is the baseline
+        let history = vec![
+            snapshot(
+                "2025-01-10T09:00:00Z",
+                vec![project("alpha", 2_000, "code")],
+            ),
+            snapshot(
+                "2025-01-13T09:00:00Z",
+                vec![project("alpha", 2_500, "code")],
+            ),
+        ];
+        let digest = digest(&history, Duration::weeks(1), &TokenPricing::default()).unwrap();
+        assert_eq!(digest.since, Some(at("2025-01-10T09:00:00Z")));
+        assert_eq!(digest.total_tokens, 500);
+    }
+}
@@ -1,15 +1,67 @@
 use anyhow::Result;
 use std::time::SystemTime;
 
-use super::{find_hegel_directories, load_state, DiscoveredProject, DiscoveryConfig};
+use super::remote::discover_remote;
+use super::{
+    disambiguate_names, find_hegel_directories, find_hegel_directories_fast, load_state,
+    run_plugins, DirSnapshot, DiscoveredProject, DiscoveryConfig, GitInfo, NoProgress,
+    ProgressEvent, ProgressSink, RemoteRoot,
+};
+use crate::warn;
 
 /// Discover all Hegel projects based on configuration
 pub fn discover_projects(config: &DiscoveryConfig) -> Result<Vec<DiscoveredProject>> {
+    discover_projects_with_progress(config, &NoProgress)
+}
+
+/// Discover all Hegel projects, reporting each one found to `progress`
+pub fn discover_projects_with_progress(
+    config: &DiscoveryConfig,
+    progress: &dyn ProgressSink,
+) -> Result<Vec<DiscoveredProject>> {
     let mut all_projects = Vec::new();
+    progress.emit(ProgressEvent::ScanStarted {
+        roots: config.root_directories.len(),
+    });
+
+    // Fast rescans start from the previous scan's directory mtimes and record this scan's
+    let cache_dir = config.cache_dir();
+    let previous_dirs = if config.fast_rescan {
+        DirSnapshot::load(&cache_dir)
+    } else {
+        DirSnapshot::default()
+    };
+    let mut scanned_dirs = DirSnapshot::default();
 
     // Scan each root directory
     for root in &config.root_directories {
-        let hegel_dirs = find_hegel_directories(root, config.max_depth, &config.exclusions)?;
+        // Unreachable hosts (and unusable ssh:// roots) shouldn't hide local projects
+        match RemoteRoot::try_parse(root) {
+            Ok(Some(remote)) => {
+                match discover_remote(config, &remote, &remote.transport(), progress) {
+                    Ok(projects) => all_projects.extend(projects),
+                    Err(e) => warn!("skipping remote root {}: {}", root.display(), e),
+                }
+                continue;
+            }
+            Err(e) => {
+                warn!("skipping remote root {}: {}", root.display(), e);
+                continue;
+            }
+            Ok(None) => {}
+        }
+
+        let hegel_dirs = if config.fast_rescan {
+            find_hegel_directories_fast(
+                root,
+                config.max_depth,
+                &config.exclusions,
+                &previous_dirs,
+                &mut scanned_dirs,
+            )?
+        } else {
+            find_hegel_directories(root, config.max_depth, &config.exclusions)?
+        };
 
         for project_path in hegel_dirs {
             let hegel_dir = project_path.join(".hegel");
@@ -31,7 +83,9 @@ pub fn discover_projects(config: &DiscoveryConfig) -> Result<Vec<DiscoveredProje
             let last_activity = DiscoveredProject::calculate_last_activity(&hegel_dir)
                 .unwrap_or(SystemTime::UNIX_EPOCH);
 
-            let project = DiscoveredProject::new(
+            let git_info = GitInfo::collect(&project_path);
+
+            let mut project = DiscoveredProject::new(
                 name,
                 project_path,
                 hegel_dir,
@@ -39,13 +93,39 @@ pub fn discover_projects(config: &DiscoveryConfig) -> Result<Vec<DiscoveredProje
                 last_activity,
                 error,
             );
-
+            project.git_info = git_info;
+            project.hegel_size_bytes = DiscoveredProject::calculate_hegel_size(&project.hegel_dir);
+            project.fs_identity = DiscoveredProject::read_fs_identity(&project.hegel_dir);
+            run_plugins(&config.plugins, &mut project);
+
+            progress.emit(ProgressEvent::ProjectScanned {
+                name: project.name.clone(),
+                path: project.project_path.clone(),
+            });
             all_projects.push(project);
         }
     }
 
+    if config.fast_rescan {
+        // Best effort: a missing snapshot only makes the next scan a full one
+        if let Err(e) = scanned_dirs.save(&cache_dir) {
+            warn!("failed to save directory mtimes: {}", e);
+        }
+    }
+
+    // Names must be unique across roots (cache keys and lookups go by name)
+    disambiguate_names(&mut all_projects, config.name_collisions);
+
+    // Overrides may name a project, so sources are picked once names are final
+    for project in &mut all_projects {
+        project.metrics_source = config.metrics_source_for(project);
+    }
+
     // Sort by last activity (most recent first)
     all_projects.sort();
+    progress.emit(ProgressEvent::ScanFinished {
+        projects: all_projects.len(),
+    });
 
     Ok(all_projects)
 }
@@ -139,6 +219,26 @@ mod tests {
         assert!(!projects[0].has_state());
     }
 
+    #[test]
+    fn test_discover_measures_hegel_size_with_archives() {
+        let temp = TempDir::new().unwrap();
+        create_test_project(temp.path(), "project1", false);
+        let archive = temp.path().join("project1/.hegel/archives/2025-01-01");
+        fs::create_dir_all(&archive).unwrap();
+        fs::write(archive.join("hooks.jsonl"), "x".repeat(100)).unwrap();
+
+        let config = DiscoveryConfig::new(
+            vec![temp.path().to_path_buf()],
+            10,
+            vec![],
+            temp.path().join("cache.json"),
+        );
+
+        let projects = discover_projects(&config).unwrap();
+
+        assert_eq!(projects[0].hegel_size_bytes, 100);
+    }
+
     #[test]
     fn test_discover_empty_workspace() {
         let temp = TempDir::new().unwrap();
@@ -197,4 +297,74 @@ mod tests {
 
         assert_eq!(projects.len(), 2);
     }
+
+    #[test]
+    fn test_discover_disambiguates_colliding_names() {
+        let temp = TempDir::new().unwrap();
+        create_test_project(&temp.path().join("client-a"), "api", true);
+        create_test_project(&temp.path().join("client-b"), "api", true);
+
+        let config = DiscoveryConfig::new(
+            vec![temp.path().to_path_buf()],
+            10,
+            vec![],
+            temp.path().join("cache.json"),
+        );
+
+        let mut names: Vec<String> = discover_projects(&config)
+            .unwrap()
+            .into_iter()
+            .map(|p| p.name)
+            .collect();
+        names.sort();
+
+        assert_eq!(names, vec!["api (client-a)", "api (client-b)"]);
+    }
+
+    #[test]
+    fn test_discover_fast_rescan() {
+        let temp = TempDir::new().unwrap();
+        let code = temp.path().join("code");
+        create_test_project(&code, "project1", true);
+
+        let mut config = DiscoveryConfig::new(
+            vec![code.clone()],
+            10,
+            vec![],
+            temp.path().join("config").join("cache.json"),
+        );
+        config.fast_rescan = true;
+
+        assert_eq!(discover_projects(&config).unwrap().len(), 1);
+        assert!(!DirSnapshot::load(&config.cache_dir()).is_empty());
+
+        // Second scan starts from the recorded mtimes and finds the same projects
+        let projects = discover_projects(&config).unwrap();
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].name, "project1");
+    }
+
+    #[test]
+    fn test_discover_emits_progress() {
+        use crate::discovery::progress::tests::RecordingProgress;
+
+        let temp = TempDir::new().unwrap();
+        create_test_project(temp.path(), "project1", true);
+        let config = DiscoveryConfig::new(
+            vec![temp.path().to_path_buf()],
+            10,
+            vec![],
+            temp.path().join("cache.json"),
+        );
+
+        let progress = RecordingProgress::default();
+        discover_projects_with_progress(&config, &progress).unwrap();
+
+        let events = progress.events.lock().unwrap();
+        assert_eq!(events[0], ProgressEvent::ScanStarted { roots: 1 });
+        assert!(
+            matches!(&events[1], ProgressEvent::ProjectScanned { name, .. } if name == "project1")
+        );
+        assert_eq!(events[2], ProgressEvent::ScanFinished { projects: 1 });
+    }
 }
@@ -0,0 +1,533 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Instant, SystemTime};
+
+use super::{
+    find_hegel_directories, load_state, open_store, CacheBackend, DiscoveredProject,
+    DiscoveryConfig, FileCacheStore, ProjectIndexEntry, RemoteRoot,
+};
+
+/// How serious a doctor finding is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Ok,
+    Warning,
+    Error,
+}
+
+/// One diagnostic result
+#[derive(Debug, Clone, Serialize)]
+pub struct Finding {
+    pub severity: Severity,
+    /// What was checked (root path, project name, cache file)
+    pub subject: String,
+    pub message: String,
+    /// Suggested fix, if there is one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suggestion: Option<String>,
+}
+
+/// Time spent loading one project found on disk
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectLoadTime {
+    pub name: String,
+    pub project_path: PathBuf,
+    pub state_ms: u128,
+    pub metrics_ms: u128,
+}
+
+impl ProjectLoadTime {
+    pub fn total_ms(&self) -> u128 {
+        self.state_ms + self.metrics_ms
+    }
+}
+
+/// Result of `diagnose`: findings grouped by check, plus per-project load times
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DoctorReport {
+    pub config: Vec<Finding>,
+    pub cache: Vec<Finding>,
+    pub projects: Vec<Finding>,
+    /// Slowest first
+    pub load_times: Vec<ProjectLoadTime>,
+}
+
+impl DoctorReport {
+    /// All findings in check order
+    pub fn findings(&self) -> impl Iterator<Item = &Finding> {
+        self.config
+            .iter()
+            .chain(self.cache.iter())
+            .chain(self.projects.iter())
+    }
+
+    /// Number of findings at `severity`
+    pub fn count(&self, severity: Severity) -> usize {
+        self.findings().filter(|f| f.severity == severity).count()
+    }
+
+    /// True if no check reported an error
+    pub fn is_healthy(&self) -> bool {
+        self.count(Severity::Error) == 0
+    }
+}
+
+impl Finding {
+    fn new(severity: Severity, subject: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity,
+            subject: subject.into(),
+            message: message.into(),
+            suggestion: None,
+        }
+    }
+
+    fn suggest(mut self, suggestion: impl Into<String>) -> Self {
+        self.suggestion = Some(suggestion.into());
+        self
+    }
+}
+
+/// Run all diagnostics for `config`
+///
+/// Never fails: problems (including an invalid config) are reported as findings, so this
+/// works in exactly the situations where `DiscoveryEngine::new` would refuse to start.
+pub fn diagnose(config: &DiscoveryConfig) -> DoctorReport {
+    let config_file = DiscoveryConfig::default_config_file();
+    let config_findings = check_config(config, &config_file.display().to_string());
+
+    let (cache_findings, index) = check_cache(config);
+    let (project_findings, load_times) = check_projects(config, index.as_deref());
+
+    DoctorReport {
+        config: config_findings,
+        cache: cache_findings,
+        projects: project_findings,
+        load_times,
+    }
+}
+
+/// Load the config file at `path` and diagnose it
+///
+/// A file that can't be read or parsed is reported as the first config finding, and the other
+/// checks run against the default config.
+pub fn diagnose_config_file(path: &Path) -> DoctorReport {
+    match DiscoveryConfig::load(path) {
+        Ok(config) => diagnose(&config.unwrap_or_default()),
+        Err(e) => {
+            let mut report = diagnose(&DiscoveryConfig::default());
+            report.config.insert(0, load_failure(&e));
+            report
+        }
+    }
+}
+
+/// Finding for a config file `DiscoveryConfig::load` rejected
+fn load_failure(error: &anyhow::Error) -> Finding {
+    // The error message names the file
+    Finding::new(Severity::Error, "config file", format!("{:#}", error))
+        .suggest("Fix the file, or move it aside to use the defaults (the checks below use them)")
+}
+
+fn check_config(config: &DiscoveryConfig, config_file: &str) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let fix_roots = format!("Edit root_directories in {}", config_file);
+
+    if config.root_directories.is_empty() {
+        findings.push(
+            Finding::new(Severity::Error, "roots", "No root directories configured")
+                .suggest(fix_roots.clone()),
+        );
+    }
+
+    for root in &config.root_directories {
+        let subject = root.display().to_string();
+        if let Err(e) = RemoteRoot::try_parse(root) {
+            findings.push(
+                Finding::new(Severity::Error, subject, e.to_string()).suggest(fix_roots.clone()),
+            );
+        } else if RemoteRoot::parse(root).is_some() {
+            findings.push(Finding::new(
+                Severity::Ok,
+                subject,
+                "Remote root (reachability checked during scan)",
+            ));
+        } else if !root.exists() {
+            findings.push(
+                Finding::new(Severity::Error, subject, "Root directory does not exist")
+                    .suggest(fix_roots.clone()),
+            );
+        } else if !root.is_dir() {
+            findings.push(
+                Finding::new(Severity::Error, subject, "Root path is not a directory")
+                    .suggest(fix_roots.clone()),
+            );
+        } else if let Err(e) = std::fs::read_dir(root) {
+            findings.push(
+                Finding::new(
+                    Severity::Error,
+                    subject,
+                    format!("Root directory is not readable: {}", e),
+                )
+                .suggest("Check directory permissions"),
+            );
+        } else {
+            findings.push(Finding::new(
+                Severity::Ok,
+                subject,
+                "Root directory readable",
+            ));
+        }
+    }
+
+    if config.max_depth < 1 {
+        findings.push(
+            Finding::new(
+                Severity::Error,
+                "max_depth",
+                format!("Max depth must be at least 1, got {}", config.max_depth),
+            )
+            .suggest(format!("Set max_depth in {}", config_file)),
+        );
+    }
+
+    findings
+}
+
+/// Cache integrity findings plus the index (if readable) for cross-checking against disk
+fn check_cache(config: &DiscoveryConfig) -> (Vec<Finding>, Option<Vec<ProjectIndexEntry>>) {
+    let mut findings = Vec::new();
+    let rebuild = "Run `hegel-pm discover list --no-cache` to rebuild the cache";
+
+    let store = match open_store(config) {
+        Ok(store) => store,
+        Err(e) => {
+            findings.push(Finding::new(Severity::Error, "cache", format!("{:#}", e)));
+            return (findings, None);
+        }
+    };
+
+    let index = match store.load_index() {
+        Ok(Some(index)) => index,
+        Ok(None) => {
+            findings.push(
+                Finding::new(Severity::Warning, "cache", "Cache not populated yet")
+                    .suggest("Run `hegel-pm discover list` to scan and cache projects"),
+            );
+            return (findings, None);
+        }
+        Err(e) => {
+            findings.push(
+                Finding::new(
+                    Severity::Error,
+                    "cache index",
+                    format!("Index unreadable: {:#}", e),
+                )
+                .suggest(rebuild),
+            );
+            return (findings, None);
+        }
+    };
+
+    let mut problems = 0;
+    for entry in &index {
+        match store.load_project(&entry.name) {
+            Ok(Some(_)) => {}
+            Ok(None) => {
+                problems += 1;
+                findings.push(
+                    Finding::new(
+                        Severity::Error,
+                        entry.name.clone(),
+                        "Listed in cache index but project data is missing",
+                    )
+                    .suggest(format!("Run `hegel-pm refresh {}`", entry.name)),
+                );
+            }
+            Err(e) => {
+                problems += 1;
+                findings.push(
+                    Finding::new(
+                        Severity::Error,
+                        entry.name.clone(),
+                        format!("Cached project data corrupted: {:#}", e),
+                    )
+                    .suggest(format!("Run `hegel-pm refresh {}`", entry.name)),
+                );
+            }
+        }
+
+        if !entry.hegel_dir.exists() {
+            problems += 1;
+            findings.push(
+                Finding::new(
+                    Severity::Warning,
+                    entry.name.clone(),
+                    format!("Cached but {} no longer exists", entry.hegel_dir.display()),
+                )
+                .suggest(format!(
+                    "Run `hegel-pm remove {}` to stop tracking it",
+                    entry.name
+                )),
+            );
+        }
+    }
+
+    if config.cache_backend == CacheBackend::Files {
+        match FileCacheStore::for_config(config).and_then(|store| store.orphaned_files()) {
+            Ok(orphans) => {
+                for path in orphans {
+                    problems += 1;
+                    findings.push(
+                        Finding::new(
+                            Severity::Warning,
+                            path.display().to_string(),
+                            "Project file not referenced by the cache index",
+                        )
+                        .suggest("Safe to delete"),
+                    );
+                }
+            }
+            Err(e) => findings.push(Finding::new(
+                Severity::Error,
+                "cache",
+                format!("Failed to scan cache directory: {:#}", e),
+            )),
+        }
+    }
+
+    if problems == 0 {
+        findings.push(Finding::new(
+            Severity::Ok,
+            "cache",
+            format!("{} project(s) cached, index consistent", index.len()),
+        ));
+    }
+
+    (findings, Some(index))
+}
+
+/// Scan roots, load each project's state and metrics, and compare against the cache index
+fn check_projects(
+    config: &DiscoveryConfig,
+    index: Option<&[ProjectIndexEntry]>,
+) -> (Vec<Finding>, Vec<ProjectLoadTime>) {
+    let mut findings = Vec::new();
+    let mut load_times = Vec::new();
+    let mut seen: HashMap<String, PathBuf> = HashMap::new();
+
+    for root in config.root_directories.iter().filter(|r| r.is_dir()) {
+        let project_paths = match find_hegel_directories(root, config.max_depth, &config.exclusions)
+        {
+            Ok(paths) => paths,
+            Err(e) => {
+                findings.push(Finding::new(
+                    Severity::Error,
+                    root.display().to_string(),
+                    format!("Scan failed: {:#}", e),
+                ));
+                continue;
+            }
+        };
+
+        for project_path in project_paths {
+            let hegel_dir = project_path.join(".hegel");
+            let name = project_path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("unknown")
+                .to_string();
+
+            let started = Instant::now();
+            let state = load_state(&hegel_dir);
+            let state_ms = started.elapsed().as_millis();
+
+            match &state {
+                Ok(_) => {}
+                Err(e) => findings.push(
+                    Finding::new(
+                        Severity::Error,
+                        name.clone(),
+                        format!("Corrupted state.json: {:#}", e),
+                    )
+                    .suggest(format!(
+                        "Fix or delete {} (the project is listed with an error until then)",
+                        hegel_dir.join("state.json").display()
+                    )),
+                ),
+            }
+
+            let mut project = DiscoveredProject::new(
+                name.clone(),
+                project_path.clone(),
+                hegel_dir,
+                None,
+                SystemTime::UNIX_EPOCH,
+                None,
+            );
+            let started = Instant::now();
+            if let Err(e) = project.load_statistics() {
+                findings.push(Finding::new(
+                    Severity::Warning,
+                    name.clone(),
+                    format!("Metrics failed to load: {:#}", e),
+                ));
+            }
+            let metrics_ms = started.elapsed().as_millis();
+
+            if let Some(first) = seen.get(&name) {
+                findings.push(
+                    Finding::new(
+                        Severity::Warning,
+                        name.clone(),
+                        format!(
+                            "Name also used by {}; only one is addressable by name",
+                            first.display()
+                        ),
+                    )
+                    .suggest("Rename one of the project directories or exclude one"),
+                );
+            } else {
+                seen.insert(name.clone(), project_path.clone());
+            }
+
+            if let Some(index) = index {
+                if !index.iter().any(|e| e.project_path == project_path) {
+                    findings.push(
+                        Finding::new(
+                            Severity::Warning,
+                            name.clone(),
+                            format!("Found at {} but not cached", project_path.display()),
+                        )
+                        .suggest("Run `hegel-pm discover list --no-cache` to pick it up"),
+                    );
+                }
+            }
+
+            load_times.push(ProjectLoadTime {
+                name,
+                project_path,
+                state_ms,
+                metrics_ms,
+            });
+        }
+    }
+
+    findings.push(Finding::new(
+        Severity::Ok,
+        "scan",
+        format!(
+            "{} project(s) found within max depth {}",
+            load_times.len(),
+            config.max_depth
+        ),
+    ));
+
+    load_times.sort_by(|a, b| b.total_ms().cmp(&a.total_ms()));
+    (findings, load_times)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn config_for(temp: &TempDir) -> DiscoveryConfig {
+        DiscoveryConfig::new(
+            vec![temp.path().join("code")],
+            10,
+            vec![],
+            temp.path().join("config").join("cache.json"),
+        )
+    }
+
+    fn create_project(temp: &TempDir, name: &str, state: &str) {
+        let hegel_dir = temp.path().join("code").join(name).join(".hegel");
+        fs::create_dir_all(&hegel_dir).unwrap();
+        fs::write(hegel_dir.join("state.json"), state).unwrap();
+    }
+
+    #[test]
+    fn test_missing_root_is_error() {
+        let temp = TempDir::new().unwrap();
+        let report = diagnose(&config_for(&temp));
+
+        assert!(!report.is_healthy());
+        assert_eq!(report.config[0].severity, Severity::Error);
+        assert!(report.config[0].suggestion.is_some());
+    }
+
+    #[test]
+    fn test_unparseable_config_file_is_error() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("config.json");
+        fs::write(&path, r#"{"max_depth": "ten"}"#).unwrap();
+
+        let error = DiscoveryConfig::load(&path).unwrap_err();
+        let finding = load_failure(&error);
+
+        assert_eq!(finding.severity, Severity::Error);
+        assert_eq!(finding.subject, "config file");
+        assert!(finding
+            .message
+            .starts_with(&format!("Failed to parse config file: {}", path.display())));
+        assert!(finding.suggestion.unwrap().contains("defaults"));
+    }
+
+    #[test]
+    fn test_corrupted_state_reported_with_parse_error() {
+        let temp = TempDir::new().unwrap();
+        create_project(&temp, "broken", "{ not json");
+        create_project(&temp, "fine", "{}");
+
+        let report = diagnose(&config_for(&temp));
+
+        let broken: Vec<&Finding> = report
+            .projects
+            .iter()
+            .filter(|f| f.subject == "broken" && f.severity == Severity::Error)
+            .collect();
+        assert_eq!(broken.len(), 1);
+        assert!(broken[0].message.starts_with("Corrupted state.json"));
+        assert_eq!(report.load_times.len(), 2);
+    }
+
+    #[test]
+    fn test_uncached_project_and_missing_cache_entry() {
+        let temp = TempDir::new().unwrap();
+        let config = config_for(&temp);
+        create_project(&temp, "cached", "{}");
+
+        let report = diagnose(&config);
+        assert!(report
+            .cache
+            .iter()
+            .any(|f| f.message == "Cache not populated yet"));
+
+        let store = open_store(&config).unwrap();
+        store
+            .save_all(&super::super::discover_projects(&config).unwrap())
+            .unwrap();
+        create_project(&temp, "new", "{}");
+        let cached_id = super::super::project_id(&temp.path().join("code").join("cached"));
+        fs::remove_file(config.cache_dir().join(format!("{}.bin", cached_id))).unwrap();
+        fs::write(config.cache_dir().join("stale.bin"), "{}").unwrap();
+
+        let report = diagnose(&config);
+        assert!(report
+            .cache
+            .iter()
+            .any(|f| f.subject == "cached" && f.severity == Severity::Error));
+        assert!(report
+            .cache
+            .iter()
+            .any(|f| f.subject.ends_with("stale.bin")));
+        assert!(report
+            .projects
+            .iter()
+            .any(|f| f.subject == "new" && f.message.contains("not cached")));
+    }
+}
@@ -1,8 +1,11 @@
 use anyhow::Result;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Arc;
 
 use super::{
-    discover_projects, load_binary_cache, load_cache, save_binary_cache, save_cache,
-    DiscoveredProject, DiscoveryConfig,
+    discover_projects_with_progress, load_cache_with_cipher, open_store, save_cache_with_cipher,
+    DiscoveredProject, DiscoveryConfig, NoProgress, ProgressEvent, ProgressSink,
 };
 use crate::debug;
 
@@ -10,13 +13,23 @@ use crate::debug;
 #[derive(Clone)]
 pub struct DiscoveryEngine {
     config: DiscoveryConfig,
+    progress: Arc<dyn ProgressSink>,
 }
 
 impl DiscoveryEngine {
     /// Create a new discovery engine with configuration
     pub fn new(config: DiscoveryConfig) -> Result<Self> {
         config.validate()?;
-        Ok(Self { config })
+        Ok(Self {
+            config,
+            progress: Arc::new(NoProgress),
+        })
+    }
+
+    /// Report scan and cache progress to `progress` (events are discarded by default)
+    pub fn with_progress(mut self, progress: Arc<dyn ProgressSink>) -> Self {
+        self.progress = progress;
+        self
     }
 
     /// Get projects, using cache if available or scanning if not
@@ -27,22 +40,24 @@ impl DiscoveryEngine {
             return self.scan_and_cache();
         }
 
-        // Try to load from binary cache first
-        match load_binary_cache(&self.config)? {
+        // Try to load from the cache store first
+        let store = open_store(&self.config)?;
+        match store.load_all()? {
             Some(projects) => {
-                debug!("✅ Loaded {} projects from binary cache", projects.len());
+                debug!("✅ Loaded {} projects from cache store", projects.len());
                 Ok(projects)
             }
             None => {
-                // No binary cache, try JSON cache for backward compatibility
-                match load_cache(&self.config.cache_location)? {
+                // Store empty, try JSON cache for backward compatibility
+                let cipher = self.config.cache_cipher()?;
+                match load_cache_with_cipher(&self.config.cache_location, cipher.as_ref())? {
                     Some(projects) => {
                         debug!(
-                            "✅ Loaded {} projects from JSON cache (migrating to binary)",
+                            "✅ Loaded {} projects from JSON cache (migrating to cache store)",
                             projects.len()
                         );
-                        // Migrate to binary cache
-                        save_binary_cache(&projects, &self.config)?;
+                        // Migrate to the cache store
+                        store.save_all(&projects)?;
                         Ok(projects)
                     }
                     None => {
@@ -57,14 +72,22 @@ impl DiscoveryEngine {
 
     /// Scan for projects and update cache
     pub fn scan_and_cache(&self) -> Result<Vec<DiscoveredProject>> {
-        let projects = discover_projects(&self.config)?;
-        debug!("💾 Saving {} projects to binary cache", projects.len());
-        save_binary_cache(&projects, &self.config)?;
+        let mut projects = discover_projects_with_progress(&self.config, self.progress.as_ref())?;
+        let store = open_store(&self.config)?;
+
+        // Carry archive flags and metadata over from the cache as of the write (a corrupted
+        // cache just loses them)
+        debug!("💾 Saving {} projects to cache store", projects.len());
+        store.merge_and_save_all(&mut projects, &carry_over)?;
+        self.progress.emit(ProgressEvent::CacheWritten {
+            projects: projects.len(),
+        });
         let cache_dir = self.config.cache_dir();
-        debug!("✅ Binary cache saved to {}", cache_dir.display());
+        debug!("✅ Cache saved to {}", cache_dir.display());
 
         // Also save JSON cache for data_layer compatibility
-        save_cache(&projects, &self.config.cache_location)?;
+        let cipher = self.config.cache_cipher()?;
+        save_cache_with_cipher(&projects, &self.config.cache_location, cipher.as_ref())?;
 
         Ok(projects)
     }
@@ -73,6 +96,43 @@ impl DiscoveryEngine {
     pub fn config(&self) -> &DiscoveryConfig {
         &self.config
     }
+
+    /// Progress sink for long operations run on behalf of this engine
+    pub fn progress(&self) -> &dyn ProgressSink {
+        self.progress.as_ref()
+    }
+}
+
+/// Carry user state from the previous cache into freshly scanned projects
+///
+/// Projects are matched by path (a project's name changes when another one starts sharing
+/// it). A project at a new path whose `.hegel/` has the identity of a previous project that is
+/// gone from its old path was renamed or moved: it inherits that project's state and records
+/// `renamed_from`. `renamed_from` is only kept until the next rescan.
+fn carry_over(projects: &mut [DiscoveredProject], previous: &[DiscoveredProject]) {
+    let scanned_paths: HashSet<PathBuf> = projects.iter().map(|p| p.project_path.clone()).collect();
+
+    for project in projects.iter_mut() {
+        if let Some(old) = previous
+            .iter()
+            .find(|p| p.project_path == project.project_path)
+        {
+            project.carry_user_state(old);
+            project.renamed_from = None;
+        } else if let Some(old) = previous.iter().find(|p| {
+            p.fs_identity.is_some()
+                && p.fs_identity == project.fs_identity
+                && !scanned_paths.contains(&p.project_path)
+        }) {
+            debug!(
+                "🔀 {} was renamed from {}",
+                project.project_path.display(),
+                old.project_path.display()
+            );
+            project.carry_user_state(old);
+            project.renamed_from = Some(old.project_path.clone());
+        }
+    }
 }
 
 #[cfg(test)]
@@ -208,4 +268,100 @@ mod tests {
         assert!(cache_dir.join("index.bin").exists());
         assert!(temp.path().join("config").join("cache.json").exists());
     }
+
+    #[test]
+    fn test_user_state_survives_rescan_and_refresh() {
+        let temp = create_test_workspace();
+        let config = DiscoveryConfig::new(
+            vec![temp.path().to_path_buf()],
+            10,
+            vec![],
+            temp.path().join("config").join("cache.json"),
+        );
+        let engine = DiscoveryEngine::new(config.clone()).unwrap();
+        engine.get_projects(false).unwrap();
+
+        assert!(crate::discovery::set_archived("test-project", true, &config).unwrap());
+        assert!(!crate::discovery::set_archived("missing", true, &config).unwrap());
+        crate::discovery::update_cached_project("test-project", &config, |p| {
+            p.metadata.insert("owner".to_string(), "alice".to_string());
+        })
+        .unwrap();
+
+        let rescanned = engine.get_projects(true).unwrap();
+        assert!(rescanned[0].archived);
+        assert_eq!(rescanned[0].metadata["owner"], "alice");
+
+        crate::discovery::refresh_project("test-project", &config).unwrap();
+        let refreshed = engine.get_projects(false).unwrap();
+        assert!(refreshed[0].archived);
+        assert_eq!(refreshed[0].metadata["owner"], "alice");
+
+        crate::discovery::set_archived("test-project", false, &config).unwrap();
+        assert!(!engine.get_projects(false).unwrap()[0].archived);
+    }
+
+    #[test]
+    fn test_rescan_detects_renamed_project() {
+        let temp = create_test_workspace();
+        let config = DiscoveryConfig::new(
+            vec![temp.path().to_path_buf()],
+            10,
+            vec![],
+            temp.path().join("config").join("cache.json"),
+        );
+        let engine = DiscoveryEngine::new(config.clone()).unwrap();
+        engine.get_projects(false).unwrap();
+        crate::discovery::update_cached_project("test-project", &config, |p| {
+            p.metadata.insert("owner".to_string(), "alice".to_string());
+        })
+        .unwrap();
+
+        fs::rename(
+            temp.path().join("test-project"),
+            temp.path().join("renamed-project"),
+        )
+        .unwrap();
+        let rescanned = engine.get_projects(true).unwrap();
+        assert_eq!(rescanned[0].name, "renamed-project");
+        if cfg!(unix) {
+            assert_eq!(rescanned[0].metadata["owner"], "alice");
+            assert_eq!(
+                rescanned[0].renamed_from,
+                Some(temp.path().join("test-project"))
+            );
+        }
+
+        // Reported until the next rescan
+        let rescanned = engine.get_projects(true).unwrap();
+        assert!(rescanned[0].renamed_from.is_none());
+        if cfg!(unix) {
+            assert_eq!(rescanned[0].metadata["owner"], "alice");
+        }
+    }
+
+    #[test]
+    fn test_carry_over_ignores_identity_of_projects_still_present() {
+        let project = |path: &str| {
+            let mut project = DiscoveredProject::new(
+                path.trim_start_matches('/').to_string(),
+                PathBuf::from(path),
+                PathBuf::from(path).join(".hegel"),
+                None,
+                std::time::SystemTime::UNIX_EPOCH,
+                None,
+            );
+            project.fs_identity = Some("1:42".to_string());
+            project
+        };
+        let mut old = project("/code/a");
+        old.archived = true;
+
+        // Same identity at both paths (e.g. inode reused): not a rename
+        let mut projects = vec![project("/code/a"), project("/code/b")];
+        carry_over(&mut projects, &[old]);
+        assert!(projects[0].archived);
+        assert!(!projects[1].archived);
+        assert!(projects[1].renamed_from.is_none());
+    }
 }
@@ -0,0 +1,194 @@
+use git2::{BranchType, Repository, StatusOptions};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Git repository status for a project (branch, dirty flag, upstream divergence)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GitInfo {
+    /// Current branch name (None for unborn HEAD, "HEAD" when detached)
+    pub branch: Option<String>,
+    /// Working tree has uncommitted or untracked changes
+    pub dirty: bool,
+    /// Commits ahead of upstream (None if branch has no upstream)
+    pub ahead: Option<usize>,
+    /// Commits behind upstream (None if branch has no upstream)
+    pub behind: Option<usize>,
+    /// Commit ID of HEAD (None for unborn HEAD; missing in caches written before it was recorded)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub head: Option<String>,
+    /// Working tree root; shared by projects nested in one repository (see `group_by_repo`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root: Option<PathBuf>,
+}
+
+impl GitInfo {
+    /// Collect git info for the repository containing `project_path`
+    ///
+    /// Returns `None` if the path is not inside a git repository.
+    pub fn collect(project_path: &Path) -> Option<Self> {
+        let repo = Repository::discover(project_path).ok()?;
+
+        let branch = repo
+            .head()
+            .ok()
+            .and_then(|head| head.shorthand().map(String::from));
+
+        let mut opts = StatusOptions::new();
+        opts.include_untracked(true).include_ignored(false);
+        let dirty = repo
+            .statuses(Some(&mut opts))
+            .map(|statuses| !statuses.is_empty())
+            .unwrap_or(false);
+
+        let (ahead, behind) = match upstream_ahead_behind(&repo) {
+            Some((ahead, behind)) => (Some(ahead), Some(behind)),
+            None => (None, None),
+        };
+
+        let head = repo
+            .head()
+            .ok()
+            .and_then(|head| head.target())
+            .map(|oid| oid.to_string());
+
+        // Canonicalized so it compares equal across projects however their roots were spelled
+        let root = repo
+            .workdir()
+            .map(|dir| dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf()));
+
+        Some(Self {
+            branch,
+            dirty,
+            ahead,
+            behind,
+            head,
+            root,
+        })
+    }
+
+    /// Compact one-line summary, e.g. `main* ↑2 ↓1`
+    pub fn summary(&self) -> String {
+        let mut summary = self.branch.clone().unwrap_or_else(|| "-".to_string());
+        if self.dirty {
+            summary.push('*');
+        }
+        if let Some(ahead) = self.ahead.filter(|&n| n > 0) {
+            summary.push_str(&format!(" ↑{}", ahead));
+        }
+        if let Some(behind) = self.behind.filter(|&n| n > 0) {
+            summary.push_str(&format!(" ↓{}", behind));
+        }
+        summary
+    }
+}
+
+/// Count commits ahead/behind the upstream of the current local branch
+fn upstream_ahead_behind(repo: &Repository) -> Option<(usize, usize)> {
+    let head = repo.head().ok()?;
+    if !head.is_branch() {
+        return None;
+    }
+
+    let local = repo
+        .find_branch(head.shorthand()?, BranchType::Local)
+        .ok()?;
+    let upstream = local.upstream().ok()?;
+
+    let local_oid = head.target()?;
+    let upstream_oid = upstream.get().target()?;
+
+    repo.graph_ahead_behind(local_oid, upstream_oid).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn commit_all(repo: &Repository) {
+        let mut index = repo.index().unwrap();
+        index
+            .add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)
+            .unwrap();
+        index.write().unwrap();
+        let tree_id = index.write_tree().unwrap();
+        let tree = repo.find_tree(tree_id).unwrap();
+        let sig = git2::Signature::now("test", "test@example.com").unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "initial", &tree, &[])
+            .unwrap();
+    }
+
+    #[test]
+    fn test_collect_outside_repo() {
+        let temp = TempDir::new().unwrap();
+        // TempDir may itself live inside a repo on some machines; only assert when it doesn't
+        if Repository::discover(temp.path()).is_err() {
+            assert!(GitInfo::collect(temp.path()).is_none());
+        }
+    }
+
+    #[test]
+    fn test_collect_clean_repo() {
+        let temp = TempDir::new().unwrap();
+        let repo = Repository::init(temp.path()).unwrap();
+        fs::write(temp.path().join("README.md"), b"hello").unwrap();
+        commit_all(&repo);
+
+        let info = GitInfo::collect(temp.path()).unwrap();
+        assert!(info.branch.is_some());
+        assert!(!info.dirty);
+        assert!(info.ahead.is_none());
+        assert!(info.behind.is_none());
+        assert_eq!(
+            info.head,
+            Some(repo.head().unwrap().target().unwrap().to_string())
+        );
+    }
+
+    #[test]
+    fn test_collect_dirty_repo() {
+        let temp = TempDir::new().unwrap();
+        let repo = Repository::init(temp.path()).unwrap();
+        fs::write(temp.path().join("README.md"), b"hello").unwrap();
+        commit_all(&repo);
+        fs::write(temp.path().join("new.txt"), b"untracked").unwrap();
+
+        let info = GitInfo::collect(temp.path()).unwrap();
+        assert!(info.dirty);
+    }
+
+    #[test]
+    fn test_collect_from_nested_directory() {
+        let temp = TempDir::new().unwrap();
+        Repository::init(temp.path()).unwrap();
+        let nested = temp.path().join("sub").join("project");
+        fs::create_dir_all(&nested).unwrap();
+
+        let info = GitInfo::collect(&nested).unwrap();
+        assert_eq!(info.root, Some(temp.path().canonicalize().unwrap()));
+    }
+
+    #[test]
+    fn test_summary() {
+        let info = GitInfo {
+            branch: Some("main".to_string()),
+            dirty: true,
+            ahead: Some(2),
+            behind: Some(0),
+            head: None,
+            root: None,
+        };
+        assert_eq!(info.summary(), "main* ↑2");
+
+        let info = GitInfo {
+            branch: None,
+            dirty: false,
+            ahead: None,
+            behind: None,
+            head: None,
+            root: None,
+        };
+        assert_eq!(info.summary(), "-");
+    }
+}
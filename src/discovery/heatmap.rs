@@ -0,0 +1,122 @@
+use chrono::{DateTime, Datelike, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::ProjectStatistics;
+
+/// Hook event counts by day of week and hour of day (UTC)
+///
+/// `cells[day][hour]`, with day 0 = Monday. Consumers shift to local time if needed.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ActivityHeatmap {
+    pub cells: [[u32; 24]; 7],
+    /// Events counted (events without a parseable timestamp are excluded)
+    pub total: u32,
+}
+
+impl ActivityHeatmap {
+    /// Busiest (day, hour) cell, or None if there were no events
+    pub fn peak(&self) -> Option<(usize, usize)> {
+        let mut peak = None;
+        let mut max = 0;
+        for (day, hours) in self.cells.iter().enumerate() {
+            for (hour, &count) in hours.iter().enumerate() {
+                if count > max {
+                    max = count;
+                    peak = Some((day, hour));
+                }
+            }
+        }
+        peak
+    }
+}
+
+/// Bucket a project's hook events (bash commands, file modifications) by weekday and hour
+pub fn activity_heatmap(stats: &ProjectStatistics) -> ActivityHeatmap {
+    let timestamps = stats
+        .hook_metrics
+        .bash_commands
+        .iter()
+        .filter_map(|c| c.timestamp.as_deref())
+        .chain(
+            stats
+                .hook_metrics
+                .file_modifications
+                .iter()
+                .filter_map(|m| m.timestamp.as_deref()),
+        );
+
+    heatmap_from_timestamps(timestamps)
+}
+
+/// Count RFC 3339 timestamps into weekday/hour cells (unparseable timestamps skipped)
+fn heatmap_from_timestamps<'a>(timestamps: impl IntoIterator<Item = &'a str>) -> ActivityHeatmap {
+    let mut heatmap = ActivityHeatmap::default();
+    for timestamp in timestamps {
+        let Ok(time) = DateTime::parse_from_rfc3339(timestamp) else {
+            continue;
+        };
+        let time = time.with_timezone(&Utc);
+        let day = time.weekday().num_days_from_monday() as usize;
+        heatmap.cells[day][time.hour() as usize] += 1;
+        heatmap.total += 1;
+    }
+    heatmap
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::{bash_json, edit_json, stats_with};
+    use serde_json::json;
+
+    #[test]
+    fn test_heatmap_buckets_by_weekday_and_hour() {
+        // 2025-01-06 is a Monday
+        let heatmap = heatmap_from_timestamps([
+            "2025-01-06T09:15:00Z",
+            "2025-01-06T09:45:00Z",
+            "2025-01-08T23:59:59+00:00",
+            "2025-01-12T10:00:00+02:00", // Sunday 08:00 UTC
+            "not a timestamp",
+        ]);
+
+        assert_eq!(heatmap.total, 4);
+        assert_eq!(heatmap.cells[0][9], 2);
+        assert_eq!(heatmap.cells[2][23], 1);
+        assert_eq!(heatmap.cells[6][8], 1);
+        assert_eq!(heatmap.peak(), Some((0, 9)));
+    }
+
+    #[test]
+    fn test_activity_heatmap_from_hook_events() {
+        let stats = stats_with(json!({
+            "hook_metrics": {
+                "bash_commands": [
+                    bash_json("cargo test", Some("2025-01-06T09:15:00Z")),
+                    bash_json("cargo build", Some("2025-01-06T09:59:59.250Z")),
+                    bash_json("ls", None),
+                ],
+                "file_modifications": [
+                    // Tuesday 01:30 local, Monday 23:30 UTC
+                    edit_json("src/lib.rs", Some("2025-01-07T01:30:00+02:00")),
+                    edit_json("src/main.rs", Some("2025-01-06T09:00:00Z")),
+                ],
+            },
+        }));
+
+        let heatmap = activity_heatmap(&stats);
+
+        assert_eq!(heatmap.total, 4);
+        assert_eq!(heatmap.cells[0][9], 3);
+        assert_eq!(heatmap.cells[0][23], 1);
+        assert_eq!(heatmap.cells[1].iter().sum::<u32>(), 0);
+        assert_eq!(heatmap.peak(), Some((0, 9)));
+    }
+
+    #[test]
+    fn test_heatmap_empty() {
+        let heatmap = activity_heatmap(&ProjectStatistics::default());
+        assert_eq!(heatmap.total, 0);
+        assert_eq!(heatmap.peak(), None);
+    }
+}
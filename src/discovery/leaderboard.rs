@@ -0,0 +1,291 @@
+//! Cross-project rankings over a time window
+//!
+//! Like `analytics`, projects without loaded statistics are skipped.
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use super::{total_tokens, DiscoveredProject, ProjectStatistics};
+
+/// What `leaderboard` ranks projects by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LeaderboardMetric {
+    /// All tokens (input, output, cache) of phases started in the window
+    Tokens,
+    /// Bash commands plus file modifications in the window
+    Events,
+    /// Git commits in the window
+    Commits,
+}
+
+impl FromStr for LeaderboardMetric {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "tokens" => Ok(LeaderboardMetric::Tokens),
+            "events" => Ok(LeaderboardMetric::Events),
+            "commits" => Ok(LeaderboardMetric::Commits),
+            other => bail!(
+                "Invalid metric '{}' (expected tokens, events, or commits)",
+                other
+            ),
+        }
+    }
+}
+
+/// One project's total for the ranked metric
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LeaderboardEntry {
+    pub project_name: String,
+    pub value: u64,
+}
+
+/// Parse a window length like `24h`, `7d`, or `2w`
+pub fn parse_period(period: &str) -> Result<Duration> {
+    let period = period.trim();
+    // Split off the last character (not byte) so non-ASCII input errors instead of panicking
+    let unit_len = period.chars().last().map_or(0, char::len_utf8);
+    let (amount, unit) = period.split_at(period.len() - unit_len);
+    let amount: i64 = amount
+        .parse()
+        .with_context(|| format!("Invalid period '{}' (expected e.g. 24h, 7d, 2w)", period))?;
+
+    match unit {
+        "h" => Ok(Duration::hours(amount)),
+        "d" => Ok(Duration::days(amount)),
+        "w" => Ok(Duration::weeks(amount)),
+        _ => bail!("Invalid period '{}' (expected e.g. 24h, 7d, 2w)", period),
+    }
+}
+
+/// Rank projects by `metric` over activity at or after `since` (all time if None)
+///
+/// Highest first, ties by name; projects with nothing in the window are left out.
+pub fn leaderboard(
+    projects: &[DiscoveredProject],
+    metric: LeaderboardMetric,
+    since: Option<DateTime<Utc>>,
+) -> Vec<LeaderboardEntry> {
+    let mut entries: Vec<LeaderboardEntry> = projects
+        .iter()
+        .filter_map(|project| {
+            let stats = project.statistics.as_ref()?;
+            Some(LeaderboardEntry {
+                project_name: project.name.clone(),
+                value: metric_total(stats, metric, since),
+            })
+        })
+        .filter(|entry| entry.value > 0)
+        .collect();
+
+    entries.sort_by(|a, b| {
+        b.value
+            .cmp(&a.value)
+            .then_with(|| a.project_name.cmp(&b.project_name))
+    });
+    entries
+}
+
+/// One project's total for `metric` within the window
+fn metric_total(
+    stats: &ProjectStatistics,
+    metric: LeaderboardMetric,
+    since: Option<DateTime<Utc>>,
+) -> u64 {
+    let in_window = |timestamp: Option<&str>| within(timestamp, since);
+
+    match metric {
+        LeaderboardMetric::Tokens => stats
+            .phase_metrics
+            .iter()
+            .filter(|phase| in_window(Some(&phase.start_time)))
+            .map(|phase| total_tokens(&phase.token_metrics))
+            .sum(),
+        LeaderboardMetric::Events => {
+            let hooks = &stats.hook_metrics;
+            let commands = hooks
+                .bash_commands
+                .iter()
+                .filter(|c| in_window(c.timestamp.as_deref()))
+                .count();
+            let modifications = hooks
+                .file_modifications
+                .iter()
+                .filter(|m| in_window(m.timestamp.as_deref()))
+                .count();
+            (commands + modifications) as u64
+        }
+        LeaderboardMetric::Commits => stats
+            .git_commits
+            .iter()
+            .filter(|commit| in_window(Some(&commit.timestamp)))
+            .count() as u64,
+    }
+}
+
+/// Whether an RFC 3339 timestamp falls in the window (without a window, everything does)
+fn within(timestamp: Option<&str>, since: Option<DateTime<Utc>>) -> bool {
+    let Some(since) = since else {
+        return true;
+    };
+    timestamp
+        .and_then(|ts| DateTime::parse_from_rfc3339(ts).ok())
+        .is_some_and(|time| time.with_timezone(&Utc) >= since)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::{bash_json, edit_json, phase_json, stats_with};
+    use serde_json::json;
+    use std::path::PathBuf;
+    use std::time::SystemTime;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_period() {
+        assert_eq!(parse_period("24h").unwrap(), Duration::hours(24));
+        assert_eq!(parse_period("7d").unwrap(), Duration::days(7));
+        assert_eq!(parse_period("2w").unwrap(), Duration::days(14));
+        assert!(parse_period("7").is_err());
+        assert!(parse_period("d").is_err());
+        assert!(parse_period("7m").is_err());
+        assert!(parse_period("7é").is_err());
+        assert!(parse_period("").is_err());
+    }
+
+    #[test]
+    fn test_within_window() {
+        let since = DateTime::parse_from_rfc3339("2025-01-06T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        assert!(within(Some("2025-01-06T00:00:00Z"), Some(since)));
+        assert!(within(Some("2025-01-06T01:00:00+02:00"), None));
+        assert!(!within(Some("2025-01-06T01:00:00+02:00"), Some(since)));
+        assert!(!within(None, Some(since)));
+        assert!(within(None, None));
+    }
+
+    #[test]
+    fn test_leaderboard_skips_unloaded_and_empty() {
+        let temp = TempDir::new().unwrap();
+        let project = |name: &str, stats: Option<ProjectStatistics>| {
+            let mut project = DiscoveredProject::new(
+                name.to_string(),
+                temp.path().join(name),
+                temp.path().join(name).join(".hegel"),
+                None,
+                SystemTime::now(),
+                None,
+            );
+            project.statistics = stats;
+            project
+        };
+
+        let projects = vec![
+            project("unloaded", None),
+            project("idle", Some(ProjectStatistics::default())),
+        ];
+
+        assert!(leaderboard(&projects, LeaderboardMetric::Tokens, None).is_empty());
+        assert_eq!(
+            "commits".parse::<LeaderboardMetric>().unwrap(),
+            LeaderboardMetric::Commits
+        );
+        assert!("lines".parse::<LeaderboardMetric>().is_err());
+    }
+
+    #[test]
+    fn test_leaderboard_from_metrics() {
+        let commit = |timestamp: &str| {
+            json!({
+                "hash": "abc1234",
+                "timestamp": timestamp,
+                "message": "Commit",
+                "author": "dev",
+                "files_changed": 1,
+                "insertions": 1,
+                "deletions": 0,
+            })
+        };
+        let project = |name: &str, stats: ProjectStatistics| {
+            let mut project = DiscoveredProject::new(
+                name.to_string(),
+                PathBuf::from("/code").join(name),
+                PathBuf::from("/code").join(name).join(".hegel"),
+                None,
+                SystemTime::now(),
+                None,
+            );
+            project.statistics = Some(stats);
+            project
+        };
+
+        let projects = vec![
+            project(
+                "alpha",
+                stats_with(json!({
+                    "phase_metrics": [
+                        phase_json("spec", "2025-01-05T10:00:00Z", Some("2025-01-05T11:00:00Z"), 5_000),
+                        phase_json("code", "2025-01-06T09:00:00+02:00", None, 300),
+                    ],
+                    "hook_metrics": {
+                        "bash_commands": [bash_json("cargo test", Some("2025-01-06T08:00:00Z"))],
+                        "file_modifications": [edit_json("src/lib.rs", Some("2025-01-05T23:00:00Z"))],
+                    },
+                    "git_commits": [commit("2025-01-06T10:00:00Z"), commit("2025-01-04T10:00:00Z")],
+                })),
+            ),
+            project(
+                "beta",
+                stats_with(json!({
+                    "phase_metrics": [phase_json("spec", "2025-01-06T12:00:00Z", None, 800)],
+                    "hook_metrics": {
+                        "bash_commands": [
+                            bash_json("ls", Some("2025-01-06T12:00:00Z")),
+                            bash_json("pwd", None),
+                        ],
+                    },
+                    "git_commits": [commit("2025-01-06T12:30:00Z")],
+                })),
+            ),
+        ];
+        let entry = |name: &str, value: u64| LeaderboardEntry {
+            project_name: name.to_string(),
+            value,
+        };
+
+        // All time: every phase, event and commit counts
+        assert_eq!(
+            leaderboard(&projects, LeaderboardMetric::Tokens, None),
+            [entry("alpha", 5_300), entry("beta", 800)]
+        );
+        assert_eq!(
+            leaderboard(&projects, LeaderboardMetric::Events, None),
+            [entry("alpha", 2), entry("beta", 2)]
+        );
+
+        // From 2025-01-06 UTC: alpha's 09:00+02:00 phase (07:00 UTC) is in; its January 5 phase
+        // and edit, and beta's undated command, are out
+        let since = DateTime::parse_from_rfc3339("2025-01-06T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(
+            leaderboard(&projects, LeaderboardMetric::Tokens, Some(since)),
+            [entry("beta", 800), entry("alpha", 300)]
+        );
+        assert_eq!(
+            leaderboard(&projects, LeaderboardMetric::Events, Some(since)),
+            [entry("alpha", 1), entry("beta", 1)]
+        );
+        assert_eq!(
+            leaderboard(&projects, LeaderboardMetric::Commits, Some(since)),
+            [entry("alpha", 1), entry("beta", 1)]
+        );
+    }
+}
@@ -0,0 +1,184 @@
+//! Metric changes for a project since a recorded snapshot (`hegel-pm diff`)
+//!
+//! Statistics are never cached, so snapshots (`SnapshotStore`) are the only history to compare
+//! against. Deltas are signed: hegel logs can be truncated or reset between snapshots.
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::Serialize;
+
+use super::{parse_period, ProjectMetricsSummary, SnapshotStore};
+
+/// Change in one project's metrics between a snapshot and now
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct MetricsDelta {
+    pub project_name: String,
+    /// When the baseline snapshot was taken
+    pub baseline_taken_at: DateTime<Utc>,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cache_creation_tokens: i64,
+    pub cache_read_tokens: i64,
+    pub total_tokens: i64,
+    pub events: i64,
+    pub bash_commands: i64,
+    pub file_modifications: i64,
+    pub git_commits: i64,
+    pub phases: i64,
+}
+
+impl MetricsDelta {
+    /// `after - before` for every metric
+    pub fn between(
+        project_name: &str,
+        baseline_taken_at: DateTime<Utc>,
+        before: &ProjectMetricsSummary,
+        after: &ProjectMetricsSummary,
+    ) -> Self {
+        let delta = |before: u64, after: u64| after as i64 - before as i64;
+
+        Self {
+            project_name: project_name.to_string(),
+            baseline_taken_at,
+            input_tokens: delta(before.total_input_tokens, after.total_input_tokens),
+            output_tokens: delta(before.total_output_tokens, after.total_output_tokens),
+            cache_creation_tokens: delta(
+                before.total_cache_creation_tokens,
+                after.total_cache_creation_tokens,
+            ),
+            cache_read_tokens: delta(
+                before.total_cache_read_tokens,
+                after.total_cache_read_tokens,
+            ),
+            total_tokens: delta(before.total_all_tokens, after.total_all_tokens),
+            events: delta(before.total_events as u64, after.total_events as u64),
+            bash_commands: delta(
+                before.bash_command_count as u64,
+                after.bash_command_count as u64,
+            ),
+            file_modifications: delta(
+                before.file_modification_count as u64,
+                after.file_modification_count as u64,
+            ),
+            git_commits: delta(
+                before.git_commit_count as u64,
+                after.git_commit_count as u64,
+            ),
+            phases: delta(before.phase_count as u64, after.phase_count as u64),
+        }
+    }
+}
+
+/// Parse a `--since` value: RFC 3339 timestamp, snapshot ID (`20250101T120000Z`), or period
+/// back from `now` (`24h`, `7d`, `2w`)
+pub fn parse_since(value: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    // Snapshot file stem, with optional fractional seconds and same-instant suffix (`Z-1`)
+    let snapshot_id = value.split('-').next().unwrap_or(value);
+    if let Ok(time) = NaiveDateTime::parse_from_str(snapshot_id, "%Y%m%dT%H%M%S%.fZ") {
+        return Ok(time.and_utc());
+    }
+    match parse_period(value) {
+        Ok(period) => Ok(now - period),
+        Err(_) => bail!(
+            "Invalid --since '{}' (expected an RFC 3339 timestamp, snapshot ID like \
+             20250101T120000Z, or period like 24h)",
+            value
+        ),
+    }
+}
+
+/// Latest snapshot of `project_name` taken at or before `at` (latest overall if None)
+///
+/// Returns when it was taken and the project's metrics then.
+pub fn snapshot_baseline(
+    store: &SnapshotStore,
+    project_name: &str,
+    at: Option<DateTime<Utc>>,
+) -> Result<Option<(DateTime<Utc>, ProjectMetricsSummary)>> {
+    let history = store.history(None, at)?;
+
+    Ok(history.into_iter().rev().find_map(|snapshot| {
+        snapshot
+            .projects
+            .into_iter()
+            .find(|p| p.name == project_name)
+            .map(|p| (snapshot.taken_at, p.metrics))
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::{MetricsSnapshot, ProjectSnapshot};
+    use tempfile::TempDir;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn summary(tokens: u64, phases: usize) -> ProjectMetricsSummary {
+        ProjectMetricsSummary {
+            total_input_tokens: tokens,
+            total_all_tokens: tokens,
+            phase_count: phases,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_since() {
+        let now = at("2025-01-08T00:00:00Z");
+
+        assert_eq!(
+            parse_since("2025-01-06T12:00:00+02:00", now).unwrap(),
+            at("2025-01-06T10:00:00Z")
+        );
+        assert_eq!(
+            parse_since("20250106T100000Z", now).unwrap(),
+            at("2025-01-06T10:00:00Z")
+        );
+        assert_eq!(
+            parse_since("20250106T100000.250000Z-1", now).unwrap(),
+            at("2025-01-06T10:00:00.25Z")
+        );
+        assert_eq!(parse_since("2d", now).unwrap(), at("2025-01-06T00:00:00Z"));
+        assert!(parse_since("yesterday", now).is_err());
+    }
+
+    #[test]
+    fn test_snapshot_baseline_and_delta() {
+        let temp = TempDir::new().unwrap();
+        let store = SnapshotStore::new(temp.path().join("snapshots"));
+
+        for (ts, tokens) in [("2025-01-01T00:00:00Z", 100), ("2025-01-02T00:00:00Z", 250)] {
+            let mut snapshot = MetricsSnapshot::capture(&[], at(ts));
+            snapshot.projects.push(ProjectSnapshot {
+                name: "alpha".to_string(),
+                metrics: summary(tokens, 1),
+                workflow_node: None,
+            });
+            store.record(&snapshot).unwrap();
+        }
+
+        let (taken_at, latest) = snapshot_baseline(&store, "alpha", None).unwrap().unwrap();
+        assert_eq!(taken_at, at("2025-01-02T00:00:00Z"));
+        assert_eq!(latest.total_all_tokens, 250);
+
+        let (taken_at, earlier) =
+            snapshot_baseline(&store, "alpha", Some(at("2025-01-01T12:00:00Z")))
+                .unwrap()
+                .unwrap();
+        assert_eq!(taken_at, at("2025-01-01T00:00:00Z"));
+
+        assert!(snapshot_baseline(&store, "beta", None).unwrap().is_none());
+
+        let delta = MetricsDelta::between("alpha", taken_at, &earlier, &summary(40, 3));
+        assert_eq!(delta.total_tokens, -60);
+        assert_eq!(delta.input_tokens, -60);
+        assert_eq!(delta.phases, 2);
+        assert_eq!(delta.events, 0);
+    }
+}
@@ -0,0 +1,337 @@
+//! Metrics sources: where a project's token/event statistics are parsed from
+//!
+//! hegel-cli's `.hegel` logs are the default. Projects driven by another agent harness can use
+//! Claude Code's session logs or a JSONL usage file instead (`"metrics_source"` in config, or
+//! per project in `"project_metrics_sources"`). The chosen source is recorded on the cached
+//! project (`DiscoveredProject::metrics_source`), so statistics load the same way everywhere.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{DiscoveredProject, ProjectStatistics};
+use crate::debug;
+
+/// Parses a project's statistics from one kind of log
+pub trait MetricsProvider {
+    /// Parse the project's metrics
+    fn load(&self, project: &DiscoveredProject) -> Result<ProjectStatistics>;
+
+    /// Bytes of logs `load` would parse (checked against `statistics_budget_mb`)
+    fn data_size(&self, project: &DiscoveredProject) -> u64;
+}
+
+/// Configured metrics source (`{"kind": "claude-code"}`, `{"kind": "jsonl", "path": "..."}`)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum MetricsSource {
+    /// hegel-cli's `.hegel/` logs
+    #[default]
+    Hegel,
+    /// Claude Code session logs (`~/.claude/projects/<project path>/*.jsonl`)
+    ClaudeCode,
+    /// JSONL usage log: one event per line with optional `input_tokens`, `output_tokens`,
+    /// `cache_creation_tokens`, and `cache_read_tokens`
+    Jsonl {
+        /// Log file, relative to the project directory
+        path: PathBuf,
+    },
+}
+
+impl MetricsSource {
+    /// Short name shown in output
+    pub fn name(&self) -> &'static str {
+        match self {
+            MetricsSource::Hegel => "hegel",
+            MetricsSource::ClaudeCode => "claude-code",
+            MetricsSource::Jsonl { .. } => "jsonl",
+        }
+    }
+
+    pub fn is_hegel(&self) -> bool {
+        *self == MetricsSource::Hegel
+    }
+
+    /// Provider that reads this source
+    pub fn provider(&self) -> Box<dyn MetricsProvider> {
+        match self {
+            MetricsSource::Hegel => Box::new(HegelProvider),
+            MetricsSource::ClaudeCode => Box::new(ClaudeCodeProvider::new()),
+            MetricsSource::Jsonl { path } => Box::new(JsonlProvider { path: path.clone() }),
+        }
+    }
+}
+
+/// hegel-cli's unified metrics from `.hegel/` (hooks, states, transcripts)
+pub struct HegelProvider;
+
+impl MetricsProvider for HegelProvider {
+    fn load(&self, project: &DiscoveredProject) -> Result<ProjectStatistics> {
+        hegel::metrics::parse_unified_metrics(&project.hegel_dir, true, None)
+    }
+
+    fn data_size(&self, project: &DiscoveredProject) -> u64 {
+        project.hegel_data_size()
+    }
+}
+
+/// Claude Code session transcripts: every line is an event, assistant messages carry usage
+pub struct ClaudeCodeProvider {
+    /// Directory holding one subdirectory per project (`~/.claude/projects`)
+    projects_dir: PathBuf,
+}
+
+impl ClaudeCodeProvider {
+    /// Read from `$CLAUDE_CONFIG_DIR/projects`, or `~/.claude/projects`
+    pub fn new() -> Self {
+        let config_dir = std::env::var_os("CLAUDE_CONFIG_DIR")
+            .map(PathBuf::from)
+            .or_else(|| dirs::home_dir().map(|home| home.join(".claude")))
+            .unwrap_or_else(|| PathBuf::from(".claude"));
+        Self::with_projects_dir(config_dir.join("projects"))
+    }
+
+    pub fn with_projects_dir(projects_dir: PathBuf) -> Self {
+        Self { projects_dir }
+    }
+
+    /// Claude Code names a project's log directory after its path, with every character other
+    /// than ASCII letters, digits, and `-` replaced by `-`
+    fn session_dir(&self, project_path: &Path) -> PathBuf {
+        let encoded: String = project_path
+            .to_string_lossy()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+            .collect();
+        self.projects_dir.join(encoded)
+    }
+
+    /// Session log files, oldest first
+    fn session_files(&self, project: &DiscoveredProject) -> Vec<PathBuf> {
+        let Ok(entries) = fs::read_dir(self.session_dir(&project.project_path)) else {
+            return Vec::new();
+        };
+        let mut files: Vec<(std::time::SystemTime, PathBuf)> = entries
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                if path.extension().and_then(|e| e.to_str()) != Some("jsonl") {
+                    return None;
+                }
+                let modified = path.metadata().ok()?.modified().ok()?;
+                Some((modified, path))
+            })
+            .collect();
+        files.sort();
+        files.into_iter().map(|(_, path)| path).collect()
+    }
+}
+
+impl Default for ClaudeCodeProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetricsProvider for ClaudeCodeProvider {
+    fn load(&self, project: &DiscoveredProject) -> Result<ProjectStatistics> {
+        let mut stats = ProjectStatistics::default();
+        for file in self.session_files(project) {
+            let content = fs::read_to_string(&file)
+                .context(format!("Failed to read session log: {}", file.display()))?;
+            for line in content.lines() {
+                let Ok(event) = serde_json::from_str::<Value>(line) else {
+                    continue;
+                };
+                stats.hook_metrics.total_events += 1;
+                if let Some(usage) = event.pointer("/message/usage") {
+                    add_usage(
+                        &mut stats,
+                        usage,
+                        [
+                            "input_tokens",
+                            "output_tokens",
+                            "cache_creation_input_tokens",
+                            "cache_read_input_tokens",
+                        ],
+                    );
+                }
+            }
+            // Sessions are named by ID; the newest one is the current session
+            stats.session_id = file.file_stem().map(|s| s.to_string_lossy().into_owned());
+        }
+        Ok(stats)
+    }
+
+    fn data_size(&self, project: &DiscoveredProject) -> u64 {
+        self.session_files(project)
+            .iter()
+            .filter_map(|file| file.metadata().ok())
+            .map(|m| m.len())
+            .sum()
+    }
+}
+
+/// A harness-agnostic JSONL usage log inside the project
+pub struct JsonlProvider {
+    /// Log file, relative to the project directory
+    path: PathBuf,
+}
+
+impl JsonlProvider {
+    fn file(&self, project: &DiscoveredProject) -> PathBuf {
+        project.project_path.join(&self.path)
+    }
+}
+
+impl MetricsProvider for JsonlProvider {
+    fn load(&self, project: &DiscoveredProject) -> Result<ProjectStatistics> {
+        let file = self.file(project);
+        let content = fs::read_to_string(&file)
+            .context(format!("Failed to read metrics log: {}", file.display()))?;
+
+        let mut stats = ProjectStatistics::default();
+        for (number, line) in content.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let event: Value = match serde_json::from_str(line) {
+                Ok(event) => event,
+                Err(e) => {
+                    debug!(
+                        "{}:{}: skipping invalid line: {}",
+                        file.display(),
+                        number + 1,
+                        e
+                    );
+                    continue;
+                }
+            };
+            stats.hook_metrics.total_events += 1;
+            add_usage(
+                &mut stats,
+                &event,
+                [
+                    "input_tokens",
+                    "output_tokens",
+                    "cache_creation_tokens",
+                    "cache_read_tokens",
+                ],
+            );
+        }
+        Ok(stats)
+    }
+
+    fn data_size(&self, project: &DiscoveredProject) -> u64 {
+        fs::metadata(self.file(project)).map_or(0, |m| m.len())
+    }
+}
+
+/// Add the input, output, cache creation, and cache read counts named by `keys` (missing = 0)
+fn add_usage(stats: &mut ProjectStatistics, usage: &Value, keys: [&str; 4]) {
+    let [input, output, cache_creation, cache_read] =
+        keys.map(|key| usage.get(key).and_then(Value::as_u64).unwrap_or(0));
+    let tokens = &mut stats.token_metrics;
+    tokens.total_input_tokens += input;
+    tokens.total_output_tokens += output;
+    tokens.total_cache_creation_tokens += cache_creation;
+    tokens.total_cache_read_tokens += cache_read;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::SystemTime;
+    use tempfile::TempDir;
+
+    fn project_at(path: &Path) -> DiscoveredProject {
+        DiscoveredProject::new(
+            "alpha".to_string(),
+            path.to_path_buf(),
+            path.join(".hegel"),
+            None,
+            SystemTime::UNIX_EPOCH,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_source_config_format() {
+        let sources: Vec<MetricsSource> = serde_json::from_str(
+            r#"[{"kind": "hegel"}, {"kind": "claude-code"}, {"kind": "jsonl", "path": "usage.jsonl"}]"#,
+        )
+        .unwrap();
+        assert_eq!(
+            sources,
+            [
+                MetricsSource::Hegel,
+                MetricsSource::ClaudeCode,
+                MetricsSource::Jsonl {
+                    path: PathBuf::from("usage.jsonl")
+                },
+            ]
+        );
+        assert_eq!(sources[2].name(), "jsonl");
+    }
+
+    #[test]
+    fn test_claude_code_sessions() {
+        let temp = TempDir::new().unwrap();
+        let provider = ClaudeCodeProvider::with_projects_dir(temp.path().join("projects"));
+        let project = project_at(Path::new("/home/dev/Code/my.app"));
+
+        let session_dir = temp.path().join("projects/-home-dev-Code-my-app");
+        assert_eq!(provider.session_dir(&project.project_path), session_dir);
+        fs::create_dir_all(&session_dir).unwrap();
+        fs::write(
+            session_dir.join("3f2a.jsonl"),
+            concat!(
+                r#"{"type":"user","message":{"role":"user","content":"hi"}}"#,
+                "\n",
+                r#"{"type":"assistant","message":{"usage":{"input_tokens":10,"output_tokens":200,"cache_creation_input_tokens":30,"cache_read_input_tokens":4000}}}"#,
+                "\n",
+                "not json\n",
+            ),
+        )
+        .unwrap();
+
+        let stats = provider.load(&project).unwrap();
+        assert_eq!(stats.hook_metrics.total_events, 2);
+        assert_eq!(stats.token_metrics.total_input_tokens, 10);
+        assert_eq!(stats.token_metrics.total_output_tokens, 200);
+        assert_eq!(stats.token_metrics.total_cache_creation_tokens, 30);
+        assert_eq!(stats.token_metrics.total_cache_read_tokens, 4000);
+        assert_eq!(stats.session_id.as_deref(), Some("3f2a"));
+        assert!(provider.data_size(&project) > 0);
+
+        // No session logs: empty statistics rather than an error
+        let other = project_at(Path::new("/home/dev/Code/other"));
+        assert_eq!(provider.load(&other).unwrap().hook_metrics.total_events, 0);
+    }
+
+    #[test]
+    fn test_jsonl_usage_log() {
+        let temp = TempDir::new().unwrap();
+        fs::write(
+            temp.path().join("usage.jsonl"),
+            "{\"input_tokens\": 5, \"output_tokens\": 7}\n\n{\"cache_read_tokens\": 100}\n{oops\n",
+        )
+        .unwrap();
+        let project = project_at(temp.path());
+
+        let source = MetricsSource::Jsonl {
+            path: PathBuf::from("usage.jsonl"),
+        };
+        let stats = source.provider().load(&project).unwrap();
+        assert_eq!(stats.hook_metrics.total_events, 2);
+        assert_eq!(stats.token_metrics.total_input_tokens, 5);
+        assert_eq!(stats.token_metrics.total_output_tokens, 7);
+        assert_eq!(stats.token_metrics.total_cache_read_tokens, 100);
+
+        let missing = MetricsSource::Jsonl {
+            path: PathBuf::from("missing.jsonl"),
+        };
+        assert!(missing.provider().load(&project).is_err());
+    }
+}
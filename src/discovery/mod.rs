@@ -1,25 +1,98 @@
+mod alerts;
+mod analytics;
 mod api_types;
+mod budgets;
 mod cache;
+mod cache_crypto;
+mod cache_lock;
+mod cache_stats;
+mod commands;
 mod config;
+mod digest;
 mod discover;
+mod doctor;
 mod engine;
+mod git;
+mod heatmap;
+mod leaderboard;
+mod metrics_diff;
+mod metrics_provider;
+mod monorepo;
+mod names;
+mod plugins;
+mod progress;
 mod project;
+mod refresh_diff;
+mod refresh_lock;
+mod remote;
+mod search;
+mod snapshot;
+#[cfg(feature = "sqlite")]
+mod sqlite_store;
 mod state;
 mod statistics;
+mod store;
+mod tail;
+mod timeseries;
+mod top;
 mod walker;
+mod webhooks;
 
-pub use api_types::{ProjectListItem, ProjectMetricsSummary};
+pub use alerts::{budget_alerts, find_alerts, Alert, AlertKind};
+pub use analytics::{
+    mode_breakdown, month_breakdown, phase_durations, phase_name_stats, PhaseDurationReport,
+    PhaseDurationStats, PhaseNameStats, ProjectPhaseDurations,
+};
+pub use api_types::{ModeMetrics, MonthTokens, Page, ProjectListItem, ProjectMetricsSummary};
+pub use budgets::{budget_statuses, current_month, Budget, BudgetStatus, BUDGET_THRESHOLDS};
 pub use cache::{
-    load_binary_cache, load_cache, refresh_all_projects, refresh_project, remove_from_cache,
-    save_binary_cache, save_cache,
+    load_binary_cache, load_cache, load_cache_with_cipher, refresh_all_projects,
+    refresh_all_projects_with_progress, refresh_project, remove_from_cache, save_binary_cache,
+    save_cache, save_cache_with_cipher, set_archived, update_cached_project, FileCacheStore,
+    ProjectIndexEntry,
 };
+pub use cache_crypto::{is_encrypted, CacheCipher};
+pub use cache_stats::{cache_stats, CacheStats, CachedProjectStats};
+pub use commands::{command_log, CommandEvent, CommandQuery};
 pub use config::DiscoveryConfig;
-pub use discover::discover_projects;
+pub use digest::{digest, Digest, DigestEntry, TokenPricing};
+pub use discover::{discover_projects, discover_projects_with_progress};
+pub use doctor::{
+    diagnose, diagnose_config_file, DoctorReport, Finding, ProjectLoadTime, Severity,
+};
 pub use engine::DiscoveryEngine;
-pub use project::DiscoveredProject;
+pub use git::GitInfo;
+pub use heatmap::{activity_heatmap, ActivityHeatmap};
+pub use leaderboard::{leaderboard, parse_period, LeaderboardEntry, LeaderboardMetric};
+pub use metrics_diff::{parse_since, snapshot_baseline, MetricsDelta};
+pub use metrics_provider::{
+    ClaudeCodeProvider, HegelProvider, JsonlProvider, MetricsProvider, MetricsSource,
+};
+pub use monorepo::{group_by_repo, ProjectGroup};
+pub use names::{disambiguate_names, project_id, NameCollisions};
+pub use plugins::{run_plugins, PluginConfig};
+#[cfg(test)]
+pub(crate) use progress::tests::RecordingProgress;
+pub use progress::{JsonProgress, NoProgress, ProgressEvent, ProgressSink};
+pub use project::{DiscoveredProject, ProjectStatus};
+pub use refresh_diff::{preview_refresh, refresh_project_diff, RefreshDiff, RefreshSnapshot};
+pub use refresh_lock::{refreshed_within, RefreshLock};
+pub use remote::{RemoteRoot, SshTransport, Transport};
+pub use search::{match_kind, search_projects, MatchKind};
+pub use snapshot::{MetricsSnapshot, ProjectSnapshot, SnapshotStore};
+#[cfg(feature = "sqlite")]
+pub use sqlite_store::SqliteCacheStore;
 pub use state::load_state;
-pub use statistics::ProjectStatistics;
-pub use walker::find_hegel_directories;
+pub use statistics::{total_tokens, ProjectStatistics, TokenMetrics};
+pub use store::{open_store, CacheBackend, CacheStore};
+pub use tail::{matches_filters, EventFilter, HookEvent, LogFollower, TAIL_FILES};
+pub use timeseries::{token_timeseries, Granularity, TimeseriesPoint};
+pub use top::{phase_files, top_activity, FileActivity, TopEntry, TopKind};
+pub use walker::{find_hegel_directories, find_hegel_directories_fast, DirSnapshot, Exclusions};
+pub use webhooks::{
+    budget_notifications, post_webhook, webhook_events, webhook_notifications, WebhookConfig,
+    WebhookEvent, WebhookPayload,
+};
 
 // Re-export hegel-cli types we depend on
 pub use hegel::storage::State;
@@ -0,0 +1,87 @@
+//! Grouping of projects nested in one git repository (monorepos)
+//!
+//! Each project records its repository's working tree root (`GitInfo::root`); projects sharing
+//! a root are grouped under it. A repository holding a single project isn't a group.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use super::DiscoveredProject;
+
+/// Projects listed together: a monorepo's projects, or one standalone project
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectGroup<'a> {
+    /// Shared repository root (None for a standalone project)
+    pub repo_root: Option<PathBuf>,
+    pub projects: Vec<&'a DiscoveredProject>,
+}
+
+/// Group projects sharing a repository root, keeping the input order
+///
+/// Groups appear where their first project does; projects within a group keep their order.
+pub fn group_by_repo(projects: &[DiscoveredProject]) -> Vec<ProjectGroup<'_>> {
+    let mut per_root: HashMap<&PathBuf, usize> = HashMap::new();
+    for root in projects.iter().filter_map(repo_root) {
+        *per_root.entry(root).or_default() += 1;
+    }
+
+    let mut groups: Vec<ProjectGroup> = Vec::new();
+    let mut group_of_root: HashMap<&PathBuf, usize> = HashMap::new();
+    for project in projects {
+        let shared_root = repo_root(project).filter(|root| per_root[root] > 1);
+
+        match shared_root {
+            Some(root) => match group_of_root.get(root) {
+                Some(&index) => groups[index].projects.push(project),
+                None => {
+                    group_of_root.insert(root, groups.len());
+                    groups.push(ProjectGroup {
+                        repo_root: Some(root.clone()),
+                        projects: vec![project],
+                    });
+                }
+            },
+            None => groups.push(ProjectGroup {
+                repo_root: None,
+                projects: vec![project],
+            }),
+        }
+    }
+
+    groups
+}
+
+fn repo_root(project: &DiscoveredProject) -> Option<&PathBuf> {
+    project.git_info.as_ref()?.root.as_ref()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::fixture_projects;
+
+    #[test]
+    fn test_group_by_repo() {
+        let mut projects = fixture_projects();
+        // Standalone without grouping
+        assert!(group_by_repo(&projects)
+            .iter()
+            .all(|g| g.repo_root.is_none() && g.projects.len() == 1));
+
+        // alpha and gamma share a repository; beta stays standalone between them
+        let root = PathBuf::from("/home/dev/Code");
+        projects[0].git_info.as_mut().unwrap().root = Some(root.clone());
+        let mut gamma_git = projects[0].git_info.clone().unwrap();
+        gamma_git.root = Some(root.clone());
+        projects[2].git_info = Some(gamma_git);
+
+        let groups = group_by_repo(&projects);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].repo_root, Some(root));
+        let names: Vec<&str> = groups[0].projects.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["alpha", "gamma"]);
+        assert_eq!(groups[1].repo_root, None);
+        assert_eq!(groups[1].projects[0].name, "beta-service");
+    }
+}
@@ -0,0 +1,161 @@
+//! Stable project IDs and display-name disambiguation
+//!
+//! Projects are named after their directory, so two checkouts called `api` would collide in
+//! the cache and in name lookups. Each project gets a stable ID (hash of its path) used for
+//! cache filenames and API routes, and colliding names are rewritten at discovery time.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use super::DiscoveredProject;
+
+/// How colliding project names are disambiguated
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NameCollisions {
+    /// `api (client-a)`: append the parent directory name (falls back to the ID if that collides too)
+    #[default]
+    Parent,
+    /// `api (3f2a9c1e)`: append the first 8 characters of the project ID
+    Id,
+}
+
+/// Stable project ID: 16 hex characters of an FNV-1a hash of the project path
+///
+/// Implemented here rather than with `DefaultHasher`, whose output may change between Rust
+/// releases (IDs end up in cache filenames and URLs).
+pub fn project_id(project_path: &Path) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in project_path.to_string_lossy().as_bytes() {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    format!("{:016x}", hash)
+}
+
+/// Rename projects whose names collide so every name is unique
+///
+/// Projects with unique names are untouched. Output only depends on names and paths, so
+/// a rescan produces the same names.
+pub fn disambiguate_names(projects: &mut [DiscoveredProject], style: NameCollisions) {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for project in projects.iter() {
+        *counts.entry(project.name.clone()).or_default() += 1;
+    }
+
+    let colliding: Vec<usize> = (0..projects.len())
+        .filter(|&i| counts[&projects[i].name] > 1)
+        .collect();
+    if colliding.is_empty() {
+        return;
+    }
+
+    let short_id = |project: &DiscoveredProject| project.id()[..8].to_string();
+    let mut candidates: Vec<(usize, String)> = colliding
+        .iter()
+        .map(|&i| {
+            let project = &projects[i];
+            let suffix = match style {
+                NameCollisions::Parent => project
+                    .project_path
+                    .parent()
+                    .and_then(|parent| parent.file_name())
+                    .map(|parent| parent.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| short_id(project)),
+                NameCollisions::Id => short_id(project),
+            };
+            (i, format!("{} ({})", project.name, suffix))
+        })
+        .collect();
+
+    // Parent names can collide too (client-a/api twice under different roots), and a
+    // candidate can clash with an existing project's name: fall back to the ID there
+    let unique: HashSet<&str> = projects
+        .iter()
+        .filter(|p| counts[&p.name] == 1)
+        .map(|p| p.name.as_str())
+        .collect();
+    let mut candidate_counts: HashMap<String, usize> = HashMap::new();
+    for (_, name) in &candidates {
+        *candidate_counts.entry(name.clone()).or_default() += 1;
+    }
+    for (i, name) in &mut candidates {
+        if candidate_counts[name.as_str()] > 1 || unique.contains(name.as_str()) {
+            *name = format!("{} ({})", projects[*i].name, short_id(&projects[*i]));
+        }
+    }
+
+    for (i, name) in candidates {
+        projects[i].name = name;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use std::time::SystemTime;
+
+    fn project(path: &str) -> DiscoveredProject {
+        let path = PathBuf::from(path);
+        DiscoveredProject::new(
+            path.file_name().unwrap().to_string_lossy().into_owned(),
+            path.clone(),
+            path.join(".hegel"),
+            None,
+            SystemTime::UNIX_EPOCH,
+            None,
+        )
+    }
+
+    fn names(projects: &[DiscoveredProject]) -> Vec<&str> {
+        projects.iter().map(|p| p.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_project_id_is_stable_and_path_specific() {
+        let id = project_id(Path::new("/home/dev/Code/client-a/api"));
+        assert_eq!(id.len(), 16);
+        assert_eq!(id, project_id(Path::new("/home/dev/Code/client-a/api")));
+        assert_ne!(id, project_id(Path::new("/home/dev/Code/client-b/api")));
+    }
+
+    #[test]
+    fn test_disambiguate_by_parent() {
+        let mut projects = vec![
+            project("/code/client-a/api"),
+            project("/code/client-b/api"),
+            project("/code/web"),
+        ];
+        disambiguate_names(&mut projects, NameCollisions::Parent);
+
+        assert_eq!(
+            names(&projects),
+            vec!["api (client-a)", "api (client-b)", "web"]
+        );
+    }
+
+    #[test]
+    fn test_disambiguate_falls_back_to_id() {
+        let mut projects = vec![
+            project("/work/client-a/api"),
+            project("/personal/client-a/api"),
+        ];
+        disambiguate_names(&mut projects, NameCollisions::Parent);
+
+        let first_id = &projects[0].id()[..8];
+        assert_eq!(projects[0].name, format!("api ({})", first_id));
+        assert_ne!(projects[0].name, projects[1].name);
+    }
+
+    #[test]
+    fn test_disambiguate_by_id() {
+        let mut projects = vec![project("/code/a/api"), project("/code/b/api")];
+        disambiguate_names(&mut projects, NameCollisions::Id);
+
+        assert!(projects[0].name.starts_with("api ("));
+        assert_eq!(projects[0].name.len(), "api (12345678)".len());
+        assert_ne!(projects[0].name, projects[1].name);
+    }
+}
@@ -0,0 +1,196 @@
+//! Discovery plugins: external commands that attach extra data to each scanned project
+//!
+//! A plugin is a command from the config (`"plugins": [{"name": "ci", "command": [...]}]`) run
+//! once per local project during `discover` and `refresh`, in the project directory. Its stdout
+//! must be one JSON value, stored under the plugin's name in `DiscoveredProject.extra` (e.g.
+//! Cargo workspace member counts, CI status). A failing plugin is logged and left out; it never
+//! fails the scan.
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::{DiscoveredProject, RemoteRoot};
+use crate::warn;
+
+/// How long a plugin may run per project unless `timeout_secs` says otherwise
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How often a running plugin is checked for exit
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// A user-configured plugin (`{"name": "ci", "command": ["ci-status", "--json"]}`)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PluginConfig {
+    /// Key the output is stored under in `DiscoveredProject.extra`
+    pub name: String,
+    /// Program and arguments (not run through a shell)
+    pub command: Vec<String>,
+    /// Kill the plugin after this many seconds (None = 10)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+}
+
+impl PluginConfig {
+    fn timeout(&self) -> Duration {
+        self.timeout_secs
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_TIMEOUT)
+    }
+}
+
+/// Run every plugin for `project`, replacing `project.extra` with their output
+///
+/// Remote projects are skipped (their working copy only exists on the host).
+pub fn run_plugins(plugins: &[PluginConfig], project: &mut DiscoveredProject) {
+    project.extra.clear();
+    if plugins.is_empty() || RemoteRoot::parse(&project.project_path).is_some() {
+        return;
+    }
+
+    for plugin in plugins {
+        match run_plugin(plugin, project) {
+            Ok(value) => {
+                project.extra.insert(plugin.name.clone(), value);
+            }
+            Err(e) => warn!(
+                "plugin '{}' failed for {}: {:#}",
+                plugin.name, project.name, e
+            ),
+        }
+    }
+}
+
+/// Run one plugin in the project directory and parse its stdout as JSON
+fn run_plugin(plugin: &PluginConfig, project: &DiscoveredProject) -> Result<serde_json::Value> {
+    let Some((program, args)) = plugin.command.split_first() else {
+        bail!("empty command");
+    };
+
+    let mut child = Command::new(program)
+        .args(args)
+        .current_dir(&project.project_path)
+        .env("HEGEL_PM_PROJECT_NAME", &project.name)
+        .env("HEGEL_PM_PROJECT_PATH", &project.project_path)
+        .env("HEGEL_PM_HEGEL_DIR", &project.hegel_dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .context(format!("failed to start '{}'", program))?;
+
+    // Drain stdout on a thread so a chatty plugin can't block on a full pipe
+    let mut stdout = child.stdout.take().expect("stdout is piped");
+    let reader = thread::spawn(move || {
+        let mut output = Vec::new();
+        stdout.read_to_end(&mut output).map(|_| output)
+    });
+
+    let timeout = plugin.timeout();
+    let started = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if started.elapsed() >= timeout {
+            child.kill().ok();
+            child.wait().ok();
+            bail!("timed out after {}s", timeout.as_secs());
+        }
+        thread::sleep(POLL_INTERVAL);
+    };
+
+    let output = reader
+        .join()
+        .map_err(|_| anyhow::anyhow!("failed to read output"))?
+        .context("failed to read output")?;
+    if !status.success() {
+        bail!("exited with {}", status);
+    }
+
+    serde_json::from_slice(&output).context("output is not valid JSON")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::fs;
+    use std::path::PathBuf;
+    use std::time::SystemTime;
+    use tempfile::TempDir;
+
+    fn project_in(dir: &std::path::Path) -> DiscoveredProject {
+        DiscoveredProject::new(
+            "alpha".to_string(),
+            dir.to_path_buf(),
+            dir.join(".hegel"),
+            None,
+            SystemTime::UNIX_EPOCH,
+            None,
+        )
+    }
+
+    fn sh(name: &str, script: &str) -> PluginConfig {
+        PluginConfig {
+            name: name.to_string(),
+            command: vec!["sh".to_string(), "-c".to_string(), script.to_string()],
+            timeout_secs: None,
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_plugin_output_attached() {
+        let temp = TempDir::new().unwrap();
+        fs::write(temp.path().join("members.txt"), "3").unwrap();
+        let mut project = project_in(temp.path());
+
+        let plugins = vec![
+            sh("cargo", r#"echo "{\"members\": $(cat members.txt)}""#),
+            sh("env", r#"echo "\"$HEGEL_PM_PROJECT_NAME\"""#),
+        ];
+        run_plugins(&plugins, &mut project);
+
+        assert_eq!(project.extra["cargo"], json!({"members": 3}));
+        assert_eq!(project.extra["env"], json!("alpha"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_failing_plugins_skipped() {
+        let temp = TempDir::new().unwrap();
+        let mut project = project_in(temp.path());
+        project
+            .extra
+            .insert("stale".to_string(), json!("from last scan"));
+
+        let mut slow = sh("slow", "sleep 5; echo 1");
+        slow.timeout_secs = Some(0);
+        let plugins = vec![
+            sh("exit", "echo 1; exit 3"),
+            sh("garbage", "echo not json"),
+            slow,
+            PluginConfig {
+                name: "missing".to_string(),
+                command: vec!["hegel-pm-no-such-plugin".to_string()],
+                timeout_secs: None,
+            },
+            sh("ok", "echo true"),
+        ];
+        run_plugins(&plugins, &mut project);
+
+        assert_eq!(project.extra.len(), 1);
+        assert_eq!(project.extra["ok"], json!(true));
+    }
+
+    #[test]
+    fn test_remote_projects_skipped() {
+        let mut project = project_in(&PathBuf::from("ssh://devbox/home/dev/Code/alpha"));
+        run_plugins(&[sh("ok", "echo true")], &mut project);
+        assert!(project.extra.is_empty());
+    }
+}
@@ -0,0 +1,107 @@
+use serde::Serialize;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Progress event emitted during long-running operations (scan, statistics, refresh)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ProgressEvent {
+    /// Filesystem scan started
+    ScanStarted { roots: usize },
+    /// A `.hegel` directory was found and its state loaded
+    ProjectScanned { name: String, path: PathBuf },
+    /// Filesystem scan finished
+    ScanFinished { projects: usize },
+    /// Statistics parsed for one project (`current` of `total`, 1-based)
+    StatisticsLoaded {
+        name: String,
+        current: usize,
+        total: usize,
+        ok: bool,
+    },
+    /// Statistics deliberately not loaded: the project's logs exceed `statistics_budget_mb`
+    /// (not a failure, unlike `StatisticsLoaded` with `ok: false`)
+    StatisticsSkipped {
+        name: String,
+        current: usize,
+        total: usize,
+    },
+    /// One cached project re-discovered (`current` of `total`, 1-based)
+    ProjectRefreshed {
+        name: String,
+        current: usize,
+        total: usize,
+        ok: bool,
+    },
+    /// Cache store written
+    CacheWritten { projects: usize },
+}
+
+/// Receiver for progress events
+pub trait ProgressSink: Send + Sync {
+    fn emit(&self, event: ProgressEvent);
+}
+
+/// Discards all events (default)
+pub struct NoProgress;
+
+impl ProgressSink for NoProgress {
+    fn emit(&self, _event: ProgressEvent) {}
+}
+
+/// Writes one JSON object per line to stderr (`--progress json`)
+pub struct JsonProgress;
+
+impl ProgressSink for JsonProgress {
+    fn emit(&self, event: ProgressEvent) {
+        if let Ok(line) = serde_json::to_string(&event) {
+            let mut stderr = std::io::stderr().lock();
+            let _ = writeln!(stderr, "{}", line);
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Collects events for assertions
+    #[derive(Default)]
+    pub(crate) struct RecordingProgress {
+        pub(crate) events: Mutex<Vec<ProgressEvent>>,
+    }
+
+    impl ProgressSink for RecordingProgress {
+        fn emit(&self, event: ProgressEvent) {
+            self.events.lock().unwrap().push(event);
+        }
+    }
+
+    #[test]
+    fn test_event_json_is_tagged() {
+        let event = ProgressEvent::StatisticsLoaded {
+            name: "alpha".to_string(),
+            current: 1,
+            total: 3,
+            ok: true,
+        };
+        assert_eq!(
+            serde_json::to_string(&event).unwrap(),
+            r#"{"event":"statistics_loaded","name":"alpha","current":1,"total":3,"ok":true}"#
+        );
+        let skipped = ProgressEvent::StatisticsSkipped {
+            name: "alpha".to_string(),
+            current: 2,
+            total: 3,
+        };
+        assert_eq!(
+            serde_json::to_string(&skipped).unwrap(),
+            r#"{"event":"statistics_skipped","name":"alpha","current":2,"total":3}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&ProgressEvent::CacheWritten { projects: 2 }).unwrap(),
+            r#"{"event":"cache_written","projects":2}"#
+        );
+    }
+}
@@ -1,14 +1,54 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use std::time::SystemTime;
+use walkdir::WalkDir;
+
+use super::{GitInfo, MetricsSource, ProjectMetricsSummary, ProjectStatistics, WorkflowState};
+
+/// Terminal workflow node: a workflow sitting here has finished
+const DONE_NODE: &str = "done";
+
+/// Levels below `.hegel/` counted toward its size (archives nest a few levels; this bounds the walk)
+const SIZE_MAX_DEPTH: usize = 8;
+
+/// Coarse workflow status, derived from the workflow state alone (no metrics)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProjectStatus {
+    /// Workflow in progress
+    Active,
+    /// Workflow reached its `done` node
+    Completed,
+    /// State file unreadable or corrupted
+    Error,
+    /// No workflow state
+    Inactive,
+}
 
-use super::{ProjectStatistics, WorkflowState};
+impl FromStr for ProjectStatus {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "active" => Ok(ProjectStatus::Active),
+            "completed" => Ok(ProjectStatus::Completed),
+            "error" => Ok(ProjectStatus::Error),
+            "inactive" => Ok(ProjectStatus::Inactive),
+            other => bail!(
+                "Invalid status '{}' (expected active, completed, error, or inactive)",
+                other
+            ),
+        }
+    }
+}
 
 /// A discovered Hegel project
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DiscoveredProject {
-    /// Name of the project (directory name)
+    /// Name of the project (directory name, disambiguated if another project shares it)
     pub name: String,
     /// Path to project root
     pub project_path: PathBuf,
@@ -22,6 +62,34 @@ pub struct DiscoveredProject {
     pub discovered_at: SystemTime,
     /// Error message if state is corrupted
     pub error: Option<String>,
+    /// Git branch and working tree status (None if not in a git repository)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_info: Option<GitInfo>,
+    /// Hidden from `discover list/all` and `x` unless archived projects are requested
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub archived: bool,
+    /// Freeform user metadata (owner, ticket link, status, notes...) set with `hegel-pm note`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, String>,
+    /// Total size of `.hegel/` including archives, measured at discovery (see `calculate_hegel_size`)
+    #[serde(default)]
+    pub hegel_size_bytes: u64,
+    /// Device and inode of `.hegel/` (`dev:ino`), unchanged when the project directory is
+    /// renamed or moved within a filesystem (None off Unix and for remote projects)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fs_identity: Option<String>,
+    /// Path the project had before a rescan found it renamed or moved (until the next rescan)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub renamed_from: Option<PathBuf>,
+    /// Metrics totals from the last refresh; unlike `statistics`, kept in the cache
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metrics_summary: Option<ProjectMetricsSummary>,
+    /// Output of discovery plugins, keyed by plugin name (see `run_plugins`)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub extra: BTreeMap<String, serde_json::Value>,
+    /// Where statistics are parsed from (set from config at discovery; see `MetricsSource`)
+    #[serde(default, skip_serializing_if = "MetricsSource::is_hegel")]
+    pub metrics_source: MetricsSource,
     /// Statistics (loaded lazily)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub statistics: Option<ProjectStatistics>,
@@ -45,20 +113,88 @@ impl DiscoveredProject {
             last_activity,
             discovered_at: SystemTime::now(),
             error,
+            git_info: None,
+            archived: false,
+            metadata: BTreeMap::new(),
+            hegel_size_bytes: 0,
+            fs_identity: None,
+            renamed_from: None,
+            metrics_summary: None,
+            extra: BTreeMap::new(),
+            metrics_source: MetricsSource::Hegel,
             statistics: None,
         }
     }
 
-    /// Load statistics for this project (lazy loading)
+    /// Stable ID derived from the project path (see `project_id`)
+    pub fn id(&self) -> String {
+        super::project_id(&self.project_path)
+    }
+
+    /// Whether `key` is this project's name or ID (how commands and API routes look projects up)
+    pub fn matches_key(&self, key: &str) -> bool {
+        self.name == key || self.id() == key
+    }
+
+    /// Copy user-set state (archive flag, metadata) from an earlier copy of this project
+    ///
+    /// Rescans and refreshes rebuild projects from disk; this keeps what only the cache knows.
+    /// The metrics summary comes along too while `.hegel/` hasn't changed since it was taken
+    /// and it was parsed from the same metrics source.
+    pub fn carry_user_state(&mut self, previous: &DiscoveredProject) {
+        self.archived = previous.archived;
+        self.metadata = previous.metadata.clone();
+        self.renamed_from = previous.renamed_from.clone();
+        if previous.last_activity == self.last_activity
+            && previous.metrics_source == self.metrics_source
+        {
+            self.metrics_summary = previous.metrics_summary.clone();
+        }
+    }
+
+    /// Load statistics for this project from its metrics source (lazy loading)
     pub fn load_statistics(&mut self) -> Result<()> {
-        self.statistics = Some(hegel::metrics::parse_unified_metrics(
-            &self.hegel_dir,
-            true,
-            None,
-        )?);
+        self.statistics = Some(self.metrics_source.provider().load(self)?);
         Ok(())
     }
 
+    /// Load statistics unless the source's logs exceed `budget_bytes`
+    ///
+    /// Returns `Ok(false)` (statistics left unloaded) when over budget.
+    pub fn load_statistics_within(&mut self, budget_bytes: Option<u64>) -> Result<bool> {
+        if let Some(budget) = budget_bytes {
+            if self.metrics_source.provider().data_size(self) > budget {
+                return Ok(false);
+            }
+        }
+        self.load_statistics()?;
+        Ok(true)
+    }
+
+    /// Recompute `metrics_summary` from the metrics source (cleared when over budget or unparseable)
+    ///
+    /// Statistics are dropped again afterwards: only the summary is meant to be kept.
+    pub fn update_summary(&mut self, budget_bytes: Option<u64>) {
+        self.metrics_summary = match self.load_statistics_within(budget_bytes) {
+            Ok(true) => self.statistics.as_ref().map(ProjectMetricsSummary::from),
+            Ok(false) | Err(_) => None,
+        };
+        self.statistics = None;
+    }
+
+    /// Total size of files directly in `.hegel/` (the logs metrics are parsed from)
+    pub fn hegel_data_size(&self) -> u64 {
+        std::fs::read_dir(&self.hegel_dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|e| e.ok()?.metadata().ok())
+                    .filter(|m| m.is_file())
+                    .map(|m| m.len())
+                    .sum()
+            })
+            .unwrap_or(0)
+    }
+
     /// Check if statistics are loaded
     pub fn has_statistics(&self) -> bool {
         self.statistics.is_some()
@@ -82,6 +218,34 @@ impl DiscoveredProject {
         Ok(latest)
     }
 
+    /// Total size of files under the .hegel directory, recursing up to `SIZE_MAX_DEPTH` levels
+    ///
+    /// Symlinks aren't followed; unreadable entries count as zero.
+    pub fn calculate_hegel_size(hegel_dir: &Path) -> u64 {
+        WalkDir::new(hegel_dir)
+            .max_depth(SIZE_MAX_DEPTH)
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_file())
+            .filter_map(|entry| entry.metadata().ok())
+            .map(|metadata| metadata.len())
+            .sum()
+    }
+
+    /// Filesystem identity of a `.hegel` directory (see `fs_identity`)
+    #[cfg(unix)]
+    pub fn read_fs_identity(hegel_dir: &Path) -> Option<String> {
+        use std::os::unix::fs::MetadataExt;
+        let metadata = std::fs::metadata(hegel_dir).ok()?;
+        Some(format!("{}:{}", metadata.dev(), metadata.ino()))
+    }
+
+    /// Filesystem identity of a `.hegel` directory (not tracked off Unix)
+    #[cfg(not(unix))]
+    pub fn read_fs_identity(_hegel_dir: &Path) -> Option<String> {
+        None
+    }
+
     /// Check if project has an error (corrupted state)
     pub fn has_error(&self) -> bool {
         self.error.is_some()
@@ -91,6 +255,32 @@ impl DiscoveredProject {
     pub fn has_state(&self) -> bool {
         self.workflow_state.is_some()
     }
+
+    /// Workflow status from the loaded state (see `reload_state` for a fresh read)
+    pub fn status(&self) -> ProjectStatus {
+        if self.has_error() {
+            return ProjectStatus::Error;
+        }
+        match &self.workflow_state {
+            Some(state) if state.current_node == DONE_NODE => ProjectStatus::Completed,
+            Some(_) => ProjectStatus::Active,
+            None => ProjectStatus::Inactive,
+        }
+    }
+
+    /// Re-read `state.json` (cheap: no metrics), replacing the cached workflow state and error
+    pub fn reload_state(&mut self) {
+        match super::load_state(&self.hegel_dir) {
+            Ok(state) => {
+                self.workflow_state = state;
+                self.error = None;
+            }
+            Err(e) => {
+                self.workflow_state = None;
+                self.error = Some(format!("Failed to load state: {}", e));
+            }
+        }
+    }
 }
 
 impl PartialEq for DiscoveredProject {
@@ -163,6 +353,40 @@ mod tests {
         assert_eq!(project.error.as_deref(), Some("Corrupted state"));
     }
 
+    #[test]
+    fn test_carry_user_state_keeps_summary_while_unchanged() {
+        let summary = ProjectMetricsSummary {
+            total_all_tokens: 500,
+            ..Default::default()
+        };
+        let mut previous = DiscoveredProject::new(
+            "test".to_string(),
+            PathBuf::from("/tmp/test"),
+            PathBuf::from("/tmp/test/.hegel"),
+            None,
+            SystemTime::UNIX_EPOCH,
+            None,
+        );
+        previous.metrics_summary = Some(summary);
+
+        let mut unchanged = previous.clone();
+        unchanged.metrics_summary = None;
+        unchanged.carry_user_state(&previous);
+        assert_eq!(unchanged.metrics_summary.unwrap().total_all_tokens, 500);
+
+        let mut changed = previous.clone();
+        changed.metrics_summary = None;
+        changed.last_activity = SystemTime::UNIX_EPOCH + Duration::from_secs(60);
+        changed.carry_user_state(&previous);
+        assert!(changed.metrics_summary.is_none());
+
+        let mut switched = previous.clone();
+        switched.metrics_summary = None;
+        switched.metrics_source = MetricsSource::ClaudeCode;
+        switched.carry_user_state(&previous);
+        assert!(switched.metrics_summary.is_none());
+    }
+
     #[test]
     fn test_calculate_last_activity() {
         let temp = TempDir::new().unwrap();
@@ -184,6 +408,38 @@ mod tests {
         assert!(elapsed < Duration::from_secs(5));
     }
 
+    #[test]
+    fn test_calculate_hegel_size_recurses_into_archives() {
+        let temp = TempDir::new().unwrap();
+        let hegel_dir = temp.path().join(".hegel");
+        let archive = hegel_dir.join("archives").join("2025-01-01");
+        fs::create_dir_all(&archive).unwrap();
+        fs::write(hegel_dir.join("state.json"), b"hello").unwrap();
+        fs::write(archive.join("hooks.jsonl"), b"world!").unwrap();
+
+        assert_eq!(DiscoveredProject::calculate_hegel_size(&hegel_dir), 11);
+        assert_eq!(
+            DiscoveredProject::calculate_hegel_size(&temp.path().join("missing")),
+            0
+        );
+    }
+
+    #[test]
+    fn test_calculate_hegel_size_depth_cap() {
+        let temp = TempDir::new().unwrap();
+        let hegel_dir = temp.path().join(".hegel");
+        let mut deep = hegel_dir.clone();
+        for level in 0..SIZE_MAX_DEPTH {
+            deep = deep.join(format!("d{}", level));
+        }
+        fs::create_dir_all(&deep).unwrap();
+        // At depth SIZE_MAX_DEPTH + 1: beyond the cap
+        fs::write(deep.join("too-deep.jsonl"), b"ignored").unwrap();
+        fs::write(deep.parent().unwrap().join("counted.jsonl"), b"abc").unwrap();
+
+        assert_eq!(DiscoveredProject::calculate_hegel_size(&hegel_dir), 3);
+    }
+
     #[test]
     fn test_sorting_by_recency() {
         let temp1 = TempDir::new().unwrap();
@@ -237,4 +493,76 @@ mod tests {
         assert_eq!(project.project_path, deserialized.project_path);
         assert_eq!(project.hegel_dir, deserialized.hegel_dir);
     }
+
+    #[test]
+    fn test_load_statistics_within_budget() {
+        let temp = TempDir::new().unwrap();
+        let hegel_dir = temp.path().join(".hegel");
+        fs::create_dir(&hegel_dir).unwrap();
+        fs::write(hegel_dir.join("hooks.jsonl"), vec![b'x'; 2048]).unwrap();
+
+        let mut project = DiscoveredProject::new(
+            "big".to_string(),
+            temp.path().to_path_buf(),
+            hegel_dir,
+            None,
+            SystemTime::now(),
+            None,
+        );
+
+        assert_eq!(project.hegel_data_size(), 2048);
+        assert!(!project.load_statistics_within(Some(1024)).unwrap());
+        assert!(!project.has_statistics());
+
+        // Over budget: a stale summary is dropped rather than kept
+        project.metrics_summary = Some(ProjectMetricsSummary::default());
+        project.update_summary(Some(1024));
+        assert!(project.metrics_summary.is_none());
+    }
+
+    #[test]
+    fn test_status_from_reloaded_state() {
+        let temp = TempDir::new().unwrap();
+        let hegel_dir = temp.path().join(".hegel");
+        fs::create_dir(&hegel_dir).unwrap();
+
+        let mut project = DiscoveredProject::new(
+            "test".to_string(),
+            temp.path().to_path_buf(),
+            hegel_dir.clone(),
+            None,
+            SystemTime::now(),
+            None,
+        );
+        assert_eq!(project.status(), ProjectStatus::Inactive);
+
+        let write_node = |node: &str| {
+            let state = format!(
+                r#"{{"workflow": {{"current_node": "{}", "mode": "discovery", "history": ["spec"]}}}}"#,
+                node
+            );
+            fs::write(hegel_dir.join("state.json"), state).unwrap();
+        };
+
+        write_node("code");
+        project.reload_state();
+        assert_eq!(project.status(), ProjectStatus::Active);
+
+        write_node("done");
+        project.reload_state();
+        assert_eq!(project.status(), ProjectStatus::Completed);
+
+        fs::write(hegel_dir.join("state.json"), "{ not json").unwrap();
+        project.reload_state();
+        assert_eq!(project.status(), ProjectStatus::Error);
+    }
+
+    #[test]
+    fn test_status_from_str() {
+        assert_eq!(
+            "completed".parse::<ProjectStatus>().unwrap(),
+            ProjectStatus::Completed
+        );
+        assert!("paused".parse::<ProjectStatus>().is_err());
+    }
 }
@@ -0,0 +1,190 @@
+//! Before/after comparison for refresh (`hegel-pm refresh --dry-run` / `--diff`)
+
+use anyhow::Result;
+use std::time::SystemTime;
+
+use super::cache::rediscover_entry;
+use super::{open_store, refresh_project, DiscoveredProject, DiscoveryConfig};
+
+/// The fields of a cached project that a refresh can change
+#[derive(Debug, Clone, PartialEq)]
+pub struct RefreshSnapshot {
+    pub last_activity: SystemTime,
+    /// Current workflow node (None without workflow state)
+    pub workflow_node: Option<String>,
+    /// Workflow nodes visited so far (length of the workflow history)
+    pub phase_count: usize,
+    pub error: Option<String>,
+    /// `GitInfo::summary()` (None outside a git repository)
+    pub git: Option<String>,
+    /// `GitInfo::head` (None outside a git repository or before HEAD was recorded)
+    pub git_head: Option<String>,
+}
+
+impl From<&DiscoveredProject> for RefreshSnapshot {
+    fn from(project: &DiscoveredProject) -> Self {
+        Self {
+            last_activity: project.last_activity,
+            workflow_node: project
+                .workflow_state
+                .as_ref()
+                .map(|ws| ws.current_node.clone()),
+            phase_count: project
+                .workflow_state
+                .as_ref()
+                .map_or(0, |ws| ws.history.len()),
+            error: project.error.clone(),
+            git: project.git_info.as_ref().map(|git| git.summary()),
+            git_head: project.git_info.as_ref().and_then(|git| git.head.clone()),
+        }
+    }
+}
+
+/// One project's cached state next to its rediscovered state
+#[derive(Debug, Clone, PartialEq)]
+pub struct RefreshDiff {
+    pub name: String,
+    /// Cached state (None if the project file was missing from the cache)
+    pub before: Option<RefreshSnapshot>,
+    pub after: RefreshSnapshot,
+}
+
+impl RefreshDiff {
+    pub fn new(name: &str, before: Option<&DiscoveredProject>, after: &DiscoveredProject) -> Self {
+        Self {
+            name: name.to_string(),
+            before: before.map(RefreshSnapshot::from),
+            after: RefreshSnapshot::from(after),
+        }
+    }
+
+    /// Whether refreshing changes anything the cache records
+    pub fn is_changed(&self) -> bool {
+        self.before.as_ref() != Some(&self.after)
+    }
+}
+
+/// Compare a cached project with what a refresh would write, without touching the cache
+///
+/// Remote projects are compared against their last mirror (nothing is fetched).
+pub fn preview_refresh(project_name: &str, config: &DiscoveryConfig) -> Result<RefreshDiff> {
+    let store = open_store(config)?;
+    let index = match store.load_index()? {
+        Some(idx) => idx,
+        None => {
+            anyhow::bail!("No cache found. Run 'hegel-pm discover list' first to populate cache.")
+        }
+    };
+    let entry = index
+        .iter()
+        .find(|e| e.name == project_name)
+        .ok_or_else(|| anyhow::anyhow!("Project '{}' not found in cache", project_name))?;
+
+    let before = store.load_project(project_name)?;
+    let after = rediscover_entry(entry, before.as_ref(), false)?;
+
+    Ok(RefreshDiff::new(project_name, before.as_ref(), &after))
+}
+
+/// Refresh one project and report what changed
+///
+/// Returns `Ok(None)` if the refresh was skipped (debounced or already in progress).
+pub fn refresh_project_diff(
+    project_name: &str,
+    config: &DiscoveryConfig,
+) -> Result<Option<RefreshDiff>> {
+    let store = open_store(config)?;
+    let before = store.load_project(project_name)?;
+
+    if !refresh_project(project_name, config)? {
+        return Ok(None);
+    }
+
+    let after = store
+        .load_project(project_name)?
+        .ok_or_else(|| anyhow::anyhow!("Project '{}' missing after refresh", project_name))?;
+
+    Ok(Some(RefreshDiff::new(
+        project_name,
+        before.as_ref(),
+        &after,
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::save_binary_cache;
+    use std::fs;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    fn cached_project(temp: &TempDir) -> DiscoveryConfig {
+        let project_path = temp.path().join("alpha");
+        let hegel_dir = project_path.join(".hegel");
+        fs::create_dir_all(&hegel_dir).unwrap();
+        fs::write(
+            hegel_dir.join("state.json"),
+            r#"{"workflow": {"current_node": "code", "mode": "discovery", "history": ["spec", "plan", "code"]}}"#,
+        )
+        .unwrap();
+
+        let config = DiscoveryConfig::new(
+            vec![temp.path().to_path_buf()],
+            10,
+            vec![],
+            temp.path().join("config").join("cache.json"),
+        );
+        // Cached before any workflow existed
+        let project = DiscoveredProject::new(
+            "alpha".to_string(),
+            project_path,
+            hegel_dir,
+            None,
+            SystemTime::UNIX_EPOCH + Duration::from_secs(1_000),
+            None,
+        );
+        save_binary_cache(&[project], &config).unwrap();
+        config
+    }
+
+    #[test]
+    fn test_preview_reports_changes_without_writing() {
+        let temp = TempDir::new().unwrap();
+        let config = cached_project(&temp);
+
+        let diff = preview_refresh("alpha", &config).unwrap();
+        assert!(diff.is_changed());
+        let before = diff.before.as_ref().unwrap();
+        assert_eq!(before.workflow_node, None);
+        assert_eq!(diff.after.workflow_node.as_deref(), Some("code"));
+        assert_eq!(diff.after.phase_count, 3);
+        assert!(diff.after.last_activity > before.last_activity);
+
+        // Nothing written: a second preview sees the same cached state
+        assert_eq!(
+            preview_refresh("alpha", &config).unwrap().before,
+            diff.before
+        );
+    }
+
+    #[test]
+    fn test_refresh_diff_then_unchanged() {
+        let temp = TempDir::new().unwrap();
+        let config = cached_project(&temp);
+
+        let diff = refresh_project_diff("alpha", &config).unwrap().unwrap();
+        assert!(diff.is_changed());
+
+        assert!(!preview_refresh("alpha", &config).unwrap().is_changed());
+    }
+
+    #[test]
+    fn test_preview_not_in_cache() {
+        let temp = TempDir::new().unwrap();
+        let config = cached_project(&temp);
+
+        let err = preview_refresh("missing", &config).unwrap_err();
+        assert!(err.to_string().contains("not found in cache"));
+    }
+}
@@ -0,0 +1,228 @@
+//! Per-project refresh lock and debounce
+//!
+//! Lock and stamp files live under `<cache_dir>/locks/`, keyed by `project_id` so distinct
+//! projects never share one, and every process sharing the cache (CLI, server worker pool)
+//! coordinates through the filesystem:
+//! - `<id>.lock`: held while a refresh runs (created exclusively, removed on drop); it holds
+//!   the owner's token, so an abandoned lock can be taken over with an atomic rename
+//! - `<id>.refreshed`: touched after a successful refresh; its mtime drives the debounce
+
+use anyhow::{Context, Result};
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime};
+
+use super::project_id;
+
+/// Locks older than this are assumed abandoned by a crashed process and taken over
+const STALE_LOCK_AFTER: Duration = Duration::from_secs(300);
+
+/// Distinguishes locks taken by one process (worker threads share a pid)
+static NEXT_TOKEN: AtomicU64 = AtomicU64::new(0);
+
+/// Exclusive refresh lock for one project, released when dropped
+#[derive(Debug)]
+pub struct RefreshLock {
+    lock_path: PathBuf,
+    stamp_path: PathBuf,
+    /// Written into the lock file; identifies this holder
+    token: String,
+}
+
+impl RefreshLock {
+    /// Try to take the refresh lock for the project at `project_path`
+    ///
+    /// Returns `Ok(None)` if another process is refreshing the project right now.
+    pub fn try_acquire(cache_dir: &Path, project_path: &Path) -> Result<Option<Self>> {
+        let lock_dir = cache_dir.join("locks");
+        fs::create_dir_all(&lock_dir).context(format!(
+            "Failed to create lock directory: {}",
+            lock_dir.display()
+        ))?;
+
+        let id = project_id(project_path);
+        let lock = Self {
+            lock_path: lock_dir.join(format!("{}.lock", id)),
+            stamp_path: lock_dir.join(format!("{}.refreshed", id)),
+            token: format!(
+                "{}-{}",
+                std::process::id(),
+                NEXT_TOKEN.fetch_add(1, Ordering::Relaxed)
+            ),
+        };
+
+        match OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&lock.lock_path)
+        {
+            Ok(mut file) => {
+                if let Err(e) = file.write_all(lock.token.as_bytes()) {
+                    fs::remove_file(&lock.lock_path).ok();
+                    return Err(e).context(format!(
+                        "Failed to write lock file: {}",
+                        lock.lock_path.display()
+                    ));
+                }
+                Ok(Some(lock))
+            }
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                if !is_older_than(&lock.lock_path, STALE_LOCK_AFTER) {
+                    return Ok(None);
+                }
+                lock.take_over()
+            }
+            Err(e) => Err(e).context(format!(
+                "Failed to create lock file: {}",
+                lock.lock_path.display()
+            )),
+        }
+    }
+
+    /// Replace an abandoned lock file with ours in one rename
+    ///
+    /// Processes racing for the same stale lock each rename their own file over it; only the
+    /// one whose token is in place afterwards holds the lock.
+    fn take_over(self) -> Result<Option<Self>> {
+        let staged = self
+            .lock_path
+            .with_extension(format!("lock.{}", self.token));
+        fs::write(&staged, &self.token)
+            .context(format!("Failed to write lock file: {}", staged.display()))?;
+        if let Err(e) = fs::rename(&staged, &self.lock_path) {
+            fs::remove_file(&staged).ok();
+            return Err(e).context(format!(
+                "Failed to replace stale lock file: {}",
+                self.lock_path.display()
+            ));
+        }
+
+        // Otherwise another process took over after us (dropping `self` leaves its lock alone)
+        Ok(self.is_held().then_some(self))
+    }
+
+    /// The lock file still carries this holder's token
+    fn is_held(&self) -> bool {
+        fs::read_to_string(&self.lock_path).is_ok_and(|token| token == self.token)
+    }
+
+    /// Record a successful refresh (starts the debounce window)
+    pub fn mark_refreshed(&self) -> Result<()> {
+        fs::write(&self.stamp_path, b"").context(format!(
+            "Failed to write refresh stamp: {}",
+            self.stamp_path.display()
+        ))
+    }
+}
+
+impl Drop for RefreshLock {
+    fn drop(&mut self) {
+        // A lock taken over as stale belongs to its new holder
+        if self.is_held() {
+            fs::remove_file(&self.lock_path).ok();
+        }
+    }
+}
+
+/// Whether the project at `project_path` was successfully refreshed less than `window` ago
+pub fn refreshed_within(cache_dir: &Path, project_path: &Path, window: Duration) -> bool {
+    let stamp_path = cache_dir
+        .join("locks")
+        .join(format!("{}.refreshed", project_id(project_path)));
+    stamp_path.exists() && !is_older_than(&stamp_path, window)
+}
+
+/// File mtime is more than `age` in the past (unreadable mtimes count as old)
+fn is_older_than(path: &Path, age: Duration) -> bool {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .map(|modified| {
+            // Clock skew can put a fresh mtime slightly in the future
+            SystemTime::now()
+                .duration_since(modified)
+                .unwrap_or_default()
+                > age
+        })
+        .unwrap_or(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_lock_is_exclusive_until_dropped() {
+        let temp = TempDir::new().unwrap();
+
+        let alpha = Path::new("/code/alpha");
+
+        let lock = RefreshLock::try_acquire(temp.path(), alpha).unwrap();
+        assert!(lock.is_some());
+        assert!(RefreshLock::try_acquire(temp.path(), alpha)
+            .unwrap()
+            .is_none());
+        // Other projects are independent, even when their names sanitize alike
+        let dotted = RefreshLock::try_acquire(temp.path(), Path::new("/code/a.b")).unwrap();
+        let underscored = RefreshLock::try_acquire(temp.path(), Path::new("/code/a_b")).unwrap();
+        assert!(dotted.is_some() && underscored.is_some());
+
+        drop(lock);
+        assert!(RefreshLock::try_acquire(temp.path(), alpha)
+            .unwrap()
+            .is_some());
+    }
+
+    #[test]
+    fn test_stale_lock_is_taken_over() {
+        let temp = TempDir::new().unwrap();
+        let alpha = Path::new("/code/alpha");
+        let abandoned = RefreshLock::try_acquire(temp.path(), alpha)
+            .unwrap()
+            .unwrap();
+
+        // Age the lock file past the stale threshold
+        let old = SystemTime::now() - STALE_LOCK_AFTER - Duration::from_secs(1);
+        fs::File::options()
+            .write(true)
+            .open(&abandoned.lock_path)
+            .unwrap()
+            .set_modified(old)
+            .unwrap();
+
+        let lock = RefreshLock::try_acquire(temp.path(), alpha)
+            .unwrap()
+            .unwrap();
+        assert!(lock.is_held());
+        assert!(!abandoned.is_held());
+
+        // The previous holder finishing late doesn't release the new holder's lock
+        drop(abandoned);
+        assert!(lock.lock_path.exists());
+        assert!(RefreshLock::try_acquire(temp.path(), alpha)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_refreshed_within_window() {
+        let temp = TempDir::new().unwrap();
+        let window = Duration::from_secs(60);
+        let alpha = Path::new("/code/alpha");
+        assert!(!refreshed_within(temp.path(), alpha, window));
+
+        let lock = RefreshLock::try_acquire(temp.path(), alpha)
+            .unwrap()
+            .unwrap();
+        lock.mark_refreshed().unwrap();
+
+        assert!(refreshed_within(temp.path(), alpha, window));
+        assert!(!refreshed_within(
+            temp.path(),
+            Path::new("/code/beta"),
+            window
+        ));
+    }
+}
@@ -0,0 +1,497 @@
+//! Remote roots (`ssh://host/path`)
+//!
+//! Remote `.hegel` directories are mirrored into the local cache directory, after which
+//! state, last-activity and statistics loading run against the mirror unchanged.
+//! Mirrors are plain copies of the remote files, even when `cache_key_file` encrypts the cache.
+
+use anyhow::{bail, Context, Result};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::SystemTime;
+
+use super::{load_state, DiscoveredProject, DiscoveryConfig, ProgressEvent, ProgressSink};
+
+/// A root directory on another machine, written as `ssh://host/absolute/path`
+/// (or `ssh://host:port/absolute/path`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteRoot {
+    /// SSH destination (`host`, `user@host`, or an alias from `~/.ssh/config`)
+    pub host: String,
+    /// SSH port, if not the default (or the one `~/.ssh/config` sets)
+    pub port: Option<u16>,
+    /// Absolute path on the remote host
+    pub path: PathBuf,
+}
+
+impl RemoteRoot {
+    /// Parse an `ssh://host/path` root (None for local paths and invalid remote roots)
+    pub fn parse(root: &Path) -> Option<Self> {
+        Self::try_parse(root).ok().flatten()
+    }
+
+    /// Parse an `ssh://host/path` root: `Ok(None)` for local paths, an error for unusable ones
+    ///
+    /// The host ends up in `ssh`/`rsync` arguments and the mirror path, so hosts that are empty,
+    /// start with `-`, or contain `/`, `\` or `..` are rejected.
+    pub fn try_parse(root: &Path) -> Result<Option<Self>> {
+        let Some(rest) = root.to_str().and_then(|r| r.strip_prefix("ssh://")) else {
+            return Ok(None);
+        };
+        let Some((authority, path)) = rest.split_once('/') else {
+            bail!("No path (expected ssh://host/absolute/path)");
+        };
+
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => match port.parse::<u16>() {
+                Ok(port) if port > 0 => (host, Some(port)),
+                _ => bail!("Invalid port '{}' (expected ssh://host:port/path)", port),
+            },
+            None => (authority, None),
+        };
+        if host.is_empty()
+            || host.starts_with('-')
+            || host.contains(['/', '\\'])
+            || host.contains("..")
+        {
+            bail!(
+                "Invalid host '{}' (must be non-empty, not start with '-', and not contain '/', '\\' or '..')",
+                host
+            );
+        }
+
+        Ok(Some(Self {
+            host: host.to_string(),
+            port,
+            path: PathBuf::from("/").join(path),
+        }))
+    }
+
+    /// `ssh://host/path` form of a path under this root (used as `project_path`)
+    pub fn url(&self, path: &Path) -> PathBuf {
+        match self.port {
+            Some(port) => PathBuf::from(format!("ssh://{}:{}{}", self.host, port, path.display())),
+            None => PathBuf::from(format!("ssh://{}{}", self.host, path.display())),
+        }
+    }
+
+    /// Transport to this root's host
+    pub fn transport(&self) -> SshTransport {
+        SshTransport::new(&self.host).with_port(self.port)
+    }
+
+    /// Local mirror of a remote `.hegel` directory under `cache_dir`
+    pub fn mirror_dir(&self, cache_dir: &Path, hegel_dir: &Path) -> PathBuf {
+        // Either separator: remote paths are unix-style, but joins on a Windows client add `\`
+        let is_separator = |c: char| c == '/' || c == '\\';
+        let safe_path = hegel_dir
+            .to_string_lossy()
+            .trim_start_matches(is_separator)
+            .replace(is_separator, "__");
+        let host_dir = match self.port {
+            Some(port) => format!("{}_{}", self.host, port),
+            None => self.host.clone(),
+        };
+        cache_dir.join("remote").join(host_dir).join(safe_path)
+    }
+}
+
+/// Access to a remote filesystem
+pub trait Transport {
+    /// Project roots (parents of `.hegel` directories) under `root`, honoring depth/exclusions
+    fn find_hegel_directories(
+        &self,
+        root: &Path,
+        max_depth: usize,
+        exclusions: &[String],
+    ) -> Result<Vec<PathBuf>>;
+
+    /// Copy the contents of remote directory `remote` into local directory `local`
+    fn fetch_dir(&self, remote: &Path, local: &Path) -> Result<()>;
+}
+
+/// Transport over the system `ssh` and `rsync` binaries (non-interactive, key auth)
+pub struct SshTransport {
+    host: String,
+    port: Option<u16>,
+}
+
+impl SshTransport {
+    pub fn new(host: &str) -> Self {
+        Self {
+            host: host.to_string(),
+            port: None,
+        }
+    }
+
+    /// Connect to `port` instead of the default
+    pub fn with_port(mut self, port: Option<u16>) -> Self {
+        self.port = port;
+        self
+    }
+}
+
+impl Transport for SshTransport {
+    fn find_hegel_directories(
+        &self,
+        root: &Path,
+        max_depth: usize,
+        exclusions: &[String],
+    ) -> Result<Vec<PathBuf>> {
+        let mut find = format!(
+            "find {} -maxdepth {}",
+            shell_quote(&root.to_string_lossy()),
+            max_depth
+        );
+        if !exclusions.is_empty() {
+            // Names and name globs match the last component; paths and path globs the whole path
+            let tests: Vec<String> = exclusions
+                .iter()
+                .map(|pattern| {
+                    let test = if pattern.contains('/') {
+                        "-path"
+                    } else {
+                        "-name"
+                    };
+                    format!("{} {}", test, shell_quote(pattern))
+                })
+                .collect();
+            find.push_str(&format!(" \\( {} \\) -prune -o", tests.join(" -o ")));
+        }
+        find.push_str(" -type d -name .hegel -print");
+
+        let output = Command::new("ssh")
+            .args(ssh_args(&self.host, self.port, &find))
+            .output()
+            .context(format!("Failed to run ssh to {}", self.host))?;
+        if !output.status.success() {
+            bail!(
+                "Remote scan of {}:{} failed: {}",
+                self.host,
+                root.display(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| Path::new(line).parent().map(Path::to_path_buf))
+            .collect())
+    }
+
+    fn fetch_dir(&self, remote: &Path, local: &Path) -> Result<()> {
+        std::fs::create_dir_all(local).context(format!(
+            "Failed to create mirror directory: {}",
+            local.display()
+        ))?;
+
+        let output = Command::new("rsync")
+            .args(rsync_args(&self.host, self.port, remote, local))
+            .output()
+            .context("Failed to run rsync")?;
+        if !output.status.success() {
+            bail!(
+                "Failed to fetch {}:{}: {}",
+                self.host,
+                remote.display(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// ssh arguments running `command` on `host`
+///
+/// `--` ends ssh's options, so the host is never read as one.
+fn ssh_args(host: &str, port: Option<u16>, command: &str) -> Vec<String> {
+    let mut args = vec!["-o".to_string(), "BatchMode=yes".to_string()];
+    if let Some(port) = port {
+        args.extend(["-p".to_string(), port.to_string()]);
+    }
+    args.extend(["--".to_string(), host.to_string(), command.to_string()]);
+    args
+}
+
+/// rsync arguments mirroring `host:remote` into `local`
+///
+/// Trailing slashes copy directory contents; -a preserves mtimes for last_activity.
+/// `--protect-args` hands the remote path to rsync as-is instead of through the remote shell,
+/// so spaces and shell metacharacters in it aren't split or interpreted.
+fn rsync_args(host: &str, port: Option<u16>, remote: &Path, local: &Path) -> Vec<String> {
+    let ssh = match port {
+        Some(port) => format!("ssh -o BatchMode=yes -p {}", port),
+        None => "ssh -o BatchMode=yes".to_string(),
+    };
+    vec![
+        "-a".to_string(),
+        "--delete".to_string(),
+        "--protect-args".to_string(),
+        "-e".to_string(),
+        ssh,
+        format!("{}:{}/", host, remote.display()),
+        format!("{}/", local.display()),
+    ]
+}
+
+/// Discover projects under a remote root, mirroring each `.hegel` directory locally
+pub(super) fn discover_remote(
+    config: &DiscoveryConfig,
+    root: &RemoteRoot,
+    transport: &dyn Transport,
+    progress: &dyn ProgressSink,
+) -> Result<Vec<DiscoveredProject>> {
+    let project_paths =
+        transport.find_hegel_directories(&root.path, config.max_depth, &config.exclusions)?;
+
+    let mut projects = Vec::new();
+    for remote_path in project_paths {
+        let name = remote_path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("unknown")
+            .to_string();
+
+        let remote_hegel_dir = remote_path.join(".hegel");
+        let hegel_dir = root.mirror_dir(&config.cache_dir(), &remote_hegel_dir);
+        let project = match transport.fetch_dir(&remote_hegel_dir, &hegel_dir) {
+            Ok(()) => load_mirrored(name, root.url(&remote_path), hegel_dir),
+            Err(e) => DiscoveredProject::new(
+                name,
+                root.url(&remote_path),
+                hegel_dir,
+                None,
+                SystemTime::UNIX_EPOCH,
+                Some(format!("Failed to fetch remote state: {}", e)),
+            ),
+        };
+
+        progress.emit(ProgressEvent::ProjectScanned {
+            name: project.name.clone(),
+            path: project.project_path.clone(),
+        });
+        projects.push(project);
+    }
+
+    Ok(projects)
+}
+
+/// Re-fetch one remote project's mirror and reload it (used by refresh)
+pub(super) fn refresh_remote(
+    name: &str,
+    project_path: &Path,
+    hegel_dir: &Path,
+) -> Result<DiscoveredProject> {
+    let root = RemoteRoot::parse(project_path)
+        .ok_or_else(|| anyhow::anyhow!("Not a remote path: {}", project_path.display()))?;
+
+    root.transport()
+        .fetch_dir(&root.path.join(".hegel"), hegel_dir)?;
+    Ok(load_mirrored(
+        name.to_string(),
+        project_path.to_path_buf(),
+        hegel_dir.to_path_buf(),
+    ))
+}
+
+/// Build a project from a local mirror (no git info: the repository is remote)
+pub(super) fn load_mirrored(
+    name: String,
+    project_path: PathBuf,
+    hegel_dir: PathBuf,
+) -> DiscoveredProject {
+    let (workflow_state, error) = match load_state(&hegel_dir) {
+        Ok(state) => (state, None),
+        Err(e) => (None, Some(format!("Failed to load state: {}", e))),
+    };
+    let last_activity =
+        DiscoveredProject::calculate_last_activity(&hegel_dir).unwrap_or(SystemTime::UNIX_EPOCH);
+
+    let mut project = DiscoveredProject::new(
+        name,
+        project_path,
+        hegel_dir,
+        workflow_state,
+        last_activity,
+        error,
+    );
+    project.hegel_size_bytes = DiscoveredProject::calculate_hegel_size(&project.hegel_dir);
+    project
+}
+
+/// Single-quote a string for a POSIX shell
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::NoProgress;
+    use std::fs;
+    use tempfile::TempDir;
+
+    /// Serves a local directory tree as if it were remote
+    struct LocalTransport {
+        base: PathBuf,
+    }
+
+    impl Transport for LocalTransport {
+        fn find_hegel_directories(
+            &self,
+            root: &Path,
+            max_depth: usize,
+            exclusions: &[String],
+        ) -> Result<Vec<PathBuf>> {
+            let local = self.base.join(root.strip_prefix("/")?);
+            Ok(
+                super::super::find_hegel_directories(&local, max_depth, exclusions)?
+                    .into_iter()
+                    .map(|p| Path::new("/").join(p.strip_prefix(&self.base).unwrap()))
+                    .collect(),
+            )
+        }
+
+        fn fetch_dir(&self, remote: &Path, local: &Path) -> Result<()> {
+            fs::create_dir_all(local)?;
+            for entry in fs::read_dir(self.base.join(remote.strip_prefix("/")?))? {
+                let entry = entry?;
+                fs::copy(entry.path(), local.join(entry.file_name()))?;
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_parse_remote_root() {
+        let root = RemoteRoot::parse(Path::new("ssh://devbox/home/me/Code")).unwrap();
+        assert_eq!(root.host, "devbox");
+        assert_eq!(root.path, PathBuf::from("/home/me/Code"));
+        assert_eq!(
+            root.url(Path::new("/home/me/Code/app")),
+            PathBuf::from("ssh://devbox/home/me/Code/app")
+        );
+
+        assert!(RemoteRoot::parse(Path::new("/home/me/Code")).is_none());
+        assert!(RemoteRoot::parse(Path::new("ssh:///path")).is_none());
+    }
+
+    #[test]
+    fn test_parse_remote_root_port() {
+        let root = RemoteRoot::parse(Path::new("ssh://me@devbox:2222/srv")).unwrap();
+        assert_eq!(root.host, "me@devbox");
+        assert_eq!(root.port, Some(2222));
+        assert_eq!(root.path, PathBuf::from("/srv"));
+        assert_eq!(
+            root.url(Path::new("/srv/app")),
+            PathBuf::from("ssh://me@devbox:2222/srv/app")
+        );
+        assert_eq!(
+            RemoteRoot::parse(&root.url(Path::new("/srv/app")))
+                .unwrap()
+                .port,
+            Some(2222)
+        );
+        assert!(root
+            .mirror_dir(Path::new("/cache"), Path::new("/srv/app/.hegel"))
+            .starts_with("/cache/remote/me@devbox_2222"));
+
+        let err = RemoteRoot::try_parse(Path::new("ssh://devbox:ssh/srv")).unwrap_err();
+        assert!(err.to_string().contains("Invalid port 'ssh'"));
+    }
+
+    #[test]
+    fn test_parse_rejects_unsafe_hosts() {
+        for root in [
+            "ssh:///srv",
+            "ssh://-oProxyCommand=touch pwned/srv",
+            "ssh://..\\evil/srv",
+            "ssh://a..b/srv",
+            "ssh://devbox",
+        ] {
+            assert!(
+                RemoteRoot::try_parse(Path::new(root)).is_err(),
+                "{} should be rejected",
+                root
+            );
+            assert!(RemoteRoot::parse(Path::new(root)).is_none());
+        }
+        assert!(RemoteRoot::try_parse(Path::new("/srv")).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_ssh_args_end_options_before_host() {
+        assert_eq!(
+            ssh_args("devbox", Some(2222), "true"),
+            ["-o", "BatchMode=yes", "-p", "2222", "--", "devbox", "true"]
+        );
+        assert_eq!(
+            ssh_args("devbox", None, "true"),
+            ["-o", "BatchMode=yes", "--", "devbox", "true"]
+        );
+    }
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("a b"), "'a b'");
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+    }
+
+    #[test]
+    fn test_rsync_args_protect_remote_path() {
+        let args = rsync_args(
+            "devbox",
+            None,
+            Path::new("/srv/my app; rm -rf ~/.hegel"),
+            Path::new("/cache/mirror"),
+        );
+        assert!(args.contains(&"--protect-args".to_string()));
+        // The remote path stays one unquoted argument: rsync sends it past the remote shell
+        assert_eq!(
+            &args[args.len() - 2..],
+            ["devbox:/srv/my app; rm -rf ~/.hegel/", "/cache/mirror/"]
+        );
+
+        let args = rsync_args("devbox", Some(2222), Path::new("/srv"), Path::new("/m"));
+        assert!(args.contains(&"ssh -o BatchMode=yes -p 2222".to_string()));
+    }
+
+    #[test]
+    fn test_discover_remote_mirrors_state() {
+        let remote = TempDir::new().unwrap();
+        let hegel_dir = remote.path().join("srv/app/.hegel");
+        fs::create_dir_all(&hegel_dir).unwrap();
+        fs::write(
+            hegel_dir.join("state.json"),
+            r#"{"workflow": {"current_node": "spec", "mode": "discovery", "history": ["spec"]}}"#,
+        )
+        .unwrap();
+
+        let local = TempDir::new().unwrap();
+        let config = DiscoveryConfig::new(
+            vec![PathBuf::from("ssh://devbox/srv")],
+            10,
+            vec![],
+            local.path().join("cache.json"),
+        );
+        let root = RemoteRoot::parse(&config.root_directories[0]).unwrap();
+        let transport = LocalTransport {
+            base: remote.path().to_path_buf(),
+        };
+
+        let projects = discover_remote(&config, &root, &transport, &NoProgress).unwrap();
+
+        assert_eq!(projects.len(), 1);
+        let project = &projects[0];
+        assert_eq!(project.name, "app");
+        assert_eq!(project.project_path, PathBuf::from("ssh://devbox/srv/app"));
+        assert!(project
+            .hegel_dir
+            .starts_with(config.cache_dir().join("remote/devbox")));
+        assert!(project.hegel_dir.join("state.json").exists());
+        assert_eq!(
+            project.workflow_state.as_ref().unwrap().current_node,
+            "spec"
+        );
+    }
+}
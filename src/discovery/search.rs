@@ -0,0 +1,137 @@
+use super::DiscoveredProject;
+
+/// How a query matched a project (higher variants rank first)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MatchKind {
+    /// Query characters appear in order in the name (e.g. "hpm" → "hegel-pm")
+    Fuzzy,
+    /// Query is a substring of the project path
+    Path,
+    /// Query is a substring of the name
+    Substring,
+    /// Name starts with the query
+    Prefix,
+    /// Name equals the query
+    Exact,
+}
+
+/// Classify how `query` matches a project name/path (case-insensitive)
+///
+/// Returns `None` if there is no match at all.
+pub fn match_kind(name: &str, path: &str, query: &str) -> Option<MatchKind> {
+    let query = query.to_lowercase();
+    let name = name.to_lowercase();
+
+    if name == query {
+        Some(MatchKind::Exact)
+    } else if name.starts_with(&query) {
+        Some(MatchKind::Prefix)
+    } else if name.contains(&query) {
+        Some(MatchKind::Substring)
+    } else if path.to_lowercase().contains(&query) {
+        Some(MatchKind::Path)
+    } else if is_subsequence(&query, &name) {
+        Some(MatchKind::Fuzzy)
+    } else {
+        None
+    }
+}
+
+/// Search projects by name and path, best matches first
+///
+/// Ties keep the input order (projects are usually sorted by recency). An empty
+/// query matches everything.
+pub fn search_projects<'a>(
+    projects: &'a [DiscoveredProject],
+    query: &str,
+) -> Vec<&'a DiscoveredProject> {
+    let query = query.trim();
+    if query.is_empty() {
+        return projects.iter().collect();
+    }
+
+    let mut matches: Vec<(MatchKind, &DiscoveredProject)> = projects
+        .iter()
+        .filter_map(|p| {
+            let path = p.project_path.display().to_string();
+            match_kind(&p.name, &path, query).map(|kind| (kind, p))
+        })
+        .collect();
+
+    // Stable sort keeps recency order within each match tier
+    matches.sort_by(|a, b| b.0.cmp(&a.0));
+    matches.into_iter().map(|(_, p)| p).collect()
+}
+
+/// Check whether all chars of `needle` appear in `haystack` in order
+fn is_subsequence(needle: &str, haystack: &str) -> bool {
+    let mut haystack_chars = haystack.chars();
+    needle
+        .chars()
+        .all(|c| haystack_chars.by_ref().any(|h| h == c))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use std::time::SystemTime;
+
+    fn project(name: &str, path: &str) -> DiscoveredProject {
+        DiscoveredProject::new(
+            name.to_string(),
+            PathBuf::from(path),
+            PathBuf::from(path).join(".hegel"),
+            None,
+            SystemTime::now(),
+            None,
+        )
+    }
+
+    #[test]
+    fn test_match_kind_tiers() {
+        let path = "/home/me/Code/hegel-pm";
+        assert_eq!(
+            match_kind("hegel-pm", path, "hegel-pm"),
+            Some(MatchKind::Exact)
+        );
+        assert_eq!(
+            match_kind("hegel-pm", path, "HEGEL"),
+            Some(MatchKind::Prefix)
+        );
+        assert_eq!(
+            match_kind("hegel-pm", path, "pm"),
+            Some(MatchKind::Substring)
+        );
+        assert_eq!(match_kind("hegel-pm", path, "code/"), Some(MatchKind::Path));
+        assert_eq!(match_kind("hegel-pm", path, "hpm"), Some(MatchKind::Fuzzy));
+        assert_eq!(match_kind("hegel-pm", path, "xyz"), None);
+    }
+
+    #[test]
+    fn test_search_projects_ranking() {
+        let projects = vec![
+            project("my-hegel-tool", "/code/my-hegel-tool"),
+            project("hegel", "/code/hegel"),
+            project("hegel-pm", "/code/hegel-pm"),
+            project("other", "/code/other"),
+        ];
+
+        let results = search_projects(&projects, "hegel");
+        let names: Vec<&str> = results.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["hegel", "hegel-pm", "my-hegel-tool"]);
+    }
+
+    #[test]
+    fn test_search_projects_empty_query() {
+        let projects = vec![project("a", "/a"), project("b", "/b")];
+        assert_eq!(search_projects(&projects, "  ").len(), 2);
+    }
+
+    #[test]
+    fn test_is_subsequence() {
+        assert!(is_subsequence("hpm", "hegel-pm"));
+        assert!(!is_subsequence("mph", "hegel-pm"));
+        assert!(is_subsequence("", "anything"));
+    }
+}
@@ -0,0 +1,351 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use super::{CacheBackend, DiscoveredProject, DiscoveryConfig, ProjectMetricsSummary};
+
+/// Metrics for one project at snapshot time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectSnapshot {
+    pub name: String,
+    pub metrics: ProjectMetricsSummary,
+    /// Current workflow node (absent in snapshots recorded before it was tracked)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workflow_node: Option<String>,
+}
+
+/// Timestamped record of metrics across all projects
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricsSnapshot {
+    pub taken_at: DateTime<Utc>,
+    /// Projects discovered (including those without metrics)
+    pub project_count: usize,
+    pub total_tokens: u64,
+    pub total_events: usize,
+    pub total_phases: usize,
+    /// Per-project summaries (projects without loaded statistics are omitted)
+    pub projects: Vec<ProjectSnapshot>,
+}
+
+impl MetricsSnapshot {
+    /// Build a snapshot from projects whose statistics have been loaded
+    pub fn capture(projects: &[DiscoveredProject], taken_at: DateTime<Utc>) -> Self {
+        let summaries: Vec<ProjectSnapshot> = projects
+            .iter()
+            .filter_map(|p| {
+                p.statistics.as_ref().map(|stats| ProjectSnapshot {
+                    name: p.name.clone(),
+                    metrics: ProjectMetricsSummary::from(stats),
+                    workflow_node: p.workflow_state.as_ref().map(|ws| ws.current_node.clone()),
+                })
+            })
+            .collect();
+
+        Self {
+            taken_at,
+            project_count: projects.len(),
+            total_tokens: summaries.iter().map(|s| s.metrics.total_all_tokens).sum(),
+            total_events: summaries.iter().map(|s| s.metrics.total_events).sum(),
+            total_phases: summaries.iter().map(|s| s.metrics.phase_count).sum(),
+            projects: summaries,
+        }
+    }
+}
+
+/// Directory of snapshot files, one JSON file per snapshot
+///
+/// Files are named by UTC timestamp (`20250101T120000.000000Z.json`) so directory order is time
+/// order; snapshots taken at the same instant get a numeric suffix (`...Z-1.json`).
+///
+/// With a database set (`cache_backend = "sqlite"`), new snapshots go to its `snapshots` table
+/// instead, and files already in the directory are still read.
+pub struct SnapshotStore {
+    dir: PathBuf,
+    #[cfg(feature = "sqlite")]
+    database: Option<PathBuf>,
+}
+
+impl SnapshotStore {
+    /// Create a store rooted at `dir` (created on first write)
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            #[cfg(feature = "sqlite")]
+            database: None,
+        }
+    }
+
+    /// Store at the default location (`~/.config/hegel-pm/snapshots`)
+    pub fn open_default() -> Self {
+        Self::new(DiscoveryConfig::default_snapshot_dir())
+    }
+
+    /// Default store for `config`'s cache backend
+    ///
+    /// The SQLite backend keeps snapshots in `~/.config/hegel-pm/snapshots.sqlite`, outside the
+    /// cache directory, so clearing the cache never drops history.
+    pub fn for_config(config: &DiscoveryConfig) -> Result<Self> {
+        match config.cache_backend {
+            CacheBackend::Files => Ok(Self::open_default()),
+            #[cfg(feature = "sqlite")]
+            CacheBackend::Sqlite => Ok(Self::open_default()
+                .with_database(DiscoveryConfig::default_snapshot_dir().with_extension("sqlite"))),
+            #[cfg(not(feature = "sqlite"))]
+            CacheBackend::Sqlite => anyhow::bail!(
+                "cache_backend \"sqlite\" requires hegel-pm built with `--features sqlite`"
+            ),
+        }
+    }
+
+    /// Record snapshots in the SQLite database at `path` (created on first write)
+    #[cfg(feature = "sqlite")]
+    pub fn with_database(mut self, path: PathBuf) -> Self {
+        self.database = Some(path);
+        self
+    }
+
+    /// Snapshot directory
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Where new snapshots are recorded: the database if set, else the directory
+    pub fn location(&self) -> &Path {
+        #[cfg(feature = "sqlite")]
+        if let Some(database) = &self.database {
+            return database;
+        }
+        &self.dir
+    }
+
+    /// Write a snapshot (atomic write), returning the file path (the database, for SQLite)
+    ///
+    /// Never overwrites an earlier snapshot, even one with the same `taken_at`.
+    pub fn record(&self, snapshot: &MetricsSnapshot) -> Result<PathBuf> {
+        #[cfg(feature = "sqlite")]
+        if let Some(database) = &self.database {
+            super::sqlite_store::record_snapshot(database, snapshot)?;
+            return Ok(database.clone());
+        }
+
+        std::fs::create_dir_all(&self.dir).context(format!(
+            "Failed to create snapshot directory: {}",
+            self.dir.display()
+        ))?;
+
+        let stem = snapshot.taken_at.format("%Y%m%dT%H%M%S%.6fZ").to_string();
+        let path = (0..)
+            .map(|n| match n {
+                0 => self.dir.join(format!("{}.json", stem)),
+                n => self.dir.join(format!("{}-{}.json", stem, n)),
+            })
+            .find(|path| !path.exists())
+            .expect("unbounded range");
+        let json =
+            serde_json::to_string_pretty(snapshot).context("Failed to serialize snapshot")?;
+
+        let temp_file = path.with_extension("json.tmp");
+        std::fs::write(&temp_file, json).context(format!(
+            "Failed to write temp snapshot file: {}",
+            temp_file.display()
+        ))?;
+        std::fs::rename(&temp_file, &path).context(format!(
+            "Failed to rename snapshot file: {}",
+            path.display()
+        ))?;
+
+        Ok(path)
+    }
+
+    /// Load snapshots taken within `[since, until]` (either bound optional), oldest first
+    ///
+    /// Unreadable or corrupted snapshot files are skipped.
+    pub fn history(
+        &self,
+        since: Option<DateTime<Utc>>,
+        until: Option<DateTime<Utc>>,
+    ) -> Result<Vec<MetricsSnapshot>> {
+        #[allow(unused_mut)]
+        let mut snapshots = self.file_history()?;
+        #[cfg(feature = "sqlite")]
+        if let Some(database) = &self.database {
+            snapshots.extend(super::sqlite_store::load_snapshots(database)?);
+        }
+
+        snapshots.retain(|snapshot| {
+            since.is_none_or(|s| snapshot.taken_at >= s)
+                && until.is_none_or(|u| snapshot.taken_at <= u)
+        });
+        snapshots.sort_by_key(|s| s.taken_at);
+        Ok(snapshots)
+    }
+
+    /// Every readable snapshot file in the directory, unordered
+    fn file_history(&self) -> Result<Vec<MetricsSnapshot>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut snapshots = Vec::new();
+        for entry in std::fs::read_dir(&self.dir).context(format!(
+            "Failed to read snapshot directory: {}",
+            self.dir.display()
+        ))? {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+
+            let snapshot: MetricsSnapshot = match std::fs::read_to_string(&path)
+                .ok()
+                .and_then(|content| serde_json::from_str(&content).ok())
+            {
+                Some(snapshot) => snapshot,
+                None => continue,
+            };
+
+            snapshots.push(snapshot);
+        }
+
+        Ok(snapshots)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::SystemTime;
+    use tempfile::TempDir;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_capture_skips_projects_without_statistics() {
+        let temp = TempDir::new().unwrap();
+        let project = DiscoveredProject::new(
+            "project1".to_string(),
+            temp.path().to_path_buf(),
+            temp.path().join(".hegel"),
+            None,
+            SystemTime::now(),
+            None,
+        );
+
+        let snapshot = MetricsSnapshot::capture(&[project], at("2025-01-01T00:00:00Z"));
+        assert_eq!(snapshot.project_count, 1);
+        assert!(snapshot.projects.is_empty());
+        assert_eq!(snapshot.total_tokens, 0);
+    }
+
+    #[test]
+    fn test_record_and_history() {
+        let temp = TempDir::new().unwrap();
+        let store = SnapshotStore::new(temp.path().join("snapshots"));
+
+        for ts in [
+            "2025-03-01T00:00:00Z",
+            "2025-01-01T00:00:00Z",
+            "2025-02-01T00:00:00Z",
+        ] {
+            let path = store
+                .record(&MetricsSnapshot::capture(&[], at(ts)))
+                .unwrap();
+            assert!(path.exists());
+        }
+
+        let all = store.history(None, None).unwrap();
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0].taken_at, at("2025-01-01T00:00:00Z"));
+
+        let range = store
+            .history(
+                Some(at("2025-01-15T00:00:00Z")),
+                Some(at("2025-02-01T00:00:00Z")),
+            )
+            .unwrap();
+        assert_eq!(range.len(), 1);
+        assert_eq!(range[0].taken_at, at("2025-02-01T00:00:00Z"));
+    }
+
+    #[test]
+    fn test_record_keeps_snapshots_taken_at_same_time() {
+        let temp = TempDir::new().unwrap();
+        let store = SnapshotStore::new(temp.path().to_path_buf());
+        let taken_at = at("2025-01-01T12:00:00.250Z");
+
+        let first = store
+            .record(&MetricsSnapshot::capture(&[], taken_at))
+            .unwrap();
+        let second = store
+            .record(&MetricsSnapshot::capture(&[], taken_at))
+            .unwrap();
+
+        assert_eq!(first, temp.path().join("20250101T120000.250000Z.json"));
+        assert_eq!(second, temp.path().join("20250101T120000.250000Z-1.json"));
+        assert_eq!(store.history(None, None).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_history_skips_corrupted_files() {
+        let temp = TempDir::new().unwrap();
+        let store = SnapshotStore::new(temp.path().to_path_buf());
+        store
+            .record(&MetricsSnapshot::capture(&[], at("2025-01-01T00:00:00Z")))
+            .unwrap();
+        std::fs::write(temp.path().join("broken.json"), "not json").unwrap();
+
+        assert_eq!(store.history(None, None).unwrap().len(), 1);
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn test_sqlite_record_and_history() {
+        let temp = TempDir::new().unwrap();
+        let database = temp.path().join("snapshots.sqlite");
+        let files = SnapshotStore::new(temp.path().join("snapshots"));
+        let store =
+            SnapshotStore::new(temp.path().join("snapshots")).with_database(database.clone());
+
+        // Snapshot files from before the switch stay in the history
+        files
+            .record(&MetricsSnapshot::capture(&[], at("2025-02-01T00:00:00Z")))
+            .unwrap();
+        for ts in ["2025-03-01T00:00:00Z", "2025-01-01T00:00:00Z"] {
+            let path = store
+                .record(&MetricsSnapshot::capture(&[], at(ts)))
+                .unwrap();
+            assert_eq!(path, database);
+        }
+        store
+            .record(&MetricsSnapshot::capture(&[], at("2025-03-01T00:00:00Z")))
+            .unwrap();
+
+        let all = store.history(None, None).unwrap();
+        let times: Vec<_> = all.iter().map(|s| s.taken_at).collect();
+        assert_eq!(
+            times,
+            [
+                at("2025-01-01T00:00:00Z"),
+                at("2025-02-01T00:00:00Z"),
+                at("2025-03-01T00:00:00Z"),
+                at("2025-03-01T00:00:00Z"),
+            ]
+        );
+        assert_eq!(files.history(None, None).unwrap().len(), 1);
+
+        let range = store
+            .history(Some(at("2025-01-15T00:00:00Z")), None)
+            .unwrap();
+        assert_eq!(range.len(), 3);
+    }
+
+    #[test]
+    fn test_history_missing_directory() {
+        let temp = TempDir::new().unwrap();
+        let store = SnapshotStore::new(temp.path().join("missing"));
+        assert!(store.history(None, None).unwrap().is_empty());
+    }
+}
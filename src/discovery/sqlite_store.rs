@@ -0,0 +1,460 @@
+//! SQLite cache backend (enabled with the `sqlite` feature)
+//!
+//! One row per project with indexed name and last activity, so lookups and
+//! activity queries don't deserialize the whole cache. Project data is stored as
+//! the same JSON the file backend writes. Metrics snapshots get their own database
+//! (`record_snapshot`/`load_snapshots`), one JSON row per snapshot.
+
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection, OptionalExtension, Transaction, TransactionBehavior};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use super::cache::cacheable_copy;
+use super::{CacheStore, DiscoveredProject, MetricsSnapshot, ProjectIndexEntry};
+use crate::warn;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS projects (
+        name TEXT PRIMARY KEY,
+        position INTEGER NOT NULL,
+        project_path TEXT NOT NULL,
+        hegel_dir TEXT NOT NULL,
+        last_activity_ns INTEGER NOT NULL,
+        data TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS projects_last_activity ON projects (last_activity_ns);
+    CREATE TABLE IF NOT EXISTS meta (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL
+    );
+";
+
+/// Snapshot history, in its own database (`SnapshotStore::with_database`)
+const SNAPSHOT_SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS snapshots (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        taken_at TEXT NOT NULL,
+        data TEXT NOT NULL
+    );
+";
+
+/// Index columns; the size is read from the project JSON (no column, so no schema migration)
+const INDEX_SELECT: &str = "SELECT name, project_path, hegel_dir, last_activity_ns,
+    COALESCE(json_extract(data, '$.hegel_size_bytes'), 0) FROM projects";
+
+/// Cache store backed by a single SQLite database file
+pub struct SqliteCacheStore {
+    conn: Connection,
+}
+
+impl SqliteCacheStore {
+    /// Open (or create) the database at `path`
+    pub fn open(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).context(format!(
+                "Failed to create cache directory: {}",
+                parent.display()
+            ))?;
+        }
+
+        let conn = Connection::open(path)
+            .context(format!("Failed to open cache database: {}", path.display()))?;
+        Self::with_connection(conn)
+    }
+
+    fn with_connection(conn: Connection) -> Result<Self> {
+        conn.execute_batch(SCHEMA)
+            .context("Failed to initialize cache schema")?;
+        Ok(Self { conn })
+    }
+
+    /// Whether `save_all` has ever run (distinguishes empty cache from cache miss)
+    fn is_populated(&self) -> Result<bool> {
+        let populated: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT value FROM meta WHERE key = 'populated'",
+                [],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(populated.is_some())
+    }
+
+    /// Replace every row with `projects`, in order, and mark the cache populated
+    fn replace_all(conn: &Connection, projects: &[DiscoveredProject]) -> Result<()> {
+        conn.execute("DELETE FROM projects", [])?;
+        for (position, project) in projects.iter().enumerate() {
+            Self::upsert(conn, project, position as i64)?;
+        }
+        conn.execute(
+            "INSERT OR REPLACE INTO meta (key, value) VALUES ('populated', '1')",
+            [],
+        )?;
+        Ok(())
+    }
+
+    /// Insert or update one project and mark the cache populated
+    fn save_in(conn: &Connection, project: &DiscoveredProject) -> Result<()> {
+        // New projects go to the end; existing rows keep their position
+        let next_position: i64 = conn.query_row(
+            "SELECT COALESCE(MAX(position) + 1, 0) FROM projects",
+            [],
+            |row| row.get(0),
+        )?;
+        Self::upsert(conn, project, next_position)?;
+        conn.execute(
+            "INSERT OR REPLACE INTO meta (key, value) VALUES ('populated', '1')",
+            [],
+        )?;
+        Ok(())
+    }
+
+    fn upsert(conn: &Connection, project: &DiscoveredProject, position: i64) -> Result<()> {
+        let data = serde_json::to_string(&cacheable_copy(project))
+            .context("Failed to serialize project")?;
+        conn.execute(
+            "INSERT INTO projects (name, position, project_path, hegel_dir, last_activity_ns, data)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT(name) DO UPDATE SET
+                 project_path = excluded.project_path,
+                 hegel_dir = excluded.hegel_dir,
+                 last_activity_ns = excluded.last_activity_ns,
+                 data = excluded.data",
+            params![
+                project.name,
+                position,
+                project.project_path.to_string_lossy(),
+                project.hegel_dir.to_string_lossy(),
+                to_nanos(project.last_activity),
+                data
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn query_index(&self, sql: &str, since_ns: i64) -> Result<Vec<ProjectIndexEntry>> {
+        let mut stmt = self.conn.prepare(sql)?;
+        let rows = stmt.query_map([since_ns], |row| {
+            Ok(ProjectIndexEntry {
+                name: row.get(0)?,
+                project_path: PathBuf::from(row.get::<_, String>(1)?),
+                hegel_dir: PathBuf::from(row.get::<_, String>(2)?),
+                last_activity: from_nanos(row.get(3)?),
+                hegel_size_bytes: row.get::<_, i64>(4)?.max(0) as u64,
+            })
+        })?;
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    }
+}
+
+fn open_snapshot_database(path: &Path) -> Result<Connection> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).context(format!(
+            "Failed to create snapshot directory: {}",
+            parent.display()
+        ))?;
+    }
+
+    let conn = Connection::open(path).context(format!(
+        "Failed to open snapshot database: {}",
+        path.display()
+    ))?;
+    conn.execute_batch(SNAPSHOT_SCHEMA)
+        .context("Failed to initialize snapshot schema")?;
+    Ok(conn)
+}
+
+/// Append a snapshot row (same JSON as a snapshot file)
+pub(super) fn record_snapshot(path: &Path, snapshot: &MetricsSnapshot) -> Result<()> {
+    let data = serde_json::to_string(snapshot).context("Failed to serialize snapshot")?;
+    open_snapshot_database(path)?.execute(
+        "INSERT INTO snapshots (taken_at, data) VALUES (?1, ?2)",
+        params![snapshot.taken_at.to_rfc3339(), data],
+    )?;
+    Ok(())
+}
+
+/// Every snapshot in the database, in recording order (unreadable rows are skipped, like files)
+pub(super) fn load_snapshots(path: &Path) -> Result<Vec<MetricsSnapshot>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let conn = open_snapshot_database(path)?;
+    let mut stmt = conn.prepare("SELECT data FROM snapshots ORDER BY id")?;
+    let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+
+    let mut snapshots = Vec::new();
+    for data in rows {
+        if let Ok(snapshot) = serde_json::from_str(&data?) {
+            snapshots.push(snapshot);
+        }
+    }
+    Ok(snapshots)
+}
+
+impl CacheStore for SqliteCacheStore {
+    fn load_index(&self) -> Result<Option<Vec<ProjectIndexEntry>>> {
+        if !self.is_populated()? {
+            return Ok(None);
+        }
+        self.query_index(
+            &format!(
+                "{} WHERE last_activity_ns >= ?1 ORDER BY position",
+                INDEX_SELECT
+            ),
+            i64::MIN,
+        )
+        .map(Some)
+    }
+
+    /// Rows that fail to deserialize are skipped with warnings (matches the file backend)
+    fn load_all(&self) -> Result<Option<Vec<DiscoveredProject>>> {
+        if !self.is_populated()? {
+            return Ok(None);
+        }
+
+        let mut stmt = self
+            .conn
+            .prepare("SELECT name, data FROM projects ORDER BY position")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+
+        let mut projects = Vec::new();
+        for row in rows {
+            let (name, data) = row?;
+            match serde_json::from_str(&data) {
+                Ok(project) => projects.push(project),
+                Err(e) => warn!("failed to load project '{}': {}", name, e),
+            }
+        }
+
+        Ok(Some(projects))
+    }
+
+    fn save_all(&self, projects: &[DiscoveredProject]) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        Self::replace_all(&tx, projects)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn merge_and_save_all(
+        &self,
+        projects: &mut [DiscoveredProject],
+        merge: &dyn Fn(&mut [DiscoveredProject], &[DiscoveredProject]),
+    ) -> Result<()> {
+        // IMMEDIATE takes the write lock before the read, not at the first write
+        let tx = Transaction::new_unchecked(&self.conn, TransactionBehavior::Immediate)?;
+        let cached = self.load_all().ok().flatten().unwrap_or_default();
+        merge(projects, &cached);
+        Self::replace_all(&tx, projects)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn load_project(&self, name: &str) -> Result<Option<DiscoveredProject>> {
+        let data: Option<String> = self
+            .conn
+            .query_row("SELECT data FROM projects WHERE name = ?1", [name], |row| {
+                row.get(0)
+            })
+            .optional()?;
+
+        data.map(|d| serde_json::from_str(&d).context("Failed to deserialize project"))
+            .transpose()
+    }
+
+    fn save_project(&self, project: &DiscoveredProject) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        Self::save_in(&tx, project)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn update_project(
+        &self,
+        name: &str,
+        update: Box<dyn FnOnce(&mut DiscoveredProject) + '_>,
+    ) -> Result<bool> {
+        // IMMEDIATE takes the write lock before the read, not at the first write
+        let tx = Transaction::new_unchecked(&self.conn, TransactionBehavior::Immediate)?;
+        let Some(mut project) = self.load_project(name)? else {
+            return Ok(false);
+        };
+        update(&mut project);
+        Self::save_in(&tx, &project)?;
+        tx.commit()?;
+        Ok(true)
+    }
+
+    fn remove_project(&self, name: &str) -> Result<bool> {
+        let removed = self
+            .conn
+            .execute("DELETE FROM projects WHERE name = ?1", [name])?;
+        Ok(removed > 0)
+    }
+
+    fn active_since(&self, since: SystemTime) -> Result<Vec<ProjectIndexEntry>> {
+        self.query_index(
+            &format!(
+                "{} WHERE last_activity_ns >= ?1 ORDER BY last_activity_ns DESC",
+                INDEX_SELECT
+            ),
+            to_nanos(since),
+        )
+    }
+}
+
+/// Nanoseconds since the Unix epoch (pre-epoch times clamp to 0)
+fn to_nanos(time: SystemTime) -> i64 {
+    time.duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_nanos() as i64)
+        .unwrap_or(0)
+}
+
+fn from_nanos(nanos: i64) -> SystemTime {
+    SystemTime::UNIX_EPOCH + Duration::from_nanos(nanos.max(0) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::cache::index_entry;
+    use tempfile::TempDir;
+
+    fn store() -> SqliteCacheStore {
+        SqliteCacheStore::with_connection(Connection::open_in_memory().unwrap()).unwrap()
+    }
+
+    fn project(name: &str, secs: u64) -> DiscoveredProject {
+        DiscoveredProject::new(
+            name.to_string(),
+            PathBuf::from("/code").join(name),
+            PathBuf::from("/code").join(name).join(".hegel"),
+            None,
+            SystemTime::UNIX_EPOCH + Duration::from_secs(secs),
+            None,
+        )
+    }
+
+    #[test]
+    fn test_empty_store_is_cache_miss() {
+        let store = store();
+        assert!(store.load_all().unwrap().is_none());
+        assert!(store.load_index().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_save_and_load_preserves_order() {
+        let store = store();
+        let mut a = project("a", 100);
+        a.hegel_size_bytes = 4096;
+        store
+            .save_all(&[project("b", 300), a.clone(), project("c", 200)])
+            .unwrap();
+
+        let names: Vec<String> = store
+            .load_all()
+            .unwrap()
+            .unwrap()
+            .into_iter()
+            .map(|p| p.name)
+            .collect();
+        assert_eq!(names, vec!["b", "a", "c"]);
+
+        let index = store.load_index().unwrap().unwrap();
+        assert_eq!(index[1], index_entry(&a));
+        assert_eq!(index[1].hegel_size_bytes, 4096);
+    }
+
+    #[test]
+    fn test_save_project_upserts() {
+        let store = store();
+        store.save_all(&[project("a", 100)]).unwrap();
+
+        store.save_project(&project("a", 500)).unwrap();
+        store.save_project(&project("b", 200)).unwrap();
+
+        let index = store.load_index().unwrap().unwrap();
+        assert_eq!(index.len(), 2);
+        assert_eq!(index[0].name, "a");
+        assert_eq!(
+            index[0].last_activity,
+            SystemTime::UNIX_EPOCH + Duration::from_secs(500)
+        );
+        assert!(store.load_project("b").unwrap().is_some());
+    }
+
+    #[test]
+    fn test_update_project() {
+        let store = store();
+        store
+            .save_all(&[project("a", 100), project("b", 200)])
+            .unwrap();
+
+        let updated = store
+            .update_project("a", Box::new(|p| p.archived = true))
+            .unwrap();
+        assert!(updated);
+        assert!(!store.update_project("c", Box::new(|_| {})).unwrap());
+
+        let loaded = store.load_all().unwrap().unwrap();
+        assert_eq!(loaded[0].name, "a");
+        assert!(loaded[0].archived);
+        assert!(!loaded[1].archived);
+    }
+
+    #[test]
+    fn test_merge_and_save_all_sees_current_rows() {
+        let store = store();
+        store.save_all(&[project("a", 100)]).unwrap();
+        let mut archived = project("a", 100);
+        archived.archived = true;
+        store.save_project(&archived).unwrap();
+
+        let mut scanned = vec![project("a", 400), project("b", 200)];
+        store
+            .merge_and_save_all(&mut scanned, &|projects, cached| {
+                projects[0].archived = cached[0].archived;
+            })
+            .unwrap();
+
+        let loaded = store.load_all().unwrap().unwrap();
+        assert_eq!(loaded.len(), 2);
+        assert!(loaded[0].archived);
+        assert_eq!(
+            loaded[0].last_activity,
+            SystemTime::UNIX_EPOCH + Duration::from_secs(400)
+        );
+    }
+
+    #[test]
+    fn test_remove_and_active_since() {
+        let store = store();
+        store
+            .save_all(&[project("a", 100), project("b", 300), project("c", 200)])
+            .unwrap();
+
+        assert!(store.remove_project("c").unwrap());
+        assert!(!store.remove_project("c").unwrap());
+
+        let active = store
+            .active_since(SystemTime::UNIX_EPOCH + Duration::from_secs(50))
+            .unwrap();
+        let names: Vec<&str> = active.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["b", "a"]);
+    }
+
+    #[test]
+    fn test_open_creates_database_file() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("cache").join("cache.sqlite");
+        let store = SqliteCacheStore::open(&path).unwrap();
+        store.save_all(&[project("a", 100)]).unwrap();
+        assert!(path.exists());
+    }
+}
@@ -1,20 +1,125 @@
-use anyhow::{Context, Result};
-use std::path::PathBuf;
+//! Workflow state extraction from `.hegel/state.json`
+//!
+//! hegel-cli's `FileStorage` reads the current schema. When it can't (older hegel-cli
+//! versions, fields it no longer understands), the file is read again through a tolerant
+//! layer that recognizes the historical layouts (`StateSchema`) and upgrades the workflow part
+//! to the current `WorkflowState`, ignoring everything else.
+
+use anyhow::{bail, Context, Result};
+use serde_json::{Map, Value};
+use std::path::{Path, PathBuf};
 
 use hegel::storage::FileStorage;
 
 use super::WorkflowState;
+use crate::debug;
+
+/// Mode recorded for workflows whose state predates modes
+const UNKNOWN_MODE: &str = "unknown";
+
+/// Layouts of `state.json` written by hegel-cli over time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StateSchema {
+    /// `{"workflow": {"current_node", "mode", "history", ...}}`
+    Current,
+    /// `{"workflow": <definition>, "workflow_state": {"current_node", ...}}`: the state lived
+    /// beside the workflow definition before moving under `workflow`
+    LegacyWorkflowState,
+}
+
+impl StateSchema {
+    /// Schema of a parsed state file (None when it holds no workflow state)
+    fn detect(state: &Value) -> Option<Self> {
+        let has_node = |key: &str| state[key].get("current_node").is_some();
+        if has_node("workflow") {
+            Some(StateSchema::Current)
+        } else if has_node("workflow_state") {
+            Some(StateSchema::LegacyWorkflowState)
+        } else {
+            None
+        }
+    }
+
+    fn workflow_key(self) -> &'static str {
+        match self {
+            StateSchema::Current => "workflow",
+            StateSchema::LegacyWorkflowState => "workflow_state",
+        }
+    }
+}
 
 /// Load workflow state from a .hegel directory
+///
+/// Falls back to the tolerant reader (see module docs) when hegel-cli's storage fails or finds
+/// no workflow; the original error is returned only if that can't make sense of the file.
 pub fn load_state(hegel_dir: &PathBuf) -> Result<Option<WorkflowState>> {
     let storage = FileStorage::new(hegel_dir).context(format!(
         "Failed to create storage for {}",
         hegel_dir.display()
     ))?;
 
-    let state = storage.load().context("Failed to load state")?;
+    match storage.load().context("Failed to load state") {
+        Ok(state) if state.workflow.is_some() => Ok(state.workflow),
+        Ok(_) => Ok(load_tolerant(hegel_dir).ok().flatten()),
+        Err(e) => match load_tolerant(hegel_dir) {
+            Ok(Some(workflow)) => Ok(Some(workflow)),
+            _ => Err(e),
+        },
+    }
+}
 
-    Ok(state.workflow)
+/// Read `state.json` directly, upgrading any known schema to the current `WorkflowState`
+fn load_tolerant(hegel_dir: &Path) -> Result<Option<WorkflowState>> {
+    let path = hegel_dir.join("state.json");
+    if !path.exists() {
+        return Ok(None);
+    }
+    let contents = std::fs::read_to_string(&path)?;
+    let state: Value = serde_json::from_str(&contents)?;
+
+    let Some(schema) = StateSchema::detect(&state) else {
+        return Ok(None);
+    };
+    let Some(workflow) = state[schema.workflow_key()].as_object() else {
+        bail!("Workflow state is not an object");
+    };
+
+    debug!("📜 Upgrading {:?} state in {}", schema, path.display());
+    upgrade_workflow(workflow).map(Some)
+}
+
+/// Convert a workflow object from any schema to the current `WorkflowState`
+///
+/// Objects the current type accepts are used as-is. Otherwise only the fields every schema
+/// shares are kept: `current_node` (required), `mode` (default `unknown`), `history` (default:
+/// just the current node), and `workflow_id`.
+fn upgrade_workflow(workflow: &Map<String, Value>) -> Result<WorkflowState> {
+    if let Ok(state) = serde_json::from_value(Value::Object(workflow.clone())) {
+        return Ok(state);
+    }
+
+    let Some(current_node) = workflow.get("current_node").and_then(Value::as_str) else {
+        bail!("Workflow state has no current_node");
+    };
+    let mode = workflow
+        .get("mode")
+        .and_then(Value::as_str)
+        .unwrap_or(UNKNOWN_MODE);
+    let history: Vec<&str> = match workflow.get("history").and_then(Value::as_array) {
+        Some(nodes) => nodes.iter().filter_map(Value::as_str).collect(),
+        None => vec![current_node],
+    };
+
+    let mut upgraded = serde_json::json!({
+        "current_node": current_node,
+        "mode": mode,
+        "history": history,
+    });
+    if let Some(id) = workflow.get("workflow_id").and_then(Value::as_str) {
+        upgraded["workflow_id"] = Value::from(id);
+    }
+
+    serde_json::from_value(upgraded).context("Failed to upgrade workflow state")
 }
 
 #[cfg(test)]
@@ -106,4 +211,56 @@ mod tests {
         assert_eq!(workflow_state.current_node, "code");
         assert!(workflow_state.workflow_id.is_some());
     }
+
+    #[test]
+    fn test_load_legacy_workflow_state_schema() {
+        let temp = create_hegel_dir_with_state(
+            r#"{
+                "workflow": {"nodes": {"spec": {}, "code": {}}, "start_node": "spec"},
+                "workflow_state": {
+                    "current_node": "code",
+                    "mode": "execution",
+                    "history": ["spec", "code"]
+                }
+            }"#,
+        );
+
+        let state = load_state(&temp.path().join(".hegel")).unwrap().unwrap();
+        assert_eq!(state.current_node, "code");
+        assert_eq!(state.mode, "execution");
+        assert_eq!(state.history, vec!["spec", "code"]);
+    }
+
+    #[test]
+    fn test_load_state_missing_fields_and_extras() {
+        let temp = create_hegel_dir_with_state(
+            r#"{
+                "workflow": {"current_node": "plan", "phase_start": 1700000000},
+                "session_metadata": "not an object any more"
+            }"#,
+        );
+
+        let state = load_state(&temp.path().join(".hegel")).unwrap().unwrap();
+        assert_eq!(state.current_node, "plan");
+    }
+
+    #[test]
+    fn test_schema_detection_and_upgrade() {
+        let current = serde_json::json!({"workflow": {"current_node": "code"}});
+        assert_eq!(StateSchema::detect(&current), Some(StateSchema::Current));
+        let legacy = serde_json::json!({"workflow": {}, "workflow_state": {"current_node": "x"}});
+        assert_eq!(
+            StateSchema::detect(&legacy),
+            Some(StateSchema::LegacyWorkflowState)
+        );
+        assert_eq!(StateSchema::detect(&serde_json::json!({})), None);
+
+        let bare = serde_json::json!({"current_node": "spec", "history": ["spec", 3]});
+        let upgraded = upgrade_workflow(bare.as_object().unwrap()).unwrap();
+        assert_eq!(upgraded.mode, UNKNOWN_MODE);
+        assert_eq!(upgraded.history, vec!["spec"]);
+
+        let no_node = serde_json::json!({"mode": "discovery"});
+        assert!(upgrade_workflow(no_node.as_object().unwrap()).is_err());
+    }
 }
@@ -1,6 +1,15 @@
 // Type alias to hegel-cli's UnifiedMetrics
+pub use hegel::metrics::TokenMetrics;
 pub use hegel::metrics::UnifiedMetrics as ProjectStatistics;
 
+/// Every token counted in `metrics`: input, output, cache creation, and cache reads
+pub fn total_tokens(metrics: &TokenMetrics) -> u64 {
+    metrics.total_input_tokens
+        + metrics.total_output_tokens
+        + metrics.total_cache_creation_tokens
+        + metrics.total_cache_read_tokens
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,166 @@
+//! Pluggable storage for the CLI project cache
+//!
+//! `CacheStore` abstracts over the multi-file layout (`FileCacheStore`, default) and an
+//! embedded SQLite database (`SqliteCacheStore`, behind the `sqlite` feature). The backend
+//! is selected by `DiscoveryConfig::cache_backend`. The server's `cache.json` is unaffected.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::time::SystemTime;
+
+use super::{DiscoveredProject, DiscoveryConfig, FileCacheStore, ProjectIndexEntry};
+
+/// Which storage backend holds the CLI project cache
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CacheBackend {
+    /// `index.bin` + one file per project under the cache directory
+    #[default]
+    Files,
+    /// `cache.sqlite` under the cache directory (requires the `sqlite` feature); snapshots go to `snapshots.sqlite`
+    Sqlite,
+}
+
+/// Storage operations for cached projects
+///
+/// Cached projects never carry `statistics` (reloaded on demand); `workflow_state` is as of the
+/// last scan or refresh.
+pub trait CacheStore {
+    /// Load the lightweight project index (`Ok(None)` if the cache hasn't been populated)
+    fn load_index(&self) -> Result<Option<Vec<ProjectIndexEntry>>>;
+
+    /// Load all cached projects in index order (`Ok(None)` if the cache hasn't been populated)
+    fn load_all(&self) -> Result<Option<Vec<DiscoveredProject>>>;
+
+    /// Replace the cached project set
+    fn save_all(&self, projects: &[DiscoveredProject]) -> Result<()>;
+
+    /// Replace the cached project set after `merge` carries state over from the cached one
+    ///
+    /// The cached set is read under the same write lock as the save, so a project archived or
+    /// saved while the caller was scanning isn't overwritten with stale state. A corrupted
+    /// cache reads as empty.
+    fn merge_and_save_all(
+        &self,
+        projects: &mut [DiscoveredProject],
+        merge: &dyn Fn(&mut [DiscoveredProject], &[DiscoveredProject]),
+    ) -> Result<()>;
+
+    /// Load one project by name
+    fn load_project(&self, name: &str) -> Result<Option<DiscoveredProject>>;
+
+    /// Insert or update one project and its index entry
+    fn save_project(&self, project: &DiscoveredProject) -> Result<()>;
+
+    /// Apply `update` to one cached project and write it back (`Ok(false)` if it isn't cached)
+    ///
+    /// The load and save run under the store's write lock, so concurrent updates (archive,
+    /// `note set`, refresh) don't overwrite each other.
+    fn update_project(
+        &self,
+        name: &str,
+        update: Box<dyn FnOnce(&mut DiscoveredProject) + '_>,
+    ) -> Result<bool>;
+
+    /// Remove one project (`Ok(false)` if it wasn't cached)
+    fn remove_project(&self, name: &str) -> Result<bool>;
+
+    /// Index entries active at or after `since`, most recent first
+    fn active_since(&self, since: SystemTime) -> Result<Vec<ProjectIndexEntry>> {
+        let mut entries: Vec<ProjectIndexEntry> = self
+            .load_index()?
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|e| e.last_activity >= since)
+            .collect();
+        entries.sort_by(|a, b| b.last_activity.cmp(&a.last_activity));
+        Ok(entries)
+    }
+}
+
+/// Open the cache store selected by `config.cache_backend`
+pub fn open_store(config: &DiscoveryConfig) -> Result<Box<dyn CacheStore>> {
+    match config.cache_backend {
+        CacheBackend::Files => Ok(Box::new(FileCacheStore::for_config(config)?)),
+        CacheBackend::Sqlite if config.cache_key_file.is_some() => anyhow::bail!(
+            "cache_key_file (cache encryption) is only supported by the \"files\" cache backend"
+        ),
+        #[cfg(feature = "sqlite")]
+        CacheBackend::Sqlite => Ok(Box::new(super::SqliteCacheStore::open(
+            &config.cache_dir().join("cache.sqlite"),
+        )?)),
+        #[cfg(not(feature = "sqlite"))]
+        CacheBackend::Sqlite => anyhow::bail!(
+            "cache_backend \"sqlite\" requires hegel-pm built with `--features sqlite`"
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    fn project(temp: &TempDir, name: &str, secs: u64) -> DiscoveredProject {
+        DiscoveredProject::new(
+            name.to_string(),
+            temp.path().join(name),
+            temp.path().join(name).join(".hegel"),
+            None,
+            SystemTime::UNIX_EPOCH + Duration::from_secs(secs),
+            None,
+        )
+    }
+
+    #[test]
+    fn test_backend_serde() {
+        assert_eq!(
+            serde_json::to_string(&CacheBackend::Sqlite).unwrap(),
+            "\"sqlite\""
+        );
+        assert_eq!(
+            serde_json::from_str::<CacheBackend>("\"files\"").unwrap(),
+            CacheBackend::Files
+        );
+    }
+
+    #[test]
+    fn test_open_default_store_round_trip() {
+        let temp = TempDir::new().unwrap();
+        let config = DiscoveryConfig::new(
+            vec![temp.path().to_path_buf()],
+            10,
+            vec![],
+            temp.path().join("config").join("cache.json"),
+        );
+        let store = open_store(&config).unwrap();
+        assert!(store.load_all().unwrap().is_none());
+
+        store
+            .save_all(&[project(&temp, "old", 100), project(&temp, "new", 300)])
+            .unwrap();
+
+        let active = store
+            .active_since(SystemTime::UNIX_EPOCH + Duration::from_secs(200))
+            .unwrap();
+        assert_eq!(active.len(), 1);
+        assert_eq!(active[0].name, "new");
+    }
+
+    #[cfg(not(feature = "sqlite"))]
+    #[test]
+    fn test_sqlite_backend_requires_feature() {
+        let temp = TempDir::new().unwrap();
+        let mut config = DiscoveryConfig::new(
+            vec![temp.path().to_path_buf()],
+            10,
+            vec![],
+            temp.path().join("cache.json"),
+        );
+        config.cache_backend = CacheBackend::Sqlite;
+
+        let err = open_store(&config).err().unwrap();
+        assert!(err.to_string().contains("--features sqlite"));
+    }
+}
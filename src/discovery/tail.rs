@@ -0,0 +1,400 @@
+//! Live hook event stream for `hegel-pm tail`
+//!
+//! Reads raw lines from `.hegel/hooks.jsonl` (tool calls) and `.hegel/states.jsonl` (workflow
+//! transitions) as they're appended. Lines are parsed leniently: anything that isn't a bash
+//! command, file edit, or transition is kept as a generic event under its hook name.
+
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+use serde_json::Value;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+/// Hook log files followed by `tail`, in the order their backlog is read
+pub const TAIL_FILES: [&str; 2] = ["hooks.jsonl", "states.jsonl"];
+
+/// Tools whose `file_path` input counts as a file edit
+const EDIT_TOOLS: [&str; 3] = ["Edit", "MultiEdit", "Write"];
+
+/// One parsed line of a hook log
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum HookEvent {
+    Bash {
+        timestamp: Option<String>,
+        command: String,
+    },
+    FileEdit {
+        timestamp: Option<String>,
+        tool: String,
+        file_path: String,
+    },
+    Transition {
+        timestamp: Option<String>,
+        from_node: String,
+        to_node: String,
+        mode: Option<String>,
+    },
+    Other {
+        timestamp: Option<String>,
+        /// Hook event name, with the tool appended when there is one (`PreToolUse Read`)
+        name: String,
+    },
+}
+
+impl HookEvent {
+    /// Parse a JSONL line (None for blank or malformed lines)
+    pub fn parse(line: &str) -> Option<Self> {
+        let value: Value = serde_json::from_str(line.trim()).ok()?;
+        let text = |v: &Value, key: &str| v.get(key).and_then(Value::as_str).map(String::from);
+        let timestamp = text(&value, "timestamp");
+
+        if let (Some(from_node), Some(to_node)) =
+            (text(&value, "from_node"), text(&value, "to_node"))
+        {
+            return Some(HookEvent::Transition {
+                timestamp,
+                from_node,
+                to_node,
+                mode: text(&value, "mode"),
+            });
+        }
+
+        let tool = text(&value, "tool_name");
+        let input = value.get("tool_input");
+        match (tool.as_deref(), input) {
+            (Some("Bash"), Some(input)) => {
+                if let Some(command) = text(input, "command") {
+                    return Some(HookEvent::Bash { timestamp, command });
+                }
+            }
+            (Some(name), Some(input)) if EDIT_TOOLS.contains(&name) => {
+                if let Some(file_path) = text(input, "file_path") {
+                    return Some(HookEvent::FileEdit {
+                        timestamp,
+                        tool: name.to_string(),
+                        file_path,
+                    });
+                }
+            }
+            _ => {}
+        }
+
+        let hook = text(&value, "hook_event_name").unwrap_or_else(|| "event".to_string());
+        let name = match tool {
+            Some(tool) => format!("{} {}", hook, tool),
+            None => hook,
+        };
+        Some(HookEvent::Other { timestamp, name })
+    }
+
+    pub fn timestamp(&self) -> Option<&str> {
+        match self {
+            HookEvent::Bash { timestamp, .. }
+            | HookEvent::FileEdit { timestamp, .. }
+            | HookEvent::Transition { timestamp, .. }
+            | HookEvent::Other { timestamp, .. } => timestamp.as_deref(),
+        }
+    }
+
+    /// Kind name as used by `--filter kind=...`
+    pub fn kind(&self) -> &'static str {
+        match self {
+            HookEvent::Bash { .. } => "bash",
+            HookEvent::FileEdit { .. } => "edit",
+            HookEvent::Transition { .. } => "transition",
+            HookEvent::Other { .. } => "other",
+        }
+    }
+
+    /// Value of a filter field (None when the event has no such field)
+    fn field(&self, field: FilterField) -> Option<&str> {
+        match (field, self) {
+            (FilterField::Kind, _) => Some(self.kind()),
+            (FilterField::Tool, HookEvent::Bash { .. }) => Some("Bash"),
+            (FilterField::Tool, HookEvent::FileEdit { tool, .. }) => Some(tool),
+            (FilterField::Command, HookEvent::Bash { command, .. }) => Some(command),
+            (FilterField::Path, HookEvent::FileEdit { file_path, .. }) => Some(file_path),
+            (FilterField::Node, HookEvent::Transition { to_node, .. }) => Some(to_node),
+            (FilterField::Mode, HookEvent::Transition { mode, .. }) => mode.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+/// Event field a filter tests
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FilterField {
+    Kind,
+    Tool,
+    Command,
+    Path,
+    /// Node a transition moved to
+    Node,
+    Mode,
+}
+
+/// A `--filter` expression: `field=value` (exact) or `field~text` (contains)
+///
+/// Fields: `kind` (bash, edit, transition, other), `tool`, `command`, `path`, `node`, `mode`.
+/// Events without the field never match.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventFilter {
+    field: FilterField,
+    value: String,
+    contains: bool,
+}
+
+impl EventFilter {
+    pub fn parse(expression: &str) -> Result<Self> {
+        let Some(split) = expression.find(['=', '~']) else {
+            bail!(
+                "Invalid filter '{}' (expected field=value or field~text)",
+                expression
+            );
+        };
+        let (name, rest) = expression.split_at(split);
+        let field = match name.trim() {
+            "kind" => FilterField::Kind,
+            "tool" => FilterField::Tool,
+            "command" => FilterField::Command,
+            "path" => FilterField::Path,
+            "node" => FilterField::Node,
+            "mode" => FilterField::Mode,
+            other => bail!(
+                "Unknown filter field '{}' (expected kind, tool, command, path, node, or mode)",
+                other
+            ),
+        };
+
+        Ok(Self {
+            field,
+            value: rest[1..].to_string(),
+            contains: rest.starts_with('~'),
+        })
+    }
+
+    pub fn matches(&self, event: &HookEvent) -> bool {
+        match event.field(self.field) {
+            Some(value) if self.contains => value.contains(&self.value),
+            Some(value) => value == self.value,
+            None => false,
+        }
+    }
+}
+
+/// Whether an event passes every filter (no filters pass everything)
+pub fn matches_filters(event: &HookEvent, filters: &[EventFilter]) -> bool {
+    filters.iter().all(|filter| filter.matches(event))
+}
+
+/// Reads lines appended to a file since the last poll (like `tail -f`)
+///
+/// A partial last line is held back until its newline arrives. If the file shrinks (rotated
+/// or truncated), reading restarts from the beginning.
+#[derive(Debug)]
+pub struct LogFollower {
+    path: PathBuf,
+    offset: u64,
+    /// Bytes after the last newline (kept undecoded: a character may be split across polls)
+    pending: Vec<u8>,
+}
+
+impl LogFollower {
+    /// Follow `path` from its current end (a missing file is followed from the start once created)
+    pub fn from_end(path: &Path) -> Self {
+        let offset = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+        Self {
+            path: path.to_path_buf(),
+            offset,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Follow `path` from the start
+    pub fn from_start(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            offset: 0,
+            pending: Vec::new(),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Complete lines appended since the last poll
+    pub fn poll(&mut self) -> Result<Vec<String>> {
+        let mut file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => {
+                return Err(e).context(format!("Failed to open {}", self.path.display()));
+            }
+        };
+
+        let len = file.metadata()?.len();
+        if len < self.offset {
+            self.offset = 0;
+            self.pending.clear();
+        }
+        if len == self.offset {
+            return Ok(Vec::new());
+        }
+
+        file.seek(SeekFrom::Start(self.offset))?;
+        let read = file
+            .take(len - self.offset)
+            .read_to_end(&mut self.pending)?;
+        self.offset += read as u64;
+
+        let Some(last_newline) = self.pending.iter().rposition(|&b| b == b'\n') else {
+            return Ok(Vec::new());
+        };
+        let rest = self.pending.split_off(last_newline + 1);
+        let complete = std::mem::replace(&mut self.pending, rest);
+
+        // Only complete lines are decoded, so multibyte characters are always whole
+        Ok(complete
+            .split(|&b| b == b'\n')
+            .map(|line| {
+                String::from_utf8_lossy(line)
+                    .trim_end_matches('\r')
+                    .to_string()
+            })
+            .filter(|line| !line.trim().is_empty())
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{self, OpenOptions};
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_hook_events() {
+        let bash = HookEvent::parse(
+            r#"{"hook_event_name":"PostToolUse","tool_name":"Bash","tool_input":{"command":"cargo test"},"timestamp":"2025-01-01T10:00:00Z"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            bash,
+            HookEvent::Bash {
+                timestamp: Some("2025-01-01T10:00:00Z".to_string()),
+                command: "cargo test".to_string(),
+            }
+        );
+
+        let edit = HookEvent::parse(
+            r#"{"tool_name":"Write","tool_input":{"file_path":"src/lib.rs","content":"x"}}"#,
+        )
+        .unwrap();
+        assert_eq!(edit.kind(), "edit");
+
+        let transition = HookEvent::parse(
+            r#"{"timestamp":"2025-01-01T10:05:00Z","from_node":"spec","to_node":"plan","mode":"discovery"}"#,
+        )
+        .unwrap();
+        assert_eq!(transition.kind(), "transition");
+
+        let read = HookEvent::parse(r#"{"hook_event_name":"PreToolUse","tool_name":"Read"}"#);
+        assert_eq!(
+            read,
+            Some(HookEvent::Other {
+                timestamp: None,
+                name: "PreToolUse Read".to_string(),
+            })
+        );
+
+        assert!(HookEvent::parse("not json").is_none());
+    }
+
+    #[test]
+    fn test_event_filters() {
+        let bash = HookEvent::Bash {
+            timestamp: None,
+            command: "cargo test --workspace".to_string(),
+        };
+        let edit = HookEvent::FileEdit {
+            timestamp: None,
+            tool: "Edit".to_string(),
+            file_path: "src/main.rs".to_string(),
+        };
+
+        let kind = EventFilter::parse("kind=bash").unwrap();
+        assert!(kind.matches(&bash));
+        assert!(!kind.matches(&edit));
+
+        let command = EventFilter::parse("command~cargo").unwrap();
+        assert!(command.matches(&bash));
+        // Edits have no command
+        assert!(!command.matches(&edit));
+
+        let filters = vec![
+            EventFilter::parse("kind=edit").unwrap(),
+            EventFilter::parse("path~src/").unwrap(),
+        ];
+        assert!(matches_filters(&edit, &filters));
+        assert!(!matches_filters(&bash, &filters));
+        assert!(matches_filters(&bash, &[]));
+
+        assert!(EventFilter::parse("kind").is_err());
+        assert!(EventFilter::parse("colour=red").is_err());
+    }
+
+    #[test]
+    fn test_log_follower_reads_appended_lines() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("hooks.jsonl");
+        fs::write(&path, "old\n").unwrap();
+
+        let mut follower = LogFollower::from_end(&path);
+        assert!(follower.poll().unwrap().is_empty());
+
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        write!(file, "first\nsec").unwrap();
+        assert_eq!(follower.poll().unwrap(), vec!["first"]);
+
+        // Partial line completes on the next write
+        writeln!(file, "ond").unwrap();
+        assert_eq!(follower.poll().unwrap(), vec!["second"]);
+
+        // Truncation restarts from the top
+        fs::write(&path, "new\n").unwrap();
+        assert_eq!(follower.poll().unwrap(), vec!["new"]);
+    }
+
+    #[test]
+    fn test_log_follower_keeps_characters_split_across_polls() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("hooks.jsonl");
+        let mut follower = LogFollower::from_start(&path);
+
+        // "é" is two bytes; the write boundary falls between them
+        let line = "{\"command\":\"café\"}\n".as_bytes();
+        let split = line.iter().position(|&b| b == 0xC3).unwrap() + 1;
+        fs::write(&path, &line[..split]).unwrap();
+        assert!(follower.poll().unwrap().is_empty());
+
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(&line[split..]).unwrap();
+        assert_eq!(follower.poll().unwrap(), vec![r#"{"command":"café"}"#]);
+    }
+
+    #[test]
+    fn test_log_follower_waits_for_missing_file() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("states.jsonl");
+
+        let mut follower = LogFollower::from_end(&path);
+        assert!(follower.poll().unwrap().is_empty());
+
+        fs::write(&path, "created\n").unwrap();
+        assert_eq!(follower.poll().unwrap(), vec!["created"]);
+    }
+}
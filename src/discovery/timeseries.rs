@@ -0,0 +1,195 @@
+use anyhow::{bail, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use super::{total_tokens, ProjectStatistics};
+
+/// Time bucket size for token timeseries
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Granularity {
+    Hour,
+    Day,
+}
+
+impl Granularity {
+    /// Bucket label for a timestamp (`2025-01-01T13:00:00Z` or `2025-01-01`)
+    fn bucket(&self, time: DateTime<Utc>) -> String {
+        match self {
+            Granularity::Hour => time.format("%Y-%m-%dT%H:00:00Z").to_string(),
+            Granularity::Day => time.format("%Y-%m-%d").to_string(),
+        }
+    }
+}
+
+impl FromStr for Granularity {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "hour" => Ok(Granularity::Hour),
+            "day" => Ok(Granularity::Day),
+            other => bail!("Invalid granularity '{}' (expected hour or day)", other),
+        }
+    }
+}
+
+/// Token consumption within one time bucket
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TimeseriesPoint {
+    pub bucket: String,
+    pub tokens: u64,
+    /// Phases that started in this bucket
+    pub phase_count: usize,
+    /// Running total including this bucket
+    pub cumulative_tokens: u64,
+}
+
+/// Bucket a project's per-phase token usage by phase start time (chronological)
+///
+/// Phases with unparseable start times are skipped.
+pub fn token_timeseries(
+    stats: &ProjectStatistics,
+    granularity: Granularity,
+) -> Vec<TimeseriesPoint> {
+    let samples = stats
+        .phase_metrics
+        .iter()
+        .filter_map(|phase| {
+            let start = DateTime::parse_from_rfc3339(&phase.start_time).ok()?;
+            Some((
+                start.with_timezone(&Utc),
+                total_tokens(&phase.token_metrics),
+            ))
+        })
+        .collect();
+
+    bucket_tokens(samples, granularity)
+}
+
+/// Sum (timestamp, tokens) samples into buckets with a running total
+fn bucket_tokens(
+    samples: Vec<(DateTime<Utc>, u64)>,
+    granularity: Granularity,
+) -> Vec<TimeseriesPoint> {
+    // Bucket labels sort chronologically, so BTreeMap order is time order
+    let mut buckets: BTreeMap<String, (u64, usize)> = BTreeMap::new();
+    for (time, tokens) in samples {
+        let entry = buckets.entry(granularity.bucket(time)).or_default();
+        entry.0 += tokens;
+        entry.1 += 1;
+    }
+
+    let mut cumulative = 0;
+    buckets
+        .into_iter()
+        .map(|(bucket, (tokens, phase_count))| {
+            cumulative += tokens;
+            TimeseriesPoint {
+                bucket,
+                tokens,
+                phase_count,
+                cumulative_tokens: cumulative,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::{phase_json, stats_with};
+    use serde_json::json;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_bucket_by_hour() {
+        let points = bucket_tokens(
+            vec![
+                (at("2025-01-01T13:45:00Z"), 200),
+                (at("2025-01-01T13:05:00Z"), 100),
+                (at("2025-01-01T15:00:00Z"), 50),
+            ],
+            Granularity::Hour,
+        );
+
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[0].bucket, "2025-01-01T13:00:00Z");
+        assert_eq!(points[0].tokens, 300);
+        assert_eq!(points[0].phase_count, 2);
+        assert_eq!(points[1].bucket, "2025-01-01T15:00:00Z");
+        assert_eq!(points[1].cumulative_tokens, 350);
+    }
+
+    #[test]
+    fn test_bucket_by_day() {
+        let points = bucket_tokens(
+            vec![
+                (at("2025-01-02T01:00:00Z"), 10),
+                (at("2025-01-01T23:00:00Z"), 5),
+                (at("2025-01-02T22:00:00+00:00"), 1),
+            ],
+            Granularity::Day,
+        );
+
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[0].bucket, "2025-01-01");
+        assert_eq!(points[1].bucket, "2025-01-02");
+        assert_eq!(points[1].tokens, 11);
+        assert_eq!(points[1].cumulative_tokens, 16);
+    }
+
+    #[test]
+    fn test_granularity_from_str() {
+        assert_eq!("hour".parse::<Granularity>().unwrap(), Granularity::Hour);
+        assert_eq!("day".parse::<Granularity>().unwrap(), Granularity::Day);
+        assert!("week".parse::<Granularity>().is_err());
+    }
+
+    #[test]
+    fn test_token_timeseries_from_phases() {
+        let mut output_heavy = phase_json("code", "2025-01-02T08:30:00+09:00", None, 100);
+        output_heavy["token_metrics"]["total_output_tokens"] = json!(400);
+        output_heavy["token_metrics"]["total_cache_read_tokens"] = json!(1_000);
+        let stats = stats_with(json!({
+            "phase_metrics": [
+                phase_json("spec", "2025-01-01T09:00:00Z", Some("2025-01-01T10:00:00Z"), 200),
+                // 2025-01-01T23:30:00Z: same UTC day as the spec phase
+                output_heavy,
+                phase_json("review", "2025-01-03T12:00:00Z", None, 50),
+                phase_json("broken", "yesterday", None, 9_999),
+            ],
+        }));
+
+        let points = token_timeseries(&stats, Granularity::Day);
+
+        assert_eq!(
+            points,
+            [
+                TimeseriesPoint {
+                    bucket: "2025-01-01".to_string(),
+                    tokens: 1_700,
+                    phase_count: 2,
+                    cumulative_tokens: 1_700,
+                },
+                TimeseriesPoint {
+                    bucket: "2025-01-03".to_string(),
+                    tokens: 50,
+                    phase_count: 1,
+                    cumulative_tokens: 1_750,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_token_timeseries_empty() {
+        let stats = ProjectStatistics::default();
+        assert!(token_timeseries(&stats, Granularity::Day).is_empty());
+    }
+}
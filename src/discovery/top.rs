@@ -0,0 +1,308 @@
+use anyhow::{bail, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
+
+use super::ProjectStatistics;
+
+/// What to rank in `top_activity`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TopKind {
+    /// Bash commands by how often they were run
+    Bash,
+    /// Files by how often they were modified
+    Files,
+}
+
+impl FromStr for TopKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "bash" => Ok(TopKind::Bash),
+            "files" => Ok(TopKind::Files),
+            other => bail!("Invalid kind '{}' (expected bash or files)", other),
+        }
+    }
+}
+
+/// One ranked command or file
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TopEntry {
+    pub value: String,
+    pub count: usize,
+}
+
+/// Most frequent bash commands or most-modified files from a project's hook metrics
+///
+/// Sorted by count (descending), ties by value; at most `limit` entries.
+pub fn top_activity(stats: &ProjectStatistics, kind: TopKind, limit: usize) -> Vec<TopEntry> {
+    let hooks = &stats.hook_metrics;
+    let values: Vec<&str> = match kind {
+        TopKind::Bash => hooks
+            .bash_commands
+            .iter()
+            .map(|c| c.command.trim())
+            .collect(),
+        TopKind::Files => hooks
+            .file_modifications
+            .iter()
+            .map(|m| m.file_path.as_str())
+            .collect(),
+    };
+
+    rank(values, limit)
+}
+
+/// Modifications of one file within a phase
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FileActivity {
+    pub file_path: String,
+    pub count: usize,
+    /// RFC 3339 timestamp of the first modification (None if no event had one)
+    pub first_modified: Option<String>,
+    /// RFC 3339 timestamp of the last modification
+    pub last_modified: Option<String>,
+}
+
+/// Files modified during the phase(s) named `phase_name`, most-modified first
+///
+/// Repeated phases (e.g. several `code` phases) are merged. Returns `None` if the
+/// project has no phase with that name.
+pub fn phase_files(stats: &ProjectStatistics, phase_name: &str) -> Option<Vec<FileActivity>> {
+    let mut phases = stats
+        .phase_metrics
+        .iter()
+        .filter(|phase| phase.phase_name == phase_name)
+        .peekable();
+    phases.peek()?;
+
+    let modifications = phases.flat_map(|phase| {
+        phase
+            .file_modifications
+            .iter()
+            .map(|m| (m.file_path.as_str(), m.timestamp.as_deref()))
+    });
+    Some(group_files(modifications))
+}
+
+/// Group (path, timestamp) pairs per file (unparseable timestamps only count)
+fn group_files<'a>(
+    modifications: impl Iterator<Item = (&'a str, Option<&'a str>)>,
+) -> Vec<FileActivity> {
+    /// Modification count, then earliest and latest (parsed time, original timestamp)
+    type Seen<'a> = (
+        usize,
+        Option<(DateTime<Utc>, &'a str)>,
+        Option<(DateTime<Utc>, &'a str)>,
+    );
+
+    let mut per_file: BTreeMap<&str, Seen> = BTreeMap::new();
+    for (path, timestamp) in modifications.filter(|(path, _)| !path.is_empty()) {
+        let (count, first, last) = per_file.entry(path).or_default();
+        *count += 1;
+        let Some(time) = timestamp.and_then(|ts| Some((parse_time(ts)?, ts))) else {
+            continue;
+        };
+        if first.is_none_or(|(earliest, _)| time.0 < earliest) {
+            *first = Some(time);
+        }
+        if last.is_none_or(|(latest, _)| time.0 > latest) {
+            *last = Some(time);
+        }
+    }
+
+    // BTreeMap yields paths in order, so the stable sort keeps ties alphabetical
+    let mut files: Vec<FileActivity> = per_file
+        .into_iter()
+        .map(|(path, (count, first, last))| FileActivity {
+            file_path: path.to_string(),
+            count,
+            first_modified: first.map(|(_, ts)| ts.to_string()),
+            last_modified: last.map(|(_, ts)| ts.to_string()),
+        })
+        .collect();
+    files.sort_by(|a, b| b.count.cmp(&a.count));
+    files
+}
+
+/// Parse an RFC 3339 hook timestamp (any offset, optional fractional seconds)
+fn parse_time(timestamp: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .ok()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+/// Count occurrences and keep the `limit` most frequent
+fn rank(values: Vec<&str>, limit: usize) -> Vec<TopEntry> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for value in values.into_iter().filter(|v| !v.is_empty()) {
+        *counts.entry(value).or_default() += 1;
+    }
+
+    let mut entries: Vec<TopEntry> = counts
+        .into_iter()
+        .map(|(value, count)| TopEntry {
+            value: value.to_string(),
+            count,
+        })
+        .collect();
+    entries.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+    entries.truncate(limit);
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::{bash_json, edit_json, phase_json, stats_with};
+    use serde_json::json;
+
+    #[test]
+    fn test_rank_orders_by_count_then_value() {
+        let entries = rank(
+            vec![
+                "cargo test",
+                "ls",
+                "cargo test",
+                "git status",
+                "ls",
+                "",
+                "cargo test",
+            ],
+            2,
+        );
+
+        assert_eq!(
+            entries,
+            vec![
+                TopEntry {
+                    value: "cargo test".to_string(),
+                    count: 3
+                },
+                TopEntry {
+                    value: "ls".to_string(),
+                    count: 2
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_top_kind_from_str() {
+        assert_eq!("bash".parse::<TopKind>().unwrap(), TopKind::Bash);
+        assert_eq!("files".parse::<TopKind>().unwrap(), TopKind::Files);
+        assert!("phases".parse::<TopKind>().is_err());
+    }
+
+    #[test]
+    fn test_group_files() {
+        let files = group_files(
+            vec![
+                ("src/lib.rs", Some("2025-01-01T10:05:00Z")),
+                ("src/main.rs", None),
+                ("src/lib.rs", Some("2025-01-01T10:01:00Z")),
+                // 09:59 UTC: earliest, though it sorts last as text
+                ("src/lib.rs", Some("2025-01-01T11:59:00+02:00")),
+                ("src/lib.rs", Some("garbled")),
+                ("", Some("2025-01-01T10:02:00Z")),
+                ("README.md", Some("2025-01-01T10:03:00Z")),
+                ("src/lib.rs", None),
+            ]
+            .into_iter(),
+        );
+
+        assert_eq!(files.len(), 3);
+        assert_eq!(
+            files[0],
+            FileActivity {
+                file_path: "src/lib.rs".to_string(),
+                count: 5,
+                first_modified: Some("2025-01-01T11:59:00+02:00".to_string()),
+                last_modified: Some("2025-01-01T10:05:00Z".to_string()),
+            }
+        );
+        assert_eq!(files[1].file_path, "README.md");
+        assert_eq!(files[2].file_path, "src/main.rs");
+        assert_eq!(files[2].first_modified, None);
+    }
+
+    #[test]
+    fn test_top_activity_from_hook_events() {
+        let stats = stats_with(json!({
+            "hook_metrics": {
+                "bash_commands": [
+                    bash_json("cargo test", None),
+                    bash_json("  cargo test\n", None),
+                    bash_json("git status", None),
+                ],
+                "file_modifications": [
+                    edit_json("src/lib.rs", None),
+                    edit_json("README.md", None),
+                    edit_json("src/lib.rs", None),
+                ],
+            },
+        }));
+
+        let commands = top_activity(&stats, TopKind::Bash, 10);
+        assert_eq!(commands.len(), 2);
+        assert_eq!(commands[0].value, "cargo test");
+        assert_eq!(commands[0].count, 2);
+        assert_eq!(commands[1].value, "git status");
+
+        let files = top_activity(&stats, TopKind::Files, 1);
+        assert_eq!(
+            files,
+            [TopEntry {
+                value: "src/lib.rs".to_string(),
+                count: 2
+            }]
+        );
+    }
+
+    #[test]
+    fn test_phase_files_merges_repeated_phases() {
+        let mut first_code = phase_json("code", "2025-01-01T10:00:00Z", None, 0);
+        first_code["file_modifications"] = json!([
+            edit_json("src/lib.rs", Some("2025-01-01T10:05:00Z")),
+            edit_json("src/main.rs", Some("2025-01-01T10:06:00Z")),
+        ]);
+        let mut review = phase_json("review", "2025-01-01T11:00:00Z", None, 0);
+        review["file_modifications"] = json!([edit_json("README.md", None)]);
+        let mut second_code = phase_json("code", "2025-01-01T12:00:00Z", None, 0);
+        second_code["file_modifications"] =
+            json!([edit_json("src/lib.rs", Some("2025-01-01T12:30:00Z"))]);
+        let stats = stats_with(json!({
+            "phase_metrics": [first_code, review, second_code],
+        }));
+
+        let files = phase_files(&stats, "code").unwrap();
+
+        assert_eq!(files.len(), 2);
+        assert_eq!(
+            files[0],
+            FileActivity {
+                file_path: "src/lib.rs".to_string(),
+                count: 2,
+                first_modified: Some("2025-01-01T10:05:00Z".to_string()),
+                last_modified: Some("2025-01-01T12:30:00Z".to_string()),
+            }
+        );
+        assert_eq!(files[1].file_path, "src/main.rs");
+        assert_eq!(phase_files(&stats, "review").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_phase_files_unknown_phase() {
+        let stats = ProjectStatistics::default();
+        assert!(phase_files(&stats, "code").is_none());
+    }
+
+    #[test]
+    fn test_top_activity_empty() {
+        let stats = ProjectStatistics::default();
+        assert!(top_activity(&stats, TopKind::Files, 10).is_empty());
+    }
+}
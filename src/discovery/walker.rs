@@ -1,33 +1,106 @@
-use anyhow::Result;
-use std::path::PathBuf;
+use anyhow::{bail, Context, Result};
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 use walkdir::WalkDir;
 
+use crate::warn;
+
+/// Directory mtimes file under the cache directory (read and written by fast rescans)
+const SNAPSHOT_FILE: &str = "dir_mtimes.json";
+
+/// Compiled `exclusions` from the config
+///
+/// Each entry is one of:
+/// - a directory name (`node_modules`): skips every directory with that name
+/// - an absolute path (`/home/dev/Code/huge-tree`): skips that directory and everything in it
+/// - a glob (`*-archive`, `**/examples/*`): matched against the directory name when it has no
+///   `/`, otherwise against the full path (`*` stays within one component, `**` spans several)
+#[derive(Debug, Clone)]
+pub struct Exclusions {
+    names: HashSet<String>,
+    prefixes: Vec<PathBuf>,
+    name_globs: GlobSet,
+    path_globs: GlobSet,
+}
+
+impl Exclusions {
+    /// Compile exclusion entries (invalid globs and relative paths are errors)
+    pub fn new(patterns: &[String]) -> Result<Self> {
+        let mut names = HashSet::new();
+        let mut prefixes = Vec::new();
+        let mut name_globs = GlobSetBuilder::new();
+        let mut path_globs = GlobSetBuilder::new();
+
+        for pattern in patterns {
+            if pattern.contains(['*', '?', '[', '{']) {
+                let glob = GlobBuilder::new(pattern)
+                    .literal_separator(true)
+                    .build()
+                    .context(format!("Invalid exclusion glob: {}", pattern))?;
+                if pattern.contains('/') {
+                    path_globs.add(glob);
+                } else {
+                    name_globs.add(glob);
+                }
+            } else if Path::new(pattern).is_absolute() {
+                prefixes.push(PathBuf::from(pattern));
+            } else if pattern.contains('/') {
+                bail!(
+                    "Exclusion '{}' is a relative path; use an absolute path or a glob like '**/{}'",
+                    pattern,
+                    pattern
+                );
+            } else {
+                names.insert(pattern.clone());
+            }
+        }
+
+        Ok(Self {
+            names,
+            prefixes,
+            name_globs: name_globs.build()?,
+            path_globs: path_globs.build()?,
+        })
+    }
+
+    /// Whether the directory at `path` (and so everything in it) is skipped
+    pub fn is_excluded(&self, path: &Path) -> bool {
+        if let Some(name) = path.file_name() {
+            if name.to_str().is_some_and(|name| self.names.contains(name))
+                || self.name_globs.is_match(name)
+            {
+                return true;
+            }
+        }
+        self.prefixes.iter().any(|prefix| path.starts_with(prefix))
+            || self.path_globs.is_match(path)
+    }
+}
+
 /// Find all .hegel directories in the given root, respecting exclusions and max depth
 pub fn find_hegel_directories(
     root: &PathBuf,
     max_depth: usize,
     exclusions: &[String],
 ) -> Result<Vec<PathBuf>> {
+    let exclusions = Exclusions::new(exclusions)?;
     let mut found = Vec::new();
 
     for entry in WalkDir::new(root)
         .max_depth(max_depth)
         .follow_links(false)
         .into_iter()
-        .filter_entry(|e| {
-            // Skip excluded directories
-            if let Some(name) = e.file_name().to_str() {
-                !exclusions.contains(&name.to_string())
-            } else {
-                true
-            }
-        })
+        .filter_entry(|e| !exclusions.is_excluded(e.path()))
     {
         let entry = match entry {
             Ok(e) => e,
             Err(err) => {
                 // Log error but continue scanning
-                eprintln!("Warning: skipping entry: {}", err);
+                warn!("skipping entry: {}", err);
                 continue;
             }
         };
@@ -44,6 +117,174 @@ pub fn find_hegel_directories(
     Ok(found)
 }
 
+/// What a fast rescan remembers about one directory
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct DirRecord {
+    mtime: SystemTime,
+    /// Child directories (`.hegel` and symlinks left out; exclusions are applied when visiting,
+    /// so a recorded listing stays valid when they change)
+    subdirs: Vec<String>,
+    /// Whether the directory contains `.hegel` (is a project root)
+    has_hegel: bool,
+}
+
+/// Directory mtimes from the last fast rescan, keyed by path
+///
+/// A directory's mtime changes when entries are added, removed, or renamed directly inside it,
+/// so an unchanged mtime means its child list is unchanged and `read_dir` can be skipped. Child
+/// directories are still visited (a deeper change doesn't touch ancestors' mtimes), but each
+/// costs a `stat` instead of a directory listing.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DirSnapshot {
+    dirs: HashMap<PathBuf, DirRecord>,
+}
+
+impl DirSnapshot {
+    /// Load the snapshot from `cache_dir` (missing or unreadable snapshots start empty)
+    pub fn load(cache_dir: &Path) -> Self {
+        fs::read(cache_dir.join(SNAPSHOT_FILE))
+            .ok()
+            .and_then(|contents| serde_json::from_slice(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    /// Save the snapshot to `cache_dir` (atomic write)
+    pub fn save(&self, cache_dir: &Path) -> Result<()> {
+        fs::create_dir_all(cache_dir).context(format!(
+            "Failed to create cache directory: {}",
+            cache_dir.display()
+        ))?;
+
+        let path = cache_dir.join(SNAPSHOT_FILE);
+        let temp_path = cache_dir.join(format!("{}.tmp", SNAPSHOT_FILE));
+        let encoded = serde_json::to_vec(self).context("Failed to serialize directory mtimes")?;
+        fs::write(&temp_path, encoded).context(format!(
+            "Failed to write temp snapshot file: {}",
+            temp_path.display()
+        ))?;
+        fs::rename(&temp_path, &path).context(format!(
+            "Failed to rename snapshot file: {}",
+            path.display()
+        ))?;
+
+        Ok(())
+    }
+
+    /// Number of directories recorded
+    pub fn len(&self) -> usize {
+        self.dirs.len()
+    }
+
+    /// Whether no directories are recorded
+    pub fn is_empty(&self) -> bool {
+        self.dirs.is_empty()
+    }
+}
+
+/// Like `find_hegel_directories`, but reuses directory listings from `previous` where mtimes
+/// are unchanged, recording every visited directory in `next`
+///
+/// Mtime resolution is filesystem-dependent: a change landing in the same tick as the recorded
+/// scan can be missed until the directory changes again (a full scan always sees it).
+pub fn find_hegel_directories_fast(
+    root: &Path,
+    max_depth: usize,
+    exclusions: &[String],
+    previous: &DirSnapshot,
+    next: &mut DirSnapshot,
+) -> Result<Vec<PathBuf>> {
+    let exclusions = Exclusions::new(exclusions)?;
+    let mut found = Vec::new();
+    if !exclusions.is_excluded(root) {
+        visit(root, 0, max_depth, &exclusions, previous, next, &mut found);
+    }
+    Ok(found)
+}
+
+/// Record one directory and recurse into its subdirectories (depth counted as in WalkDir)
+fn visit(
+    dir: &Path,
+    depth: usize,
+    max_depth: usize,
+    exclusions: &Exclusions,
+    previous: &DirSnapshot,
+    next: &mut DirSnapshot,
+    found: &mut Vec<PathBuf>,
+) {
+    // `.hegel` and subdirectories are children: nothing to see past max_depth
+    if depth >= max_depth {
+        return;
+    }
+
+    let mtime = match fs::metadata(dir).and_then(|m| m.modified()) {
+        Ok(mtime) => mtime,
+        Err(err) => {
+            warn!("skipping entry: {}: {}", dir.display(), err);
+            return;
+        }
+    };
+
+    let record = match previous.dirs.get(dir) {
+        Some(record) if record.mtime == mtime => record.clone(),
+        _ => match list_dir(dir, mtime) {
+            Ok(record) => record,
+            Err(err) => {
+                warn!("skipping entry: {}: {}", dir.display(), err);
+                return;
+            }
+        },
+    };
+
+    if record.has_hegel {
+        found.push(dir.to_path_buf());
+    }
+    for subdir in &record.subdirs {
+        let child = dir.join(subdir);
+        if exclusions.is_excluded(&child) {
+            continue;
+        }
+        visit(
+            &child,
+            depth + 1,
+            max_depth,
+            exclusions,
+            previous,
+            next,
+            found,
+        );
+    }
+    next.dirs.insert(dir.to_path_buf(), record);
+}
+
+/// Read a directory's children into a fresh record
+fn list_dir(dir: &Path, mtime: SystemTime) -> Result<DirRecord> {
+    let mut subdirs = Vec::new();
+    let mut has_hegel = false;
+
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        // file_type() doesn't follow symlinks, so linked directories are skipped
+        if !entry.file_type()?.is_dir() {
+            continue;
+        }
+        let Some(name) = entry.file_name().to_str().map(str::to_string) else {
+            continue;
+        };
+        if name == ".hegel" {
+            has_hegel = true;
+        } else {
+            subdirs.push(name);
+        }
+    }
+    subdirs.sort();
+
+    Ok(DirRecord {
+        mtime,
+        subdirs,
+        has_hegel,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -136,6 +377,128 @@ mod tests {
         assert_eq!(found[0], project);
     }
 
+    #[test]
+    fn test_fast_scan_matches_full_scan() {
+        let temp = create_test_workspace();
+        let root = temp.path().to_path_buf();
+        let exclusions = vec!["node_modules".to_string()];
+
+        let mut full = find_hegel_directories(&root, 10, &exclusions).unwrap();
+        let mut snapshot = DirSnapshot::default();
+        let mut fast = find_hegel_directories_fast(
+            &root,
+            10,
+            &exclusions,
+            &DirSnapshot::default(),
+            &mut snapshot,
+        )
+        .unwrap();
+        full.sort();
+        fast.sort();
+
+        assert_eq!(fast, full);
+        assert!(!snapshot.is_empty());
+    }
+
+    #[test]
+    fn test_fast_scan_reuses_unchanged_directories() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path().join("code");
+        fs::create_dir_all(root.join("existing").join(".hegel")).unwrap();
+
+        let mut first = DirSnapshot::default();
+        find_hegel_directories_fast(&root, 10, &[], &DirSnapshot::default(), &mut first).unwrap();
+
+        // Unchanged mtime: the recorded listing is trusted (a stale record proves it)
+        let mut stale = first.clone();
+        stale
+            .dirs
+            .get_mut(&root.join("existing"))
+            .unwrap()
+            .has_hegel = false;
+        let mut next = DirSnapshot::default();
+        let found = find_hegel_directories_fast(&root, 10, &[], &stale, &mut next).unwrap();
+        assert!(found.is_empty());
+
+        // A new project changes its parent's mtime, so that listing is re-read (the recorded
+        // mtime is backdated: back-to-back writes can land in the same coarse timestamp tick)
+        fs::create_dir_all(root.join("new").join(".hegel")).unwrap();
+        first.dirs.get_mut(&root).unwrap().mtime = SystemTime::UNIX_EPOCH;
+        let mut next = DirSnapshot::default();
+        let found = find_hegel_directories_fast(&root, 10, &[], &first, &mut next).unwrap();
+        assert_eq!(found, vec![root.join("existing"), root.join("new")]);
+
+        next.save(temp.path()).unwrap();
+        assert_eq!(DirSnapshot::load(temp.path()).len(), next.len());
+    }
+
+    #[test]
+    fn test_exclusion_patterns() {
+        let exclusions = Exclusions::new(&[
+            "node_modules".to_string(),
+            "*-archive".to_string(),
+            "**/examples/*".to_string(),
+            "/home/dev/Code/huge-tree".to_string(),
+        ])
+        .unwrap();
+        let excluded = |path: &str| exclusions.is_excluded(Path::new(path));
+
+        assert!(excluded("/home/dev/Code/web/node_modules"));
+        assert!(excluded("/home/dev/Code/2023-archive"));
+        assert!(excluded("/home/dev/Code/lib/examples/demo"));
+        assert!(excluded("/home/dev/Code/huge-tree"));
+        assert!(excluded("/home/dev/Code/huge-tree/vendor/pkg"));
+
+        assert!(!excluded("/home/dev/Code/node_modules_docs"));
+        assert!(!excluded("/home/dev/Code/archive-tools"));
+        // `*` doesn't cross directories: only direct children of examples/ match
+        assert!(!excluded("/home/dev/Code/lib/examples"));
+        assert!(!excluded("/home/dev/Code/lib/examples/demo/nested"));
+        assert!(!excluded("/home/dev/Code/huge-tree-2"));
+
+        assert!(Exclusions::new(&["vendor/big".to_string()]).is_err());
+        assert!(Exclusions::new(&["[unclosed".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_glob_and_path_exclusions_in_scans() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path().to_path_buf();
+        for project in [
+            "keep",
+            "old-archive/proj",
+            "lib/examples/demo",
+            "big/inner/proj",
+        ] {
+            fs::create_dir_all(root.join(project).join(".hegel")).unwrap();
+        }
+
+        let exclusions = vec![
+            "*-archive".to_string(),
+            "**/examples/*".to_string(),
+            root.join("big").display().to_string(),
+        ];
+        let found = find_hegel_directories(&root, 10, &exclusions).unwrap();
+        assert_eq!(found, vec![root.join("keep")]);
+
+        let mut snapshot = DirSnapshot::default();
+        let fast = find_hegel_directories_fast(
+            &root,
+            10,
+            &exclusions,
+            &DirSnapshot::default(),
+            &mut snapshot,
+        )
+        .unwrap();
+        assert_eq!(fast, vec![root.join("keep")]);
+
+        // Recorded listings ignore exclusions, so dropping one takes effect on a fast rescan
+        let mut next = DirSnapshot::default();
+        let mut fast = find_hegel_directories_fast(&root, 10, &[], &snapshot, &mut next).unwrap();
+        fast.sort();
+        assert_eq!(fast.len(), 4);
+    }
+
     #[test]
     fn test_multiple_exclusions() {
         let temp = TempDir::new().unwrap();
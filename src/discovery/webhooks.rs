@@ -0,0 +1,303 @@
+//! Outbound webhooks fired by refreshes (workflow completed, new git commits, budget thresholds)
+//!
+//! Payloads carry a Slack-compatible `text` field plus structured fields for other receivers.
+//! Project events are derived from a `RefreshDiff`, budget events from budget statuses before
+//! and after the refreshes, so only refreshes that write the cache notify.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::{BudgetStatus, RefreshDiff};
+
+/// Workflow node hegel moves to when a workflow finishes
+const DONE_NODE: &str = "done";
+
+/// What a refresh can notify about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEvent {
+    /// The workflow reached its `done` node
+    WorkflowCompleted,
+    /// HEAD moved to a different commit
+    NewCommits,
+    /// A monthly budget crossed 80% or 100%
+    BudgetThreshold,
+}
+
+/// A user-configured webhook (`"webhooks": [{"url": "...", "events": ["workflow_completed"]}]`)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WebhookConfig {
+    pub url: String,
+    /// Events to send (empty = all)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<WebhookEvent>,
+}
+
+impl WebhookConfig {
+    fn wants(&self, event: WebhookEvent) -> bool {
+        self.events.is_empty() || self.events.contains(&event)
+    }
+}
+
+/// JSON body POSTed to a webhook
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WebhookPayload {
+    /// Human-readable message (what Slack incoming webhooks display)
+    pub text: String,
+    pub event: WebhookEvent,
+    pub project: String,
+    pub workflow_node: Option<String>,
+    pub git_head: Option<String>,
+    /// Percent of the budget used (budget events only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget_percent: Option<f64>,
+}
+
+impl WebhookPayload {
+    /// Payload with only the message, event, and project set
+    fn new(event: WebhookEvent, project: &str, text: String) -> Self {
+        Self {
+            text,
+            event,
+            project: project.to_string(),
+            workflow_node: None,
+            git_head: None,
+            budget_percent: None,
+        }
+    }
+}
+
+/// Events a refresh produced
+///
+/// Projects refreshed for the first time (or cached before HEAD was recorded) never report
+/// new commits, so upgrading doesn't notify for every repository at once.
+pub fn webhook_events(diff: &RefreshDiff) -> Vec<WebhookEvent> {
+    let Some(before) = &diff.before else {
+        return Vec::new();
+    };
+    let after = &diff.after;
+    let mut events = Vec::new();
+
+    if after.workflow_node.as_deref() == Some(DONE_NODE)
+        && before.workflow_node.as_deref() != Some(DONE_NODE)
+    {
+        events.push(WebhookEvent::WorkflowCompleted);
+    }
+    if before.git_head.is_some() && after.git_head.is_some() && before.git_head != after.git_head {
+        events.push(WebhookEvent::NewCommits);
+    }
+
+    events
+}
+
+/// Payloads to send for a refresh, paired with the URL each goes to
+pub fn webhook_notifications(
+    webhooks: &[WebhookConfig],
+    diff: &RefreshDiff,
+) -> Vec<(String, WebhookPayload)> {
+    let events = webhook_events(diff);
+
+    webhooks
+        .iter()
+        .flat_map(|webhook| {
+            events
+                .iter()
+                .filter(|&&event| webhook.wants(event))
+                .filter_map(|&event| Some((webhook.url.clone(), payload(diff, event)?)))
+        })
+        .collect()
+}
+
+/// Payloads for budgets that crossed a threshold between two sets of statuses, with URLs
+///
+/// A budget notifies once per threshold: only when `after` reaches a higher threshold than
+/// the same budget did in `before`. The project is "all projects" for the global budget.
+pub fn budget_notifications(
+    webhooks: &[WebhookConfig],
+    before: &[BudgetStatus],
+    after: &[BudgetStatus],
+) -> Vec<(String, WebhookPayload)> {
+    let crossed = after.iter().filter_map(|status| {
+        let reached = status.threshold_reached()?;
+        let previous = before
+            .iter()
+            .find(|b| b.project_name == status.project_name && b.month == status.month)
+            .and_then(BudgetStatus::threshold_reached);
+        (previous < Some(reached)).then_some((status, reached))
+    });
+
+    let payloads: Vec<WebhookPayload> = crossed
+        .map(|(status, threshold)| {
+            let text = format!(
+                "{} {}: {}% of the {} monthly budget used",
+                if threshold >= 100 { "✗" } else { "⚠" },
+                status.label(),
+                status.percent_used.floor(),
+                status.month
+            );
+            WebhookPayload {
+                budget_percent: Some(status.percent_used),
+                ..WebhookPayload::new(WebhookEvent::BudgetThreshold, status.label(), text)
+            }
+        })
+        .collect();
+
+    webhooks
+        .iter()
+        .filter(|webhook| webhook.wants(WebhookEvent::BudgetThreshold))
+        .flat_map(|webhook| {
+            payloads
+                .iter()
+                .map(|payload| (webhook.url.clone(), payload.clone()))
+        })
+        .collect()
+}
+
+/// POST a payload as JSON (non-2xx responses are errors)
+pub fn post_webhook(url: &str, payload: &WebhookPayload) -> Result<()> {
+    ureq::post(url)
+        .timeout(std::time::Duration::from_secs(10))
+        .send_json(payload)
+        .context(format!("Failed to POST webhook to {}", url))?;
+    Ok(())
+}
+
+/// Payload for a project event of `diff` (None for events that don't come from a diff)
+fn payload(diff: &RefreshDiff, event: WebhookEvent) -> Option<WebhookPayload> {
+    let text = match event {
+        WebhookEvent::WorkflowCompleted => format!("✓ {}: workflow completed", diff.name),
+        WebhookEvent::NewCommits => format!(
+            "{}: new commits (HEAD {})",
+            diff.name,
+            diff.after
+                .git_head
+                .as_deref()
+                .map(|head| head.chars().take(7).collect::<String>())
+                .unwrap_or_default()
+        ),
+        // Budget events are built from budget statuses (`budget_notifications`)
+        WebhookEvent::BudgetThreshold => return None,
+    };
+
+    Some(WebhookPayload {
+        workflow_node: diff.after.workflow_node.clone(),
+        git_head: diff.after.git_head.clone(),
+        ..WebhookPayload::new(event, &diff.name, text)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::RefreshSnapshot;
+    use std::time::SystemTime;
+
+    fn snapshot(node: Option<&str>, head: Option<&str>) -> RefreshSnapshot {
+        RefreshSnapshot {
+            last_activity: SystemTime::UNIX_EPOCH,
+            workflow_node: node.map(String::from),
+            phase_count: 0,
+            error: None,
+            git: None,
+            git_head: head.map(String::from),
+        }
+    }
+
+    fn diff(before: Option<RefreshSnapshot>, after: RefreshSnapshot) -> RefreshDiff {
+        RefreshDiff {
+            name: "alpha".to_string(),
+            before,
+            after,
+        }
+    }
+
+    #[test]
+    fn test_webhook_events() {
+        let completed = diff(
+            Some(snapshot(Some("review"), Some("aaa"))),
+            snapshot(Some("done"), Some("bbb")),
+        );
+        assert_eq!(
+            webhook_events(&completed),
+            vec![WebhookEvent::WorkflowCompleted, WebhookEvent::NewCommits]
+        );
+
+        // Already done, or HEAD not known before: nothing new
+        let unchanged = diff(
+            Some(snapshot(Some("done"), None)),
+            snapshot(Some("done"), Some("bbb")),
+        );
+        assert!(webhook_events(&unchanged).is_empty());
+
+        // First time cached
+        let new = diff(None, snapshot(Some("done"), Some("bbb")));
+        assert!(webhook_events(&new).is_empty());
+    }
+
+    #[test]
+    fn test_webhook_notifications_filter_by_event() {
+        let webhooks: Vec<WebhookConfig> = serde_json::from_str(
+            r#"[
+                {"url": "https://hooks.example/all"},
+                {"url": "https://hooks.example/done", "events": ["workflow_completed"]}
+            ]"#,
+        )
+        .unwrap();
+        let commits = diff(
+            Some(snapshot(Some("code"), Some("aaa"))),
+            snapshot(Some("code"), Some("bbbbbbbbbb")),
+        );
+
+        let notifications = webhook_notifications(&webhooks, &commits);
+        assert_eq!(notifications.len(), 1);
+        let (url, payload) = &notifications[0];
+        assert_eq!(url, "https://hooks.example/all");
+        assert_eq!(payload.event, WebhookEvent::NewCommits);
+        assert_eq!(payload.text, "alpha: new commits (HEAD bbbbbbb)");
+    }
+
+    #[test]
+    fn test_budget_notifications() {
+        let webhooks: Vec<WebhookConfig> = serde_json::from_str(
+            r#"[
+                {"url": "https://hooks.example/budget", "events": ["budget_threshold"]},
+                {"url": "https://hooks.example/done", "events": ["workflow_completed"]}
+            ]"#,
+        )
+        .unwrap();
+        let status = |name: Option<&str>, percent_used: f64| BudgetStatus {
+            project_name: name.map(String::from),
+            month: "2025-01".to_string(),
+            budget: Default::default(),
+            used_tokens: 0,
+            estimated_cost: 0.0,
+            percent_used,
+        };
+        let before = [status(None, 70.0), status(Some("alpha"), 85.0)];
+        let after = [
+            status(None, 81.5),
+            status(Some("alpha"), 95.0),
+            status(Some("beta"), 100.0),
+        ];
+
+        let notifications = budget_notifications(&webhooks, &before, &after);
+        let sent: Vec<(&str, &str)> = notifications
+            .iter()
+            .map(|(url, payload)| (url.as_str(), payload.text.as_str()))
+            .collect();
+        assert_eq!(
+            sent,
+            [
+                (
+                    "https://hooks.example/budget",
+                    "⚠ all projects: 81% of the 2025-01 monthly budget used"
+                ),
+                (
+                    "https://hooks.example/budget",
+                    "✗ beta: 100% of the 2025-01 monthly budget used"
+                ),
+            ]
+        );
+        assert_eq!(notifications[1].1.budget_percent, Some(100.0));
+    }
+}
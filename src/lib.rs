@@ -1,9 +1,12 @@
-// Debug utilities (requires explicit import: use hegel_pm::debug;)
+// Diagnostic logging: debug!/warn! macros and --log-* setup (requires explicit import: use hegel_pm::debug;)
 pub mod debug;
 
 // Core library: project discovery
 pub mod discovery;
 
+// Stable facade over discovery for embedding tools (semver-covered)
+pub mod api;
+
 // CLI commands
 pub mod cli;
 
@@ -1,79 +1,271 @@
 use clap::Parser;
-use hegel_pm::cli::{Args, Command};
+use hegel_pm::cli::discover::{remember_all_layout, ListFilter};
+use hegel_pm::cli::open::OpenTarget;
+use hegel_pm::cli::pick::pick_project;
+use hegel_pm::cli::refresh::RefreshMode;
+use hegel_pm::cli::resolve::resolve_project_name;
+use hegel_pm::cli::{Args, Command, DiscoverCommand, HegelPmError, NoteCommand, ProgressMode};
 use hegel_pm::discovery::{
-    refresh_all_projects, refresh_project, remove_from_cache, DiscoveryConfig, DiscoveryEngine,
+    remove_from_cache, set_archived, DiscoveryConfig, DiscoveryEngine, JsonProgress, NoProgress,
+    ProgressSink, SnapshotStore,
 };
+use std::sync::Arc;
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
+fn main() {
     let args = Args::parse();
 
-    // Initialize discovery engine with default config
-    let config = DiscoveryConfig::default();
+    if let Err(e) = run(args) {
+        eprintln!("✗ {}", e);
+        std::process::exit(e.exit_code());
+    }
+}
+
+/// Create the discovery engine, reporting validation failures as config errors
+fn engine(
+    config: DiscoveryConfig,
+    progress: &Arc<dyn ProgressSink>,
+) -> Result<DiscoveryEngine, HegelPmError> {
+    Ok(DiscoveryEngine::new(config)
+        .map_err(HegelPmError::Config)?
+        .with_progress(progress.clone()))
+}
+
+/// Open the snapshot store for the configured cache backend, reporting failures as config errors
+fn snapshot_store(config: &DiscoveryConfig) -> Result<SnapshotStore, HegelPmError> {
+    SnapshotStore::for_config(config).map_err(HegelPmError::Config)
+}
+
+/// Resolve a project-name argument to a cached project's name (see `resolve_project_name`)
+fn resolve(
+    name: &str,
+    config: &DiscoveryConfig,
+    progress: &Arc<dyn ProgressSink>,
+    exact: bool,
+) -> Result<String, HegelPmError> {
+    resolve_project_name(&engine(config.clone(), progress)?, name, exact)
+}
+
+fn run(args: Args) -> Result<(), HegelPmError> {
+    let exact = args.exact;
+
+    // Diagnostics (debug!/warn!) go to stderr or --log-file, never stdout
+    hegel_pm::debug::init(args.log_level, args.log_format, args.log_file.as_deref())
+        .map_err(|e| HegelPmError::InvalidArgument(format!("{:#}", e)))?;
+
+    // Doctor reads the config file itself, so it can diagnose one that fails to parse
+    if let Some(Command::Discover {
+        subcommand: DiscoverCommand::Doctor,
+        json,
+        ..
+    }) = &args.command
+    {
+        return hegel_pm::cli::discover::doctor::run_config_file(
+            &DiscoveryConfig::default_config_file(),
+            *json,
+        );
+    }
+
+    // Load config file (~/.config/hegel-pm/config.json), falling back to defaults
+    let config = DiscoveryConfig::load_or_default().map_err(HegelPmError::Config)?;
+
+    let progress: Arc<dyn ProgressSink> = match args.progress {
+        ProgressMode::None => Arc::new(NoProgress),
+        ProgressMode::Json => Arc::new(JsonProgress),
+    };
 
     match args.command {
         Some(Command::Discover {
-            subcommand,
+            mut subcommand,
             json,
             no_cache,
+            include_archived,
+            status,
+            roots,
+            max_depth,
         }) => {
+            let mut config = config;
+            if let DiscoverCommand::All {
+                sort_by, columns, ..
+            } = &subcommand
+            {
+                // Remember the table layout in the config file for the next run
+                if remember_all_layout(&mut config, sort_by.as_deref(), columns.as_deref()) {
+                    config
+                        .save_all_layout(&DiscoveryConfig::default_config_file())
+                        .map_err(HegelPmError::Config)?;
+                }
+            }
+
+            // --root/--max-depth apply to this run only (after saving, so they never persist)
+            let config = config.with_overrides(roots, max_depth);
+
             // Discover subcommand: list, show, or all projects
-            let engine = DiscoveryEngine::new(config)?;
-            hegel_pm::cli::discover::run(&engine, &subcommand, json, no_cache)?;
+            let engine = engine(config, &progress)?;
+            match &mut subcommand {
+                DiscoverCommand::Show {
+                    project_name: Some(name),
+                }
+                | DiscoverCommand::Phases {
+                    project: Some(name),
+                } => *name = resolve_project_name(&engine, name, exact)?,
+                _ => {}
+            }
+            let filter = ListFilter {
+                include_archived,
+                status,
+            };
+            hegel_pm::cli::discover::run(&engine, &subcommand, json, no_cache, &filter)?;
         }
         Some(Command::Remove { project_name }) => {
             // Remove project from cache
-            let removed = remove_from_cache(&project_name, &config)?;
-            if removed {
+            let project_name = match project_name {
+                Some(name) => resolve(&name, &config, &progress, exact)?,
+                None => pick_project(&engine(config.clone(), &progress)?)?,
+            };
+            if remove_from_cache(&project_name, &config)? {
                 println!("✓ Removed '{}' from tracking", project_name);
             } else {
-                eprintln!("✗ Project '{}' not found in cache", project_name);
-                std::process::exit(1);
+                return Err(HegelPmError::not_found(&project_name));
             }
         }
-        Some(Command::Refresh { project_names }) => {
-            if project_names.is_empty() {
-                // Refresh all cached projects
-                match refresh_all_projects(&config) {
-                    Ok(count) => {
-                        println!("✓ Refreshed {} project(s)", count);
-                    }
-                    Err(e) => {
-                        eprintln!("✗ Failed to refresh projects: {}", e);
-                        std::process::exit(1);
-                    }
-                }
+        Some(Command::Archive { project_name }) => {
+            // Mark project archived (data kept, hidden from list/all/x)
+            let project_name = resolve(&project_name, &config, &progress, exact)?;
+            if set_archived(&project_name, true, &config)? {
+                println!("✓ Archived '{}'", project_name);
             } else {
-                // Refresh specific projects
-                let mut success_count = 0;
-                let mut failed = Vec::new();
-
-                for project_name in &project_names {
-                    match refresh_project(project_name, &config) {
-                        Ok(_) => {
-                            println!("✓ Refreshed '{}'", project_name);
-                            success_count += 1;
-                        }
-                        Err(e) => {
-                            eprintln!("✗ Failed to refresh '{}': {}", project_name, e);
-                            failed.push(project_name.clone());
-                        }
-                    }
-                }
+                return Err(HegelPmError::not_found(&project_name));
+            }
+        }
+        Some(Command::Unarchive { project_name }) => {
+            let project_name = resolve(&project_name, &config, &progress, exact)?;
+            if set_archived(&project_name, false, &config)? {
+                println!("✓ Unarchived '{}'", project_name);
+            } else {
+                return Err(HegelPmError::not_found(&project_name));
+            }
+        }
+        Some(Command::Note { mut subcommand }) => {
+            // Set or unset project metadata in the cache
+            let (NoteCommand::Set { project_name, .. } | NoteCommand::Unset { project_name, .. }) =
+                &mut subcommand;
+            *project_name = resolve(project_name, &config, &progress, exact)?;
+            hegel_pm::cli::note::run(&config, &subcommand)?;
+        }
+        Some(Command::Refresh {
+            project_names,
+            pick,
+            force,
+            dry_run,
+            diff,
+        }) => {
+            let mut config = config;
+            if force {
+                config.refresh_debounce_secs = None;
+            }
 
-                if !failed.is_empty() {
-                    eprintln!("\nFailed to refresh {} project(s)", failed.len());
-                    std::process::exit(1);
-                }
+            let project_names = if pick {
+                vec![pick_project(&engine(config.clone(), &progress)?)?]
+            } else {
+                project_names
+                    .iter()
+                    .map(|name| resolve(name, &config, &progress, exact))
+                    .collect::<Result<_, _>>()?
+            };
 
-                if success_count > 0 {
-                    println!("\n✓ Successfully refreshed {} project(s)", success_count);
-                }
-            }
+            let mode = if dry_run {
+                RefreshMode::DryRun
+            } else if diff {
+                RefreshMode::Diff
+            } else {
+                RefreshMode::Apply
+            };
+            hegel_pm::cli::refresh::run(&config, &project_names, mode, progress.as_ref())?;
         }
-        Some(Command::X { args: hegel_args }) => {
+        Some(Command::Report {
+            project_name,
+            format,
+            output,
+        }) => {
+            // Render project report to stdout or file
+            let engine = engine(config, &progress)?;
+            let project_name = resolve_project_name(&engine, &project_name, exact)?;
+            hegel_pm::cli::report::run(&engine, &project_name, format, output.as_deref())?;
+        }
+        Some(Command::Diff {
+            project_name,
+            since,
+            json,
+        }) => {
+            // Compare current metrics with a recorded snapshot
+            let store = snapshot_store(&config)?;
+            let engine = engine(config, &progress)?;
+            let project_name = resolve_project_name(&engine, &project_name, exact)?;
+            hegel_pm::cli::diff::run(&engine, &store, &project_name, since.as_deref(), json)?;
+        }
+        Some(Command::Snapshot { list, json }) => {
+            // Record or list metrics snapshots (~/.config/hegel-pm/snapshots, or snapshots.sqlite)
+            let store = snapshot_store(&config)?;
+            let engine = engine(config, &progress)?;
+            hegel_pm::cli::snapshot::run(&engine, &store, list, json)?;
+        }
+        Some(Command::Digest {
+            period,
+            format,
+            output,
+        }) => {
+            // Summarize recorded snapshots over the period
+            hegel_pm::cli::digest::run(
+                &config,
+                &snapshot_store(&config)?,
+                period,
+                format,
+                output.as_deref(),
+            )?;
+        }
+        Some(Command::Alerts { json }) => {
+            // Stuck phases and corrupted state across projects
+            let engine = engine(config, &progress)?;
+            hegel_pm::cli::alerts::run(&engine, json)?;
+        }
+        Some(Command::Tail {
+            project_name,
+            filters,
+            lines,
+        }) => {
+            // Follow hooks.jsonl/states.jsonl until interrupted
+            let engine = engine(config, &progress)?;
+            let project_name = resolve_project_name(&engine, &project_name, exact)?;
+            hegel_pm::cli::tail::run(&engine, &project_name, &filters, lines)?;
+        }
+        Some(Command::Open {
+            project_name,
+            terminal,
+            browser,
+            ..
+        }) => {
+            // Launch an editor, shell, or browser for the project and wait for it
+            let engine = engine(config, &progress)?;
+            let project_name = match project_name {
+                Some(name) => resolve_project_name(&engine, &name, exact)?,
+                None => pick_project(&engine)?,
+            };
+            let target = if terminal {
+                OpenTarget::Terminal
+            } else if browser {
+                OpenTarget::Browser
+            } else {
+                OpenTarget::Editor
+            };
+            hegel_pm::cli::open::run(&engine, &project_name, target)?;
+        }
+        Some(Command::X {
+            include_archived,
+            args: hegel_args,
+        }) => {
             // Run hegel command across all projects
-            let engine = DiscoveryEngine::new(config)?;
-            hegel_pm::cli::hegel::run(&engine, &hegel_args)?;
+            let engine = engine(config, &progress)?;
+            hegel_pm::cli::hegel::run(&engine, &hegel_args, include_archived)?;
         }
         None => {
             // No command specified - show help
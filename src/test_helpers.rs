@@ -1,6 +1,13 @@
+use crate::discovery::{DiscoveredProject, GitInfo, ProjectStatistics};
+use serde_json::{json, Value};
 use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
 use tempfile::TempDir;
 
+/// Fixed timestamp used by fixtures (2023-11-14 22:13:20 UTC)
+pub const FIXTURE_TIME_SECS: u64 = 1_700_000_000;
+
 /// Create a test workspace with multiple projects for integration testing
 pub fn create_test_workspace() -> TempDir {
     let temp = TempDir::new().unwrap();
@@ -33,6 +40,120 @@ pub fn create_test_workspace() -> TempDir {
     temp
 }
 
+/// Create fixture projects with fixed paths and timestamps for snapshot tests
+///
+/// Paths live under `/home/dev` and don't exist on disk, so `.hegel` sizes render as 0 B.
+pub fn fixture_projects() -> Vec<DiscoveredProject> {
+    let at = |secs: u64| SystemTime::UNIX_EPOCH + Duration::from_secs(secs);
+
+    // alpha: active workflow, dirty git checkout ahead of upstream
+    let workflow_state = serde_json::from_value(serde_json::json!({
+        "current_node": "code",
+        "mode": "discovery",
+        "history": ["spec", "plan", "code"]
+    }))
+    .unwrap();
+    let mut alpha = DiscoveredProject::new(
+        "alpha".to_string(),
+        PathBuf::from("/home/dev/Code/alpha"),
+        PathBuf::from("/home/dev/Code/alpha/.hegel"),
+        Some(workflow_state),
+        at(FIXTURE_TIME_SECS),
+        None,
+    );
+    alpha.git_info = Some(GitInfo {
+        branch: Some("main".to_string()),
+        dirty: true,
+        ahead: Some(2),
+        behind: Some(0),
+        head: None,
+        root: None,
+    });
+
+    // beta-service: no workflow state, not a git repository
+    let beta = DiscoveredProject::new(
+        "beta-service".to_string(),
+        PathBuf::from("/home/dev/Code/work/beta-service"),
+        PathBuf::from("/home/dev/Code/work/beta-service/.hegel"),
+        None,
+        at(FIXTURE_TIME_SECS - 100_000),
+        None,
+    );
+
+    // gamma: outside the home directory, corrupted state
+    let gamma = DiscoveredProject::new(
+        "gamma".to_string(),
+        PathBuf::from("/srv/gamma"),
+        PathBuf::from("/srv/gamma/.hegel"),
+        None,
+        at(FIXTURE_TIME_SECS - 1_000_000),
+        Some("Failed to load state".to_string()),
+    );
+
+    vec![alpha, beta, gamma]
+}
+
+/// Statistics with the given top-level fields (`phase_metrics`, `state_transitions`, ...)
+///
+/// Built through serde from `ProjectStatistics::default()`, like the workflow state above, so
+/// fixtures only spell out the fields they care about. Object fields (`hook_metrics`,
+/// `token_metrics`) are merged into the defaults rather than replacing them.
+pub fn stats_with(fields: Value) -> ProjectStatistics {
+    let mut stats = serde_json::to_value(ProjectStatistics::default()).unwrap();
+    for (key, value) in fields.as_object().unwrap() {
+        match (stats[key].as_object_mut(), value.as_object()) {
+            (Some(defaults), Some(values)) => defaults.extend(values.clone()),
+            _ => stats[key] = value.clone(),
+        }
+    }
+    serde_json::from_value(stats).unwrap()
+}
+
+/// A bash command hook event (hegel-cli `BashCommand` JSON)
+pub fn bash_json(command: &str, timestamp: Option<&str>) -> Value {
+    json!({"command": command, "timestamp": timestamp, "stdout": null, "stderr": null})
+}
+
+/// A file modification hook event (hegel-cli `FileModification` JSON)
+pub fn edit_json(file_path: &str, timestamp: Option<&str>) -> Value {
+    json!({"file_path": file_path, "tool": "Edit", "timestamp": timestamp})
+}
+
+/// A phase (hegel-cli `PhaseMetrics` JSON) with `input_tokens` and no events
+///
+/// Completed phases get a duration from their start and end times. Add `bash_commands`,
+/// `file_modifications` or `git_commits` by indexing into the returned value.
+pub fn phase_json(name: &str, start: &str, end: Option<&str>, input_tokens: u64) -> Value {
+    let mut token_metrics =
+        serde_json::to_value(ProjectStatistics::default().token_metrics).unwrap();
+    token_metrics["total_input_tokens"] = json!(input_tokens);
+    let parse = |s: &str| chrono::DateTime::parse_from_rfc3339(s).unwrap();
+    let duration_seconds = end.map_or(0, |end| (parse(end) - parse(start)).num_seconds());
+
+    json!({
+        "phase_name": name,
+        "start_time": start,
+        "end_time": end,
+        "duration_seconds": duration_seconds,
+        "token_metrics": token_metrics,
+        "bash_commands": [],
+        "file_modifications": [],
+        "git_commits": [],
+    })
+}
+
+/// A state transition (hegel-cli `StateTransitionEvent` JSON) into `mode`
+pub fn transition_json(timestamp: &str, to_node: &str, mode: &str) -> Value {
+    json!({
+        "timestamp": timestamp,
+        "workflow_id": null,
+        "from_node": "start",
+        "to_node": to_node,
+        "phase": to_node,
+        "mode": mode,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -58,4 +179,14 @@ mod tests {
         assert!(root.join("no_hegel").exists());
         assert!(!root.join("no_hegel/.hegel").exists());
     }
+
+    #[test]
+    fn test_fixture_projects() {
+        let projects = fixture_projects();
+        assert_eq!(projects.len(), 3);
+        assert!(projects[0].has_state());
+        assert!(projects[2].has_error());
+        // Sorted by recency, like discovery results
+        assert!(projects.windows(2).all(|w| w[0] <= w[1]));
+    }
 }